@@ -1,6 +1,28 @@
 export const BASE_PATH: string = (window as any).__SPACEBOT_BASE_PATH || "";
 const API_BASE = BASE_PATH + "/api";
 
+const EVENTS_TOKEN_KEY = "spacebot:events_token";
+
+/**
+ * The `api.events_token` to open the event stream with, if any. Opening the
+ * dashboard with `?token=...` once stores it for later visits. EventSource
+ * can't send headers, so it goes in the stream's query string.
+ */
+function eventsToken(): string | null {
+	const fromPage = new URLSearchParams(window.location.search).get("token");
+	if (fromPage) {
+		localStorage.setItem(EVENTS_TOKEN_KEY, fromPage);
+		return fromPage;
+	}
+	return localStorage.getItem(EVENTS_TOKEN_KEY);
+}
+
+function eventsUrl(): string {
+	const token = eventsToken();
+	const query = token ? `&token=${encodeURIComponent(token)}` : "";
+	return `${API_BASE}/events?replay=0${query}`;
+}
+
 export interface StatusResponse {
 	status: string;
 	version: string;
@@ -1367,5 +1389,6 @@ export const api = {
 	webChatHistory: (agentId: string, sessionId: string, limit = 100) =>
 		fetch(`${API_BASE}/webchat/history?agent_id=${encodeURIComponent(agentId)}&session_id=${encodeURIComponent(sessionId)}&limit=${limit}`),
 
-	eventsUrl: `${API_BASE}/events`,
+	// The UI loads its state over REST, so it skips the recent-event replay.
+	eventsUrl: eventsUrl(),
 };
@@ -69,13 +69,13 @@ export function useEventSource(url: string, options: UseEventSourceOptions) {
 			});
 		}
 
-		// Handle the lagged event from the server
-		source.addEventListener("lagged", (event: MessageEvent) => {
+		// Handle the gap event the server sends when this stream skipped events
+		source.addEventListener("gap", (event: MessageEvent) => {
 			try {
 				const data = JSON.parse(event.data);
-				console.warn(`SSE lagged, skipped ${data.skipped} events`);
+				console.warn(`SSE gap, skipped ${data.skipped} events`);
 			} catch {
-				console.warn("SSE lagged, skipped events");
+				console.warn("SSE gap, skipped events");
 			}
 			// Trigger re-sync since we missed events
 			onReconnectRef.current?.();
@@ -0,0 +1,10 @@
+-- One-shot reminders scheduled by a channel for itself.
+CREATE TABLE IF NOT EXISTS reminders (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL,
+    text TEXT NOT NULL,
+    due_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_reminders_channel ON reminders(channel_id, due_at);
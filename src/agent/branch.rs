@@ -91,7 +91,8 @@ impl Branch {
         let routing = self.deps.runtime_config.routing.load();
         let model_name = routing.resolve(ProcessType::Branch, None).to_string();
         let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
-            .with_routing((**routing).clone());
+            .with_routing((**routing).clone())
+            .with_agent(self.deps.agent_id.clone());
 
         let agent = AgentBuilder::new(model)
             .preamble(&self.system_prompt)
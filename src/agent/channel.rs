@@ -121,9 +121,61 @@ pub struct Channel {
     /// Branch IDs for silent memory persistence branches (results not injected into history).
     memory_persistence_branches: HashSet<BranchId>,
     /// Buffer for coalescing rapid-fire messages.
-    coalesce_buffer: Vec<InboundMessage>,
-    /// Deadline for flushing the coalesce buffer.
-    coalesce_deadline: Option<tokio::time::Instant>,
+    coalesce: CoalesceBuffer,
+}
+
+/// Inbound messages held back so a burst can be answered in one turn.
+///
+/// The first message opens a debounce window. Once the burst reaches
+/// `min_messages` the window is kept but never runs past `max_wait_ms` from
+/// the first message. When the deadline passes, everything buffered is
+/// handled as a single turn.
+#[derive(Debug, Default)]
+struct CoalesceBuffer {
+    messages: Vec<InboundMessage>,
+    deadline: Option<tokio::time::Instant>,
+}
+
+impl CoalesceBuffer {
+    /// Buffer a message and move the flush deadline accordingly.
+    fn push(
+        &mut self,
+        message: InboundMessage,
+        config: &crate::config::CoalesceConfig,
+        now: tokio::time::Instant,
+    ) {
+        self.messages.push(message);
+        let first_message = &self.messages[0];
+        let elapsed_since_first = chrono::Utc::now().signed_duration_since(first_message.timestamp);
+        let elapsed_millis = elapsed_since_first.num_milliseconds().max(0) as u64;
+        let debounce = std::time::Duration::from_millis(config.debounce_ms);
+
+        // If we have enough messages to trigger coalescing (min_messages threshold)
+        if self.messages.len() >= config.min_messages {
+            // Cap at max_wait from the first message
+            let remaining_wait_ms = config.max_wait_ms.saturating_sub(elapsed_millis);
+            let max_deadline = now + std::time::Duration::from_millis(remaining_wait_ms);
+
+            // If no deadline set yet, use debounce window
+            // Otherwise, keep existing deadline (don't extend past max_wait)
+            let deadline = self.deadline.unwrap_or(now + debounce);
+            self.deadline = Some(deadline.min(max_deadline));
+        } else {
+            // Not enough messages yet - set a short debounce window
+            self.deadline = Some(now + debounce);
+        }
+    }
+
+    /// When the buffered messages should be flushed, if any are buffered.
+    fn deadline(&self) -> Option<tokio::time::Instant> {
+        self.deadline
+    }
+
+    /// Take every buffered message, clearing the deadline.
+    fn take(&mut self) -> Vec<InboundMessage> {
+        self.deadline = None;
+        std::mem::take(&mut self.messages)
+    }
 }
 
 impl Channel {
@@ -197,8 +249,7 @@ impl Channel {
             compactor,
             message_count: 0,
             memory_persistence_branches: HashSet::new(),
-            coalesce_buffer: Vec::new(),
-            coalesce_deadline: None,
+            coalesce: CoalesceBuffer::default(),
         };
 
         (channel, message_tx)
@@ -211,7 +262,8 @@ impl Channel {
         loop {
             // Compute sleep duration based on coalesce deadline
             let sleep_duration = self
-                .coalesce_deadline
+                .coalesce
+                .deadline()
                 .map(|deadline| {
                     let now = tokio::time::Instant::now();
                     if deadline > now {
@@ -226,8 +278,8 @@ impl Channel {
                 Some(message) = self.message_rx.recv() => {
                     let config = self.deps.runtime_config.coalesce.load();
                     if self.should_coalesce(&message, &config) {
-                        self.coalesce_buffer.push(message);
-                        self.update_coalesce_deadline(&config).await;
+                        self.coalesce
+                            .push(message, &config, tokio::time::Instant::now());
                     } else {
                         // Flush any pending buffer before handling this message
                         if let Err(error) = self.flush_coalesce_buffer().await {
@@ -247,7 +299,7 @@ impl Channel {
                         tracing::error!(%error, channel_id = %self.id, "error handling event");
                     }
                 }
-                _ = tokio::time::sleep(sleep_duration), if self.coalesce_deadline.is_some() => {
+                _ = tokio::time::sleep(sleep_duration), if self.coalesce.deadline().is_some() => {
                     // Deadline reached - flush the buffer
                     if let Err(error) = self.flush_coalesce_buffer().await {
                         tracing::error!(%error, channel_id = %self.id, "error flushing coalesce buffer on deadline");
@@ -302,54 +354,16 @@ impl Channel {
         }
     }
 
-    /// Update the coalesce deadline based on buffer size and config.
-    async fn update_coalesce_deadline(&mut self, config: &crate::config::CoalesceConfig) {
-        let now = tokio::time::Instant::now();
-
-        if let Some(first_message) = self.coalesce_buffer.first() {
-            let elapsed_since_first =
-                chrono::Utc::now().signed_duration_since(first_message.timestamp);
-            let elapsed_millis = elapsed_since_first.num_milliseconds().max(0) as u64;
-
-            let max_wait_ms = config.max_wait_ms;
-            let debounce_ms = config.debounce_ms;
-
-            // If we have enough messages to trigger coalescing (min_messages threshold)
-            if self.coalesce_buffer.len() >= config.min_messages {
-                // Cap at max_wait from the first message
-                let remaining_wait_ms = max_wait_ms.saturating_sub(elapsed_millis);
-                let max_deadline = now + std::time::Duration::from_millis(remaining_wait_ms);
-
-                // If no deadline set yet, use debounce window
-                // Otherwise, keep existing deadline (don't extend past max_wait)
-                if self.coalesce_deadline.is_none() {
-                    let new_deadline = now + std::time::Duration::from_millis(debounce_ms);
-                    self.coalesce_deadline = Some(new_deadline.min(max_deadline));
-                } else {
-                    // Already have a deadline, cap it at max_wait
-                    self.coalesce_deadline = self.coalesce_deadline.map(|d| d.min(max_deadline));
-                }
-            } else {
-                // Not enough messages yet - set a short debounce window
-                let new_deadline = now + std::time::Duration::from_millis(debounce_ms);
-                self.coalesce_deadline = Some(new_deadline);
-            }
-        }
-    }
-
     /// Flush the coalesce buffer by processing all buffered messages.
     ///
     /// If there's only one message, process it normally.
     /// If there are multiple messages, batch them into a single turn.
     async fn flush_coalesce_buffer(&mut self) -> Result<()> {
-        if self.coalesce_buffer.is_empty() {
+        let messages = self.coalesce.take();
+        if messages.is_empty() {
             return Ok(());
         }
 
-        self.coalesce_deadline = None;
-
-        let messages: Vec<InboundMessage> = std::mem::take(&mut self.coalesce_buffer);
-
         if messages.len() == 1 {
             // Single message - process normally
             let message = messages.into_iter().next().unwrap();
@@ -560,9 +574,15 @@ impl Channel {
 
         let browser_enabled = rc.browser_config.load().enabled;
         let web_search_enabled = rc.brave_search_key.load().is_some();
+        let web_fetch_enabled = rc.web_fetch_config.load().enabled;
         let opencode_enabled = rc.opencode.load().enabled;
         let worker_capabilities = prompt_engine
-            .render_worker_capabilities(browser_enabled, web_search_enabled, opencode_enabled)
+            .render_worker_capabilities(
+                browser_enabled,
+                web_search_enabled,
+                web_fetch_enabled,
+                opencode_enabled,
+            )
             .expect("failed to render worker capabilities");
 
         let status_text = {
@@ -754,9 +774,15 @@ impl Channel {
 
         let browser_enabled = rc.browser_config.load().enabled;
         let web_search_enabled = rc.brave_search_key.load().is_some();
+        let web_fetch_enabled = rc.web_fetch_config.load().enabled;
         let opencode_enabled = rc.opencode.load().enabled;
         let worker_capabilities = prompt_engine
-            .render_worker_capabilities(browser_enabled, web_search_enabled, opencode_enabled)
+            .render_worker_capabilities(
+                browser_enabled,
+                web_search_enabled,
+                web_fetch_enabled,
+                opencode_enabled,
+            )
             .expect("failed to render worker capabilities");
 
         let status_text = {
@@ -820,7 +846,8 @@ impl Channel {
         let max_turns = **rc.max_turns.load();
         let model_name = routing.resolve(ProcessType::Channel, None);
         let model = SpacebotModel::make(&self.deps.llm_manager, model_name)
-            .with_routing((**routing).clone());
+            .with_routing((**routing).clone())
+            .with_agent(self.deps.agent_id.clone());
 
         let agent = AgentBuilder::new(model)
             .preamble(system_prompt)
@@ -1034,6 +1061,16 @@ impl Channel {
 
                 tracing::info!(worker_id = %worker_id, "worker completed");
             }
+            ProcessEvent::ReminderDue {
+                reminder_id, text, ..
+            } => {
+                let mut history = self.state.history.write().await;
+                let reminder_message = format!("[Reminder due]: {text}");
+                history.push(rig::message::Message::from(reminder_message));
+                should_retrigger = true;
+
+                tracing::info!(reminder_id = %reminder_id, "reminder incorporated");
+            }
             _ => {}
         }
 
@@ -1638,6 +1675,10 @@ fn event_is_for_channel(event: &ProcessEvent, channel_id: &ChannelId) -> bool {
             channel_id: event_channel,
             ..
         } => event_channel.as_ref() == Some(channel_id),
+        ProcessEvent::ReminderDue {
+            channel_id: event_channel,
+            ..
+        } => event_channel == channel_id,
         // Status block updates, tool events, etc. — match on agent_id which
         // is already filtered by the event bus subscription. Let them through.
         _ => true,
@@ -1835,3 +1876,60 @@ async fn download_text_attachment(
         attachment.filename, attachment.mime_type, truncated
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn message(text: &str) -> InboundMessage {
+        InboundMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            source: "discord".into(),
+            conversation_id: "discord:1:2".into(),
+            sender_id: "user".into(),
+            agent_id: None,
+            content: crate::MessageContent::Text(text.into()),
+            timestamp: chrono::Utc::now(),
+            metadata: HashMap::new(),
+            formatted_author: None,
+        }
+    }
+
+    #[test]
+    fn burst_within_window_is_one_turn() {
+        let config = crate::config::CoalesceConfig {
+            debounce_ms: 100,
+            max_wait_ms: 1000,
+            min_messages: 2,
+            ..Default::default()
+        };
+        let start = tokio::time::Instant::now();
+        let mut buffer = CoalesceBuffer::default();
+
+        for (offset, text) in [(0, "one"), (10, "two"), (20, "three")] {
+            buffer.push(
+                message(text),
+                &config,
+                start + Duration::from_millis(offset),
+            );
+        }
+        let deadline = buffer.deadline().expect("burst should be scheduled");
+        assert_eq!(deadline, start + Duration::from_millis(100));
+
+        let first_turn = buffer.take();
+        assert_eq!(first_turn.len(), 3);
+        assert!(buffer.deadline().is_none());
+
+        // A message after the flush starts a new turn of its own.
+        let later = deadline + Duration::from_millis(400);
+        buffer.push(message("four"), &config, later);
+        assert_eq!(buffer.deadline(), Some(later + Duration::from_millis(100)));
+        let second_turn = buffer.take();
+        assert_eq!(second_turn.len(), 1);
+        assert!(matches!(
+            &second_turn[0].content,
+            crate::MessageContent::Text(text) if text == "four"
+        ));
+    }
+}
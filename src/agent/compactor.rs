@@ -199,8 +199,9 @@ async fn run_compaction(
     // 3. Run the compaction LLM to produce summary + extracted memories
     let routing = deps.runtime_config.routing.load();
     let model_name = routing.resolve(ProcessType::Worker, None).to_string();
-    let model =
-        SpacebotModel::make(&deps.llm_manager, &model_name).with_routing((**routing).clone());
+    let model = SpacebotModel::make(&deps.llm_manager, &model_name)
+        .with_routing((**routing).clone())
+        .with_agent(deps.agent_id.clone());
 
     // Give the compaction worker memory_save so it can directly persist memories
     let tool_server: ToolServerHandle = ToolServer::new()
@@ -263,7 +263,8 @@ impl CortexChatSession {
         let routing = self.deps.runtime_config.routing.load();
         let model_name = routing.resolve(ProcessType::Branch, None).to_string();
         let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
-            .with_routing((**routing).clone());
+            .with_routing((**routing).clone())
+            .with_agent(self.deps.agent_id.clone());
 
         let agent = AgentBuilder::new(model)
             .preamble(&system_prompt)
@@ -322,9 +323,15 @@ impl CortexChatSession {
 
         let browser_enabled = runtime_config.browser_config.load().enabled;
         let web_search_enabled = runtime_config.brave_search_key.load().is_some();
+        let web_fetch_enabled = runtime_config.web_fetch_config.load().enabled;
         let opencode_enabled = runtime_config.opencode.load().enabled;
         let worker_capabilities = prompt_engine
-            .render_worker_capabilities(browser_enabled, web_search_enabled, opencode_enabled)
+            .render_worker_capabilities(
+                browser_enabled,
+                web_search_enabled,
+                web_fetch_enabled,
+                opencode_enabled,
+            )
             .expect("failed to render worker capabilities");
 
         // Load channel transcript if a channel context is active
@@ -190,16 +190,21 @@ impl Worker {
             self.channel_id.clone(),
             self.deps.event_tx.clone(),
             self.browser_config.clone(),
+            (**self.deps.runtime_config.shell_config.load()).clone(),
+            (**self.deps.runtime_config.shell_rate_limiter.load()).clone(),
             self.screenshot_dir.clone(),
             self.brave_search_key.clone(),
+            (**self.deps.runtime_config.web_fetch_config.load()).clone(),
             self.deps.runtime_config.workspace_dir.clone(),
             self.deps.runtime_config.instance_dir.clone(),
-        );
+        )
+        .map_err(anyhow::Error::from)?;
 
         let routing = self.deps.runtime_config.routing.load();
         let model_name = routing.resolve(ProcessType::Worker, None).to_string();
         let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
-            .with_routing((**routing).clone());
+            .with_routing((**routing).clone())
+            .with_agent(self.deps.agent_id.clone());
 
         let agent = AgentBuilder::new(model)
             .preamble(&self.system_prompt)
@@ -2,7 +2,7 @@
 //!
 //! Serves the embedded frontend assets and provides a JSON API for
 //! managing agents, viewing status, and interacting with the system.
-//! Includes an SSE endpoint for realtime event streaming.
+//! Includes SSE and WebSocket endpoints for realtime event streaming.
 
 mod agents;
 mod bindings;
@@ -10,17 +10,25 @@ mod channels;
 mod config;
 mod cortex;
 mod cron;
+mod events;
 mod ingest;
 mod memories;
 mod messaging;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod models;
+mod prompts;
 mod providers;
+mod security;
 mod server;
 mod settings;
 mod skills;
 mod state;
 mod system;
+mod usage;
 mod webchat;
+mod websocket;
 
+pub use events::{Backlog, EventSequencer, ForwarderPool, SequencedEvent, Severity};
 pub use server::start_http_server;
-pub use state::{AgentInfo, ApiEvent, ApiState};
+pub use state::{AgentInfo, ApiEvent, ApiState, ChannelLimits};
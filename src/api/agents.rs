@@ -4,7 +4,7 @@ use crate::agent::cortex::CortexLogger;
 use crate::conversation::channels::ChannelStore;
 
 use axum::Json;
-use axum::extract::{Query, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use sqlx::Row as _;
@@ -111,6 +111,11 @@ pub(super) struct AgentOverviewQuery {
     agent_id: String,
 }
 
+#[derive(Serialize)]
+pub(super) struct AgentToolsResponse {
+    tools: Vec<crate::tools::ToolStatus>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct CreateAgentRequest {
     agent_id: String,
@@ -129,6 +134,51 @@ pub(super) async fn list_agents(State(state): State<Arc<ApiState>>) -> Json<Agen
     })
 }
 
+#[derive(Serialize)]
+pub(super) struct AgentStatusResponse {
+    agents: Vec<AgentStatus>,
+}
+
+#[derive(Serialize)]
+struct AgentStatus {
+    agent_id: String,
+    /// Whether the agent's event stream is still open.
+    connected: bool,
+    /// None until the agent sends its first event.
+    last_event_at: Option<String>,
+    /// Seconds since the agent's event stream was registered. None for an
+    /// agent that never registered one.
+    uptime_seconds: Option<u64>,
+    /// Events SSE clients never saw because the agent's forwarder fell
+    /// behind it.
+    lagged_events: u64,
+}
+
+/// List running agents and whether their event streams are alive.
+pub(super) async fn agent_status(State(state): State<Arc<ApiState>>) -> Json<AgentStatusResponse> {
+    let activity = state.agent_activity.load();
+    let mut agents: Vec<AgentStatus> = state
+        .agent_pools
+        .load()
+        .keys()
+        .map(|agent_id| {
+            let activity = activity.get(agent_id);
+            AgentStatus {
+                agent_id: agent_id.clone(),
+                connected: activity.is_some_and(|activity| activity.connected()),
+                last_event_at: activity
+                    .and_then(|activity| activity.last_event_at())
+                    .map(|at| at.to_rfc3339()),
+                uptime_seconds: activity.map(|activity| activity.uptime().as_secs()),
+                lagged_events: activity.map_or(0, |activity| activity.lagged_events()),
+            }
+        })
+        .collect();
+    agents.sort_by(|left, right| left.agent_id.cmp(&right.agent_id));
+
+    Json(AgentStatusResponse { agents })
+}
+
 /// Create a new agent and initialize it live (directories, databases, memory, identity, cron, cortex).
 pub(super) async fn create_agent(
     State(state): State<Arc<ApiState>>,
@@ -210,6 +260,8 @@ pub(super) async fn create_agent(
         ingestion: None,
         cortex: None,
         browser: None,
+        shell: None,
+        web_fetch: None,
         brave_search_key: None,
         cron: Vec::new(),
     };
@@ -364,11 +416,18 @@ pub(super) async fn create_agent(
     };
     let scheduler = std::sync::Arc::new(crate::cron::Scheduler::new(cron_context));
     runtime_config.set_cron(cron_store.clone(), scheduler.clone());
+    runtime_config.set_reminders(std::sync::Arc::new(crate::cron::ReminderScheduler::new(
+        arc_agent_id.clone(),
+        std::sync::Arc::new(crate::cron::ReminderStore::new(db.sqlite.clone())),
+        event_tx.clone(),
+    )));
 
     let cron_tool = crate::tools::CronTool::new(cron_store.clone(), scheduler.clone());
 
     let browser_config = (**runtime_config.browser_config.load()).clone();
+    let shell_config = (**runtime_config.shell_config.load()).clone();
     let brave_search_key = (**runtime_config.brave_search_key.load()).clone();
+    let web_fetch_config = (**runtime_config.web_fetch_config.load()).clone();
     let conversation_logger =
         crate::conversation::history::ConversationLogger::new(db.sqlite.clone());
     let channel_store = crate::conversation::ChannelStore::new(db.sqlite.clone());
@@ -377,11 +436,17 @@ pub(super) async fn create_agent(
         conversation_logger,
         channel_store,
         browser_config,
+        shell_config,
         agent_config.screenshot_dir(),
         brave_search_key,
+        web_fetch_config,
         runtime_config.workspace_dir.clone(),
         runtime_config.instance_dir.clone(),
-    );
+    )
+    .map_err(|error| {
+        tracing::error!(%error, "failed to create cortex chat tools");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
     let cortex_store = crate::agent::cortex_chat::CortexChatStore::new(db.sqlite.clone());
     let cortex_session = crate::agent::cortex_chat::CortexChatSession::new(
         deps.clone(),
@@ -557,6 +622,10 @@ pub(super) async fn delete_agent(
         pools.remove(&agent_id);
         state.agent_pools.store(std::sync::Arc::new(pools));
 
+        let mut activity = (**state.agent_activity.load()).clone();
+        activity.remove(&agent_id);
+        state.agent_activity.store(std::sync::Arc::new(activity));
+
         let mut searches = (**state.memory_searches.load()).clone();
         searches.remove(&agent_id);
         state.memory_searches.store(std::sync::Arc::new(searches));
@@ -878,6 +947,59 @@ pub(super) async fn get_agent_profile(
     Ok(Json(AgentProfileResponse { profile }))
 }
 
+/// List the tools an agent's workers get, flagging any whose required
+/// binaries are missing from PATH as degraded.
+pub(super) async fn list_agent_tools(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<AgentToolsResponse>, StatusCode> {
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(&agent_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(AgentToolsResponse {
+        tools: crate::tools::worker_tool_statuses(runtime_config),
+    }))
+}
+
+#[derive(Serialize)]
+pub(super) struct AgentUsageResponse {
+    agent_id: String,
+    /// Summed over every model.
+    total: crate::llm::TokenUsage,
+    models: HashMap<String, crate::llm::TokenUsage>,
+}
+
+/// Get the LLM tokens an agent has used since startup, per model.
+pub(super) async fn agent_usage(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<AgentUsageResponse>, StatusCode> {
+    if !state.runtime_configs.load().contains_key(&agent_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let llm_manager = state.llm_manager.read().await.clone().ok_or_else(|| {
+        tracing::error!("LLM manager not available");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let models = llm_manager
+        .usage_snapshot()
+        .remove(&agent_id)
+        .unwrap_or_default();
+    let mut total = crate::llm::TokenUsage::default();
+    for usage in models.values() {
+        total.add(usage);
+    }
+
+    Ok(Json(AgentUsageResponse {
+        agent_id,
+        total,
+        models,
+    }))
+}
+
 /// Get identity files (SOUL.md, IDENTITY.md, USER.md) for an agent.
 pub(super) async fn get_identity(
     State(state): State<Arc<ApiState>>,
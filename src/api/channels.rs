@@ -1,11 +1,11 @@
-use super::state::ApiState;
+use super::state::{ApiEvent, ApiState};
 
 use crate::conversation::channels::ChannelStore;
-use crate::conversation::history::ProcessRunLogger;
+use crate::conversation::history::{ConversationLogger, ProcessRunLogger};
 
 use axum::Json;
-use axum::extract::{Query, State};
-use axum::http::StatusCode;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -44,6 +44,56 @@ fn default_message_limit() -> i64 {
     20
 }
 
+/// Most messages one history page may hold.
+const MAX_HISTORY_LIMIT: i64 = 200;
+
+#[derive(Deserialize)]
+pub(super) struct HistoryQuery {
+    #[serde(default = "default_history_limit")]
+    limit: i64,
+    /// A previous page's `next_cursor`; the page ends just before it.
+    before: Option<String>,
+}
+
+fn default_history_limit() -> i64 {
+    50
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct HistoryPage {
+    /// Newest first.
+    messages: Vec<HistoryMessage>,
+    /// Pass as `before` to fetch the page preceding this one. None when
+    /// there are no older messages.
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct HistoryMessage {
+    id: String,
+    sender_id: Option<String>,
+    text: String,
+    timestamp: String,
+    /// `inbound` for messages from users, `outbound` for the agent's replies.
+    direction: &'static str,
+}
+
+/// Longest text, in characters, the send endpoint accepts.
+const MAX_SEND_TEXT_CHARS: usize = 4000;
+
+#[derive(Deserialize)]
+pub(super) struct SendMessageRequest {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct SendMessageResponse {
+    /// The ID the message is stored under in the channel's history.
+    message_id: String,
+    channel_id: String,
+    platform: String,
+}
+
 #[derive(Deserialize)]
 pub(super) struct CancelProcessRequest {
     channel_id: String,
@@ -128,6 +178,150 @@ pub(super) async fn channel_messages(
     })
 }
 
+/// Get one page of an agent's conversation history in a channel, newest
+/// first. 404 when the agent has no registered database or has never seen
+/// the channel.
+///
+/// Pages are keyed on the oldest message they hold rather than an offset,
+/// so messages arriving while a client scrolls back don't shift later pages.
+pub(super) async fn agent_channel_messages(
+    State(state): State<Arc<ApiState>>,
+    Path((agent_id, channel_id)): Path<(String, String)>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistoryPage>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let channel = ChannelStore::new(pool.clone())
+        .get(&channel_id)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, agent_id, channel_id, "failed to look up channel");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if channel.is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let logger = ConversationLogger::new(pool.clone());
+    let limit = query.limit.clamp(1, MAX_HISTORY_LIMIT);
+
+    // One extra message says whether there is an older page.
+    let mut messages = logger
+        .load_page(&channel_id, limit + 1, query.before.as_deref())
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, agent_id, channel_id, "failed to load conversation history");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let has_more = messages.len() as i64 > limit;
+    if has_more {
+        messages.pop();
+    }
+    let next_cursor = messages
+        .last()
+        .filter(|_| has_more)
+        .map(|message| message.id.clone());
+
+    Ok(Json(HistoryPage {
+        messages: messages
+            .into_iter()
+            .map(|message| HistoryMessage {
+                direction: if message.role == "assistant" {
+                    "outbound"
+                } else {
+                    "inbound"
+                },
+                id: message.id,
+                sender_id: message.sender_id,
+                text: message.content,
+                timestamp: message.created_at.to_rfc3339(),
+            })
+            .collect(),
+        next_cursor,
+    }))
+}
+
+/// Send a message into a channel as the agent, e.g. from a dashboard or an
+/// automation. It goes out through the channel's platform adapter, is
+/// logged as one of the agent's messages and reaches SSE clients as an
+/// `outbound_message` event. Requires the admin token.
+pub(super) async fn send_channel_message(
+    State(state): State<Arc<ApiState>>,
+    Path((agent_id, channel_id)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(request): Json<SendMessageRequest>,
+) -> Result<Json<SendMessageResponse>, StatusCode> {
+    super::security::authorize_admin(&state, &headers)?;
+    send_as_agent(&state, agent_id, channel_id, request.text)
+        .await
+        .map(Json)
+}
+
+/// Send `text` into a channel as the agent, for [`send_channel_message`] and
+/// the WebSocket `send_message` action. Callers check the admin token.
+pub(super) async fn send_as_agent(
+    state: &ApiState,
+    agent_id: String,
+    channel_id: String,
+    text: String,
+) -> Result<SendMessageResponse, StatusCode> {
+    if text.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if text.chars().count() > MAX_SEND_TEXT_CHARS {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let channel = ChannelStore::new(pool.clone())
+        .get(&channel_id)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, agent_id, channel_id, "failed to look up channel");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let (adapter_name, target) =
+        crate::tools::send_message_to_another_channel::resolve_broadcast_target(&channel)
+            .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    let manager = state
+        .messaging_manager
+        .read()
+        .await
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    manager
+        .broadcast(
+            &adapter_name,
+            &target,
+            crate::OutboundResponse::Text(text.clone()),
+        )
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, agent_id, channel_id, "failed to send message to channel");
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    let message_id =
+        ConversationLogger::new(pool.clone()).log_bot_message(&channel_id.as_str().into(), &text);
+    state
+        .event_tx
+        .send(ApiEvent::OutboundMessage {
+            agent_id,
+            channel_id: channel_id.clone(),
+            text,
+        })
+        .ok();
+
+    Ok(SendMessageResponse {
+        message_id,
+        channel_id,
+        platform: adapter_name,
+    })
+}
+
 /// Get live status (active workers, branches, completed items) for all channels.
 pub(super) async fn channel_status(
     State(state): State<Arc<ApiState>>,
@@ -190,3 +384,275 @@ pub(super) async fn cancel_process(
         _ => Err(StatusCode::BAD_REQUEST),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn state_with_history() -> Arc<ApiState> {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        sqlx::query("INSERT INTO channels (id, platform) VALUES ('discord:1', 'discord')")
+            .execute(&pool)
+            .await
+            .expect("channel is inserted");
+        for (id, role, sender_id, minute) in [
+            ("m1", "user", Some("alice"), 1),
+            ("m2", "assistant", None, 2),
+            ("m3", "user", Some("alice"), 3),
+            ("m4", "assistant", None, 4),
+        ] {
+            insert_message(&pool, id, role, sender_id, minute).await;
+        }
+
+        let (provider_tx, _) = tokio::sync::mpsc::channel(1);
+        let (agent_tx, _) = tokio::sync::mpsc::channel(1);
+        let (agent_remove_tx, _) = tokio::sync::mpsc::channel(1);
+        let state = Arc::new(ApiState::new_with_provider_sender(
+            provider_tx,
+            agent_tx,
+            agent_remove_tx,
+        ));
+        state.set_agent_pools(HashMap::from([("main".to_string(), pool)]));
+        state
+    }
+
+    async fn insert_message(
+        pool: &sqlx::SqlitePool,
+        id: &str,
+        role: &str,
+        sender_id: Option<&str>,
+        minute: u32,
+    ) {
+        sqlx::query(
+            "INSERT INTO conversation_messages (id, channel_id, role, sender_id, content, created_at) \
+             VALUES (?, 'discord:1', ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(role)
+        .bind(sender_id)
+        .bind(format!("message {id}"))
+        .bind(format!("2026-01-01 12:{minute:02}:00"))
+        .execute(pool)
+        .await
+        .expect("message is inserted");
+    }
+
+    fn ids(page: &HistoryPage) -> Vec<String> {
+        page.messages
+            .iter()
+            .map(|message| message.id.clone())
+            .collect()
+    }
+
+    async fn page(
+        state: &Arc<ApiState>,
+        agent_id: &str,
+        limit: i64,
+        before: Option<&str>,
+    ) -> Result<HistoryPage, StatusCode> {
+        channel_page(state, agent_id, "discord:1", limit, before).await
+    }
+
+    async fn channel_page(
+        state: &Arc<ApiState>,
+        agent_id: &str,
+        channel_id: &str,
+        limit: i64,
+        before: Option<&str>,
+    ) -> Result<HistoryPage, StatusCode> {
+        agent_channel_messages(
+            State(state.clone()),
+            Path((agent_id.to_string(), channel_id.to_string())),
+            Query(HistoryQuery {
+                limit,
+                before: before.map(str::to_string),
+            }),
+        )
+        .await
+        .map(|Json(page)| page)
+    }
+
+    #[tokio::test]
+    async fn history_pages_back_from_the_newest_message() {
+        let state = state_with_history().await;
+
+        let newest = page(&state, "main", 2, None).await.expect("page loads");
+        assert_eq!(ids(&newest), ["m4", "m3"]);
+        assert_eq!(newest.messages[0].direction, "outbound");
+        assert_eq!(newest.messages[1].direction, "inbound");
+        assert_eq!(newest.messages[1].sender_id.as_deref(), Some("alice"));
+        assert_eq!(newest.next_cursor.as_deref(), Some("m3"));
+
+        let older = page(&state, "main", 2, newest.next_cursor.as_deref())
+            .await
+            .expect("page loads");
+        assert_eq!(ids(&older), ["m2", "m1"]);
+        assert_eq!(older.next_cursor, None);
+        let oldest = page(&state, "main", 2, Some("m1"))
+            .await
+            .expect("page loads");
+        assert!(oldest.messages.is_empty());
+
+        assert_eq!(
+            page(&state, "missing", 2, None).await.err(),
+            Some(StatusCode::NOT_FOUND)
+        );
+        assert_eq!(
+            channel_page(&state, "main", "discord:2", 2, None)
+                .await
+                .err(),
+            Some(StatusCode::NOT_FOUND)
+        );
+    }
+
+    #[tokio::test]
+    async fn messages_arriving_mid_scroll_neither_shift_nor_repeat_pages() {
+        let state = state_with_history().await;
+        let pool = state.agent_pools.load()["main"].clone();
+
+        let first = page(&state, "main", 2, None).await.expect("page loads");
+        assert_eq!(ids(&first), ["m4", "m3"]);
+
+        // New messages land while the client scrolls back, one of them in
+        // the same second as the cursor.
+        insert_message(&pool, "m5", "user", Some("bob"), 5).await;
+        insert_message(&pool, "m3b", "assistant", None, 3).await;
+        insert_message(&pool, "m6", "assistant", None, 6).await;
+
+        let mut seen = ids(&first);
+        let mut cursor = first.next_cursor;
+        while let Some(before) = cursor {
+            let older = page(&state, "main", 2, Some(&before))
+                .await
+                .expect("page loads");
+            seen.extend(ids(&older));
+            cursor = older.next_cursor;
+        }
+        assert_eq!(seen, ["m4", "m3", "m2", "m1"]);
+    }
+
+    /// Records what it's asked to broadcast instead of sending it.
+    struct RecordingAdapter {
+        sent: Arc<std::sync::Mutex<Vec<(String, String)>>>,
+    }
+
+    impl crate::messaging::Messaging for RecordingAdapter {
+        fn name(&self) -> &str {
+            "discord"
+        }
+
+        async fn start(&self) -> crate::Result<crate::messaging::traits::InboundStream> {
+            Ok(Box::pin(futures::stream::pending()))
+        }
+
+        async fn respond(
+            &self,
+            _message: &crate::InboundMessage,
+            _response: crate::OutboundResponse,
+        ) -> crate::Result<()> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            target: &str,
+            response: crate::OutboundResponse,
+        ) -> crate::Result<()> {
+            if let crate::OutboundResponse::Text(text) = response {
+                self.sent
+                    .lock()
+                    .expect("lock")
+                    .push((target.to_string(), text));
+            }
+            Ok(())
+        }
+
+        async fn health_check(&self) -> crate::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn sent_messages_go_out_through_the_adapter_and_to_sse_clients() {
+        let state = state_with_history().await;
+        let pool = state.agent_pools.load()["main"].clone();
+        sqlx::query("INSERT INTO channels (id, platform) VALUES ('discord:10:20', 'discord')")
+            .execute(&pool)
+            .await
+            .expect("channel is inserted");
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let manager = crate::messaging::MessagingManager::new();
+        manager
+            .register(RecordingAdapter { sent: sent.clone() })
+            .await;
+        state.set_messaging_manager(Arc::new(manager)).await;
+        let mut events = state.event_tx.subscribe();
+
+        let send = |channel_id: &str, text: &str, token: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(token) = token {
+                headers.insert(
+                    axum::http::header::AUTHORIZATION,
+                    format!("Bearer {token}").parse().expect("valid header"),
+                );
+            }
+            send_channel_message(
+                State(state.clone()),
+                Path(("main".to_string(), channel_id.to_string())),
+                headers,
+                Json(SendMessageRequest { text: text.into() }),
+            )
+        };
+
+        // Hidden until an admin token is configured.
+        assert_eq!(
+            send("discord:10:20", "hi", None).await.err(),
+            Some(StatusCode::NOT_FOUND)
+        );
+        state.set_admin_token(Some("secret".into()));
+        assert_eq!(
+            send("discord:10:20", "hi", Some("wrong")).await.err(),
+            Some(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            send("discord:10:20", " ", Some("secret")).await.err(),
+            Some(StatusCode::BAD_REQUEST)
+        );
+        let long = "x".repeat(MAX_SEND_TEXT_CHARS + 1);
+        assert_eq!(
+            send("discord:10:20", &long, Some("secret")).await.err(),
+            Some(StatusCode::PAYLOAD_TOO_LARGE)
+        );
+        assert_eq!(
+            send("discord:99:99", "hi", Some("secret")).await.err(),
+            Some(StatusCode::NOT_FOUND)
+        );
+
+        let Json(response) = send("discord:10:20", "deploy finished", Some("secret"))
+            .await
+            .expect("message is sent");
+        assert_eq!(response.platform, "discord");
+        assert!(!response.message_id.is_empty());
+        assert_eq!(
+            *sent.lock().expect("lock"),
+            [("20".to_string(), "deploy finished".to_string())]
+        );
+        let event = events.recv().await.expect("event is broadcast");
+        assert!(matches!(
+            event.event,
+            ApiEvent::OutboundMessage { ref channel_id, ref text, .. }
+                if channel_id == "discord:10:20" && text == "deploy finished"
+        ));
+    }
+}
@@ -387,7 +387,7 @@ pub(super) fn find_or_create_agent_table(
     Ok(agents.len() - 1)
 }
 
-fn get_agent_table_mut(
+pub(super) fn get_agent_table_mut(
     doc: &mut toml_edit::DocumentMut,
     agent_idx: usize,
 ) -> Result<&mut toml_edit::Table, StatusCode> {
@@ -397,7 +397,7 @@ fn get_agent_table_mut(
         .ok_or(StatusCode::INTERNAL_SERVER_ERROR)
 }
 
-fn get_or_create_subtable<'a>(
+pub(super) fn get_or_create_subtable<'a>(
     agent: &'a mut toml_edit::Table,
     key: &str,
 ) -> &'a mut toml_edit::Table {
@@ -0,0 +1,880 @@
+//! The aggregated event stream behind the SSE endpoint.
+//!
+//! Every event bound for SSE clients enters through one [`EventSequencer`],
+//! which stamps it with a global sequence number, so all clients see events
+//! in the same order and can tell when they missed some. The most recent
+//! events are kept so a client that reconnects with `Last-Event-ID` gets
+//! what it missed first. Agents' ProcessEvents
+//! reach it through forwarders: one task per agent by default, or a
+//! [`ForwarderPool`] of `api.event_forwarders` tasks shared by all agents.
+//! Either way an agent's events are forwarded in the order it sent them,
+//! and a forwarder stops serving an agent once its event channel closes.
+//! Forwarders also note in the agent's [`AgentActivity`] when it last sent
+//! an event, whether its channel is still open, and how many events they
+//! skipped by falling behind it.
+//!
+//! Each event's SSE payload is serialized once, as it enters the sequencer.
+//! Subscribers and the replay buffer share the sequenced event behind an
+//! `Arc`, so a large event costs the same to send to one client as to a
+//! hundred, and they all get the same bytes.
+
+use super::state::ApiEvent;
+use crate::{ProcessEvent, ProcessId};
+
+use futures::StreamExt as _;
+use futures::stream::{BoxStream, SelectAll};
+use serde::{Deserialize, Serialize};
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::{broadcast, mpsc};
+
+/// How much an event matters, so clients can subscribe to only the
+/// important ones. Ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// An API event with its position in the aggregated stream.
+#[derive(Debug, Clone)]
+pub struct SequencedEvent {
+    /// Position in the stream, counting from 1. Sent to SSE clients as the
+    /// event id.
+    pub sequence: u64,
+    /// When the sequencer received the event.
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub severity: Severity,
+    pub event: ApiEvent,
+    /// The event's SSE payload. None if the event couldn't be serialized, in
+    /// which case it isn't sent to clients.
+    pub json: Option<String>,
+}
+
+impl SequencedEvent {
+    /// Stamp `event` with its position and serialize its payload.
+    pub fn new(sequence: u64, severity: Severity, event: ApiEvent) -> Self {
+        PreparedEvent::new(severity, event).sequence(sequence)
+    }
+}
+
+/// An event serialized before it has a sequence number, so the sequencer's
+/// lock only covers numbering it.
+struct PreparedEvent {
+    severity: Severity,
+    event: ApiEvent,
+    /// The SSE payload up to the value of its trailing `id` field, with room
+    /// reserved for the number and the closing brace.
+    payload: Option<String>,
+}
+
+/// Room for the digits of any `u64` and the closing brace.
+const ID_RESERVE: usize = 21;
+
+impl PreparedEvent {
+    fn new(severity: Severity, event: ApiEvent) -> Self {
+        let payload = sse_payload(severity, &event).and_then(|payload| {
+            let fields = payload.strip_suffix('}')?;
+            let separator = if fields == "{" { "" } else { "," };
+            let mut prefix = String::with_capacity(payload.len() + 6 + ID_RESERVE);
+            prefix.push_str(fields);
+            prefix.push_str(separator);
+            prefix.push_str("\"id\":");
+            Some(prefix)
+        });
+        Self {
+            payload,
+            severity,
+            event,
+        }
+    }
+
+    /// Give the event its position by finishing the payload with it, which
+    /// fits in the room reserved for it.
+    fn sequence(self, sequence: u64) -> SequencedEvent {
+        let json = self.payload.map(|mut payload| {
+            use std::fmt::Write as _;
+            let _ = write!(payload, "{sequence}}}");
+            payload
+        });
+        if json.is_none() {
+            tracing::warn!(sequence, "failed to serialize API event");
+        }
+        SequencedEvent {
+            sequence,
+            timestamp: chrono::Utc::now(),
+            severity: self.severity,
+            event: self.event,
+            json,
+        }
+    }
+}
+
+/// SSE payload for an API event, with its severity added. The sequence
+/// number is appended as `id` once the event has one. A replayed event is sent
+/// as the event it carries, with `"replayed": true` added so it can't pass
+/// for a live one.
+fn sse_payload(severity: Severity, event: &ApiEvent) -> Option<String> {
+    let (payload, replayed) = match event {
+        ApiEvent::Replayed { event } => (event.as_ref(), true),
+        event => (event, false),
+    };
+    let mut value = serde_json::to_value(payload).ok()?;
+    let object = value.as_object_mut()?;
+    object.remove("id");
+    object.insert("severity".into(), serde_json::to_value(severity).ok()?);
+    if replayed {
+        object.insert("replayed".into(), serde_json::Value::Bool(true));
+    }
+    serde_json::to_string(&value).ok()
+}
+
+/// Events kept for replay to reconnecting clients unless configured
+/// otherwise.
+pub const DEFAULT_REPLAY_BUFFER: usize = 1000;
+
+/// Most recent events a client connecting without `Last-Event-ID` gets
+/// before the live stream, unless it asks for another number.
+pub const DEFAULT_CONNECT_REPLAY: usize = 256;
+
+/// Events the broadcast channel holds for its slowest subscriber unless
+/// configured otherwise.
+pub const DEFAULT_EVENT_CAPACITY: usize = 512;
+
+/// A forwarder skipping more than this many of an agent's events within
+/// [`LAG_WARNING_WINDOW`] is logged as a warning, at most once per window.
+const LAG_WARNING_THRESHOLD: u64 = 100;
+const LAG_WARNING_WINDOW: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// The single entry point of the aggregated event stream.
+#[derive(Debug)]
+pub struct EventSequencer {
+    tx: broadcast::Sender<Arc<SequencedEvent>>,
+    /// Held while sending, so events enter the channel and the replay
+    /// buffer in sequence order.
+    history: std::sync::Mutex<History>,
+}
+
+#[derive(Debug)]
+struct History {
+    /// The last sequence number handed out.
+    last_sequence: u64,
+    /// The most recent events, oldest first.
+    recent: VecDeque<Arc<SequencedEvent>>,
+    capacity: usize,
+}
+
+/// What a client that last saw a given event needs to catch up.
+#[derive(Debug, Default)]
+pub struct Backlog {
+    /// Buffered events after the one the client saw, oldest first.
+    pub events: Vec<Arc<SequencedEvent>>,
+    /// Events the client missed that are no longer buffered.
+    pub missed: u64,
+}
+
+impl EventSequencer {
+    /// A stream whose slowest subscriber may fall up to `capacity` events
+    /// behind. Sends never wait for subscribers: one that falls further
+    /// behind skips ahead and is told how many events it missed. A larger
+    /// capacity lets slow clients catch up, at the cost of holding that many
+    /// events in memory. Must be at least 1.
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            history: std::sync::Mutex::new(History {
+                last_sequence: 0,
+                recent: VecDeque::new(),
+                capacity: DEFAULT_REPLAY_BUFFER,
+            }),
+        }
+    }
+
+    /// Keep the last `capacity` events for replay. 0 turns replay off.
+    pub fn set_replay_buffer(&self, capacity: usize) {
+        let mut history = self.history();
+        history.capacity = capacity;
+        let excess = history.recent.len().saturating_sub(capacity);
+        history.recent.drain(..excess);
+    }
+
+    /// Stamp `event` with the next sequence number and send it to all
+    /// subscribers at its default severity. Fails, like a broadcast send,
+    /// when nobody is subscribed.
+    pub fn send(
+        &self,
+        event: ApiEvent,
+    ) -> Result<u64, broadcast::error::SendError<Arc<SequencedEvent>>> {
+        let severity = event.default_severity();
+        self.send_with_severity(event, severity)
+    }
+
+    /// Like [`send`](Self::send), for producers that know better than the
+    /// event's default how severe it is.
+    pub fn send_with_severity(
+        &self,
+        event: ApiEvent,
+        severity: Severity,
+    ) -> Result<u64, broadcast::error::SendError<Arc<SequencedEvent>>> {
+        #[cfg(feature = "metrics")]
+        record_event(&event);
+        // Serializing large events (shell output, long messages) happens
+        // here, so forwarders don't queue behind it on the lock.
+        let event = PreparedEvent::new(severity, event);
+        let mut history = self.history();
+        history.last_sequence += 1;
+        let sequence = history.last_sequence;
+        let event = Arc::new(event.sequence(sequence));
+        // Buffered even when nobody is listening, for clients that
+        // reconnect later. The evicted event is dropped after the lock is
+        // released, in case this was its last reference.
+        let mut evicted = None;
+        if history.capacity > 0 {
+            if history.recent.len() == history.capacity {
+                evicted = history.recent.pop_front();
+            }
+            history.recent.push_back(event.clone());
+        }
+        let sent = self.tx.send(event);
+        drop(history);
+        drop(evicted);
+        sent.map(|_| sequence)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<SequencedEvent>> {
+        self.tx.subscribe()
+    }
+
+    /// How many receivers are currently subscribed.
+    pub fn receiver_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// Subscribe on behalf of a client that last saw event `last_seen`: the
+    /// receiver picks up right after the returned backlog, with nothing
+    /// missed or repeated in between. An id from before a restart, which is
+    /// ahead of the current sequence, gets everything buffered.
+    pub fn subscribe_after(
+        &self,
+        last_seen: u64,
+    ) -> (Backlog, broadcast::Receiver<Arc<SequencedEvent>>) {
+        let history = self.history();
+        let last_seen = if last_seen > history.last_sequence {
+            0
+        } else {
+            last_seen
+        };
+        let events: Vec<Arc<SequencedEvent>> = history
+            .recent
+            .iter()
+            .filter(|event| event.sequence > last_seen)
+            .cloned()
+            .collect();
+        let first_available = events
+            .first()
+            .map_or(history.last_sequence + 1, |event| event.sequence);
+        let missed = first_available.saturating_sub(last_seen + 1);
+        (Backlog { events, missed }, self.tx.subscribe())
+    }
+
+    /// The events currently buffered for replay, oldest first.
+    pub fn buffered(&self) -> Vec<Arc<SequencedEvent>> {
+        self.history().recent.iter().cloned().collect()
+    }
+
+    fn history(&self) -> std::sync::MutexGuard<'_, History> {
+        self.history
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// What an agent's event stream has been doing since it was registered.
+#[derive(Debug)]
+pub struct AgentActivity {
+    registered_at: std::time::Instant,
+    /// Unix milliseconds of the latest event, 0 before the first.
+    last_event_ms: AtomicI64,
+    connected: AtomicBool,
+    /// Events the forwarder skipped because it fell behind.
+    lagged_events: AtomicU64,
+    lag_window: std::sync::Mutex<LagWindow>,
+}
+
+/// Lag within the current warning window.
+#[derive(Debug)]
+struct LagWindow {
+    started: tokio::time::Instant,
+    skipped: u64,
+    warned: bool,
+}
+
+impl Default for AgentActivity {
+    fn default() -> Self {
+        Self {
+            registered_at: std::time::Instant::now(),
+            last_event_ms: AtomicI64::new(0),
+            connected: AtomicBool::new(true),
+            lagged_events: AtomicU64::new(0),
+            lag_window: std::sync::Mutex::new(LagWindow {
+                started: tokio::time::Instant::now(),
+                skipped: 0,
+                warned: false,
+            }),
+        }
+    }
+}
+
+impl AgentActivity {
+    /// Whether the agent's event channel is still open.
+    pub fn connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// When the agent last sent an event, if it has sent any.
+    pub fn last_event_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self.last_event_ms.load(Ordering::Relaxed) {
+            0 => None,
+            millis => chrono::DateTime::from_timestamp_millis(millis),
+        }
+    }
+
+    /// How long ago the agent's event stream was registered.
+    pub fn uptime(&self) -> std::time::Duration {
+        self.registered_at.elapsed()
+    }
+
+    /// Events the forwarder has skipped since registration because it fell
+    /// behind the agent.
+    pub fn lagged_events(&self) -> u64 {
+        self.lagged_events.load(Ordering::Relaxed)
+    }
+
+    /// Count `skipped` events lost to lag. Returns true when that takes the
+    /// current window over [`LAG_WARNING_THRESHOLD`] for the first time, so
+    /// the caller warns once per window rather than on every lag.
+    fn record_lag(&self, skipped: u64) -> bool {
+        self.lagged_events.fetch_add(skipped, Ordering::Relaxed);
+        let now = tokio::time::Instant::now();
+        let mut window = self
+            .lag_window
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if now.duration_since(window.started) >= LAG_WARNING_WINDOW {
+            *window = LagWindow {
+                started: now,
+                skipped: 0,
+                warned: false,
+            };
+        }
+        window.skipped += skipped;
+        if window.skipped > LAG_WARNING_THRESHOLD && !window.warned {
+            window.warned = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// One agent's ProcessEvents, tagged with its ID. A lag shows up as the
+/// number of events skipped.
+type AgentEvents = BoxStream<'static, (Arc<str>, Result<ProcessEvent, u64>)>;
+
+fn agent_events(
+    agent_id: String,
+    rx: broadcast::Receiver<ProcessEvent>,
+    activity: Arc<AgentActivity>,
+) -> AgentEvents {
+    let agent_id: Arc<str> = agent_id.into();
+    let state = (rx, activity, agent_id.clone());
+    futures::stream::unfold(state, |(mut rx, activity, agent_id)| async move {
+        let item = match rx.recv().await {
+            Ok(event) => Ok(event),
+            Err(broadcast::error::RecvError::Lagged(count)) => {
+                if activity.record_lag(count) {
+                    tracing::warn!(
+                        agent_id = %agent_id,
+                        window_secs = LAG_WARNING_WINDOW.as_secs(),
+                        total_skipped = activity.lagged_events(),
+                        "API event forwarder keeps falling behind, SSE clients are missing agent events"
+                    );
+                }
+                Err(count)
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                activity.connected.store(false, Ordering::Relaxed);
+                return None;
+            }
+        };
+        activity
+            .last_event_ms
+            .store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+        Some((item, (rx, activity, agent_id)))
+    })
+    .map(move |item| (agent_id.clone(), item))
+    .boxed()
+}
+
+/// Forward an agent's events in a task of its own.
+pub(super) fn spawn_forwarder(
+    sequencer: Arc<EventSequencer>,
+    agent_id: String,
+    rx: broadcast::Receiver<ProcessEvent>,
+    activity: Arc<AgentActivity>,
+) {
+    let mut events = agent_events(agent_id, rx, activity);
+    tokio::spawn(async move {
+        while let Some((agent_id, item)) = events.next().await {
+            forward(&sequencer, &agent_id, item);
+        }
+    });
+}
+
+/// A fixed set of tasks forwarding the events of every agent, so the task
+/// count stays bounded however many agents there are. Agents are handed to
+/// the tasks in turn.
+#[derive(Debug)]
+pub struct ForwarderPool {
+    workers: Vec<mpsc::UnboundedSender<AgentEvents>>,
+    next_worker: AtomicUsize,
+}
+
+impl ForwarderPool {
+    /// Start `parallelism` forwarder tasks feeding `sequencer`.
+    pub fn new(parallelism: usize, sequencer: Arc<EventSequencer>) -> Self {
+        let workers = (0..parallelism.max(1))
+            .map(|_| {
+                let (tx, rx) = mpsc::unbounded_channel();
+                tokio::spawn(run_worker(rx, sequencer.clone()));
+                tx
+            })
+            .collect();
+        Self {
+            workers,
+            next_worker: AtomicUsize::new(0),
+        }
+    }
+
+    /// Start forwarding an agent's events on one of the pool's tasks.
+    pub fn register(
+        &self,
+        agent_id: String,
+        rx: broadcast::Receiver<ProcessEvent>,
+        activity: Arc<AgentActivity>,
+    ) {
+        let index = self.next_worker.fetch_add(1, Ordering::Relaxed) % self.workers.len();
+        if self.workers[index]
+            .send(agent_events(agent_id.clone(), rx, activity))
+            .is_err()
+        {
+            tracing::warn!(agent_id = %agent_id, "API event forwarder is gone, agent events dropped");
+        }
+    }
+}
+
+/// Forward the events of the agents handed to this worker until the pool
+/// and all of its agents are gone.
+async fn run_worker(
+    mut agents: mpsc::UnboundedReceiver<AgentEvents>,
+    sequencer: Arc<EventSequencer>,
+) {
+    let mut events = SelectAll::new();
+    loop {
+        tokio::select! {
+            agent = agents.recv() => match agent {
+                Some(agent) => events.push(agent),
+                None => break,
+            },
+            Some((agent_id, item)) = events.next(), if !events.is_empty() => {
+                forward(&sequencer, &agent_id, item);
+            }
+        }
+    }
+    while let Some((agent_id, item)) = events.next().await {
+        forward(&sequencer, &agent_id, item);
+    }
+}
+
+fn forward(sequencer: &EventSequencer, agent_id: &str, item: Result<ProcessEvent, u64>) {
+    match item {
+        Ok(event) => {
+            if let Some(event) = api_event(agent_id, &event) {
+                sequencer.send(event).ok();
+            }
+        }
+        Err(count) => {
+            tracing::debug!(agent_id = %agent_id, count, "API event forwarder lagged, skipped events");
+            #[cfg(feature = "metrics")]
+            crate::telemetry::Metrics::global()
+                .api_events_lagged_total
+                .with_label_values(&[agent_id])
+                .inc_by(count);
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn record_event(event: &ApiEvent) {
+    let metrics = crate::telemetry::Metrics::global();
+    let agent_id = event.agent_id().unwrap_or_default();
+    metrics
+        .api_events_total
+        .with_label_values(&[agent_id, event.type_name()])
+        .inc();
+    let direction = match event {
+        ApiEvent::InboundMessage { .. } => "inbound",
+        ApiEvent::OutboundMessage { .. } => "outbound",
+        _ => return,
+    };
+    metrics
+        .messages_total
+        .with_label_values(&[agent_id, direction])
+        .inc();
+}
+
+/// Translate a ProcessEvent into the ApiEvent SSE clients see, if they see
+/// it at all.
+fn api_event(agent_id: &str, event: &ProcessEvent) -> Option<ApiEvent> {
+    let agent_id = agent_id.to_string();
+    let event = match event {
+        ProcessEvent::WorkerStarted {
+            worker_id,
+            channel_id,
+            task,
+            ..
+        } => ApiEvent::WorkerStarted {
+            agent_id,
+            channel_id: channel_id.as_deref().map(|s| s.to_string()),
+            worker_id: worker_id.to_string(),
+            task: task.clone(),
+        },
+        ProcessEvent::BranchStarted {
+            branch_id,
+            channel_id,
+            description,
+            ..
+        } => ApiEvent::BranchStarted {
+            agent_id,
+            channel_id: channel_id.to_string(),
+            branch_id: branch_id.to_string(),
+            description: description.clone(),
+        },
+        ProcessEvent::WorkerStatus {
+            worker_id,
+            channel_id,
+            status,
+            ..
+        } => ApiEvent::WorkerStatusUpdate {
+            agent_id,
+            channel_id: channel_id.as_deref().map(|s| s.to_string()),
+            worker_id: worker_id.to_string(),
+            status: status.clone(),
+        },
+        ProcessEvent::WorkerComplete {
+            worker_id,
+            channel_id,
+            result,
+            ..
+        } => ApiEvent::WorkerCompleted {
+            agent_id,
+            channel_id: channel_id.as_deref().map(|s| s.to_string()),
+            worker_id: worker_id.to_string(),
+            result: result.clone(),
+        },
+        ProcessEvent::BranchResult {
+            branch_id,
+            channel_id,
+            conclusion,
+            ..
+        } => ApiEvent::BranchCompleted {
+            agent_id,
+            channel_id: channel_id.to_string(),
+            branch_id: branch_id.to_string(),
+            conclusion: conclusion.clone(),
+        },
+        ProcessEvent::ToolStarted {
+            process_id,
+            channel_id,
+            tool_name,
+            ..
+        } => {
+            let (process_type, id_str) = process_id_info(process_id);
+            ApiEvent::ToolStarted {
+                agent_id,
+                channel_id: channel_id.as_deref().map(|s| s.to_string()),
+                process_type,
+                process_id: id_str,
+                tool_name: tool_name.clone(),
+            }
+        }
+        ProcessEvent::ToolCompleted {
+            process_id,
+            channel_id,
+            tool_name,
+            ..
+        } => {
+            let (process_type, id_str) = process_id_info(process_id);
+            ApiEvent::ToolCompleted {
+                agent_id,
+                channel_id: channel_id.as_deref().map(|s| s.to_string()),
+                process_type,
+                process_id: id_str,
+                tool_name: tool_name.clone(),
+            }
+        }
+        ProcessEvent::ShellOutput {
+            process_id,
+            channel_id,
+            stream,
+            line,
+            ..
+        } => {
+            let (process_type, id_str) = process_id_info(process_id);
+            ApiEvent::ShellOutput {
+                agent_id,
+                channel_id: channel_id.as_deref().map(|s| s.to_string()),
+                process_type,
+                process_id: id_str,
+                stream: stream.clone(),
+                line: line.clone(),
+            }
+        }
+        ProcessEvent::PolicyViolation {
+            process_id,
+            channel_id,
+            tool_name,
+            reason,
+            terminated,
+            ..
+        } => {
+            let (process_type, id_str) = process_id_info(process_id);
+            ApiEvent::PolicyViolation {
+                agent_id,
+                channel_id: channel_id.as_deref().map(|s| s.to_string()),
+                process_type,
+                process_id: id_str,
+                tool_name: tool_name.clone(),
+                reason: reason.clone(),
+                terminated: *terminated,
+            }
+        }
+        ProcessEvent::ReminderDue {
+            channel_id, text, ..
+        } => ApiEvent::ReminderDue {
+            agent_id,
+            channel_id: channel_id.to_string(),
+            text: text.clone(),
+        },
+        _ => return None,
+    };
+    Some(event)
+}
+
+/// Extract (process_type, id_string) from a ProcessId.
+fn process_id_info(id: &ProcessId) -> (String, String) {
+    match id {
+        ProcessId::Channel(channel_id) => ("channel".into(), channel_id.to_string()),
+        ProcessId::Branch(branch_id) => ("branch".into(), branch_id.to_string()),
+        ProcessId::Worker(worker_id) => ("worker".into(), worker_id.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn status(agent_id: &str, worker_id: uuid::Uuid, status: usize) -> ProcessEvent {
+        ProcessEvent::WorkerStatus {
+            agent_id: agent_id.into(),
+            worker_id,
+            channel_id: None,
+            status: status.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn events_are_serialized_once_for_all_subscribers() {
+        const SUBSCRIBERS: usize = 50;
+        let sequencer = EventSequencer::new(16);
+        let mut receivers: Vec<_> = (0..SUBSCRIBERS).map(|_| sequencer.subscribe()).collect();
+        sequencer
+            .send(ApiEvent::OutboundMessage {
+                agent_id: "main".into(),
+                channel_id: "discord:1".into(),
+                text: "x".repeat(64 * 1024),
+            })
+            .expect("subscribed");
+
+        let buffered = sequencer.buffered();
+        for rx in &mut receivers {
+            let event = rx.recv().await.expect("delivered");
+            // Every subscriber holds the one event serialized at ingest.
+            assert!(Arc::ptr_eq(&event, &buffered[0]));
+        }
+
+        let shared = buffered[0].json.as_deref().expect("serializable");
+        let json: serde_json::Value = serde_json::from_str(shared).expect("valid json");
+        assert_eq!(json["type"], "outbound_message");
+        assert_eq!(json["id"], 1);
+    }
+
+    #[tokio::test]
+    async fn reconnecting_clients_get_the_buffered_backlog() {
+        let sequencer = EventSequencer::new(16);
+        sequencer.set_replay_buffer(3);
+        let worker_id = uuid::Uuid::new_v4();
+        let event = |n| api_event("main", &status("main", worker_id, n)).expect("forwarded");
+        for n in 1..=5 {
+            // Nobody is subscribed, but the event is still buffered.
+            assert!(sequencer.send(event(n)).is_err());
+        }
+
+        let (backlog, mut rx) = sequencer.subscribe_after(2);
+        let sequences: Vec<u64> = backlog.events.iter().map(|event| event.sequence).collect();
+        assert_eq!(sequences, [3, 4, 5]);
+        assert_eq!(backlog.missed, 0);
+
+        // Event 2 fell out of the buffer.
+        let (backlog, _) = sequencer.subscribe_after(1);
+        assert_eq!((backlog.events.len(), backlog.missed), (3, 1));
+        let (backlog, _) = sequencer.subscribe_after(5);
+        assert_eq!((backlog.events.len(), backlog.missed), (0, 0));
+
+        // The live stream continues right after the backlog.
+        sequencer.send(event(6)).expect("subscribed");
+        assert_eq!(rx.recv().await.expect("live event").sequence, 6);
+
+        // An id from before a restart replays everything buffered.
+        let (backlog, _) = sequencer.subscribe_after(1_000);
+        assert_eq!(backlog.events.first().map(|event| event.sequence), Some(4));
+    }
+
+    #[tokio::test]
+    async fn forwarders_track_agent_activity() {
+        let sequencer = Arc::new(EventSequencer::new(16));
+        let mut rx = sequencer.subscribe();
+        let activity = Arc::new(AgentActivity::default());
+        let (tx, agent_rx) = broadcast::channel(4);
+        spawn_forwarder(sequencer, "main".into(), agent_rx, activity.clone());
+        assert!(activity.connected());
+        assert_eq!(activity.last_event_at(), None);
+
+        let before = chrono::Utc::now();
+        tx.send(status("main", uuid::Uuid::new_v4(), 1))
+            .expect("forwarder is subscribed");
+        rx.recv().await.expect("event is forwarded");
+        let last_event_at = activity.last_event_at().expect("event was recorded");
+        assert!(last_event_at >= before - chrono::Duration::milliseconds(1));
+
+        drop(tx);
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while activity.connected() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("closed channel marks the agent disconnected");
+    }
+
+    #[tokio::test]
+    async fn forwarders_count_the_events_they_skip() {
+        let sequencer = Arc::new(EventSequencer::new(16));
+        let mut rx = sequencer.subscribe();
+        let activity = Arc::new(AgentActivity::default());
+        let (tx, agent_rx) = broadcast::channel(4);
+        let worker_id = uuid::Uuid::new_v4();
+        // Sent before the forwarder runs, so it starts 6 events behind.
+        for n in 0..10 {
+            tx.send(status("main", worker_id, n))
+                .expect("forwarder is subscribed");
+        }
+        spawn_forwarder(sequencer, "main".into(), agent_rx, activity.clone());
+
+        for _ in 0..4 {
+            rx.recv().await.expect("kept events are forwarded");
+        }
+        assert_eq!(activity.lagged_events(), 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lag_warnings_are_limited_to_one_per_window() {
+        let activity = AgentActivity::default();
+        assert!(!activity.record_lag(LAG_WARNING_THRESHOLD));
+        assert!(activity.record_lag(1));
+        assert!(!activity.record_lag(500));
+
+        tokio::time::advance(LAG_WARNING_WINDOW).await;
+        assert!(!activity.record_lag(1));
+        assert!(activity.record_lag(LAG_WARNING_THRESHOLD));
+        assert_eq!(activity.lagged_events(), 2 * LAG_WARNING_THRESHOLD + 502);
+    }
+
+    #[tokio::test]
+    async fn pooled_forwarders_sequence_events_from_many_agents() {
+        const AGENTS: usize = 12;
+        const EVENTS: usize = 50;
+
+        let sequencer = Arc::new(EventSequencer::new(AGENTS * EVENTS * 2));
+        let mut rx = sequencer.subscribe();
+        let pool = ForwarderPool::new(3, sequencer.clone());
+
+        let mut senders = Vec::new();
+        for agent in 0..AGENTS {
+            let (tx, agent_rx) = broadcast::channel(EVENTS * 2);
+            pool.register(format!("agent-{agent}"), agent_rx, Arc::default());
+            senders.push(tx);
+        }
+        let worker_id = uuid::Uuid::new_v4();
+        for event in 0..EVENTS {
+            for (agent, tx) in senders.iter().enumerate() {
+                tx.send(status(&format!("agent-{agent}"), worker_id, event))
+                    .expect("forwarder is subscribed");
+            }
+        }
+        // Agents going away ends their streams without disturbing the rest.
+        senders.truncate(AGENTS / 2);
+
+        let mut last_status: HashMap<String, usize> = HashMap::new();
+        for expected_sequence in 1..=(AGENTS * EVENTS) as u64 {
+            let SequencedEvent {
+                sequence, event, ..
+            } = Arc::unwrap_or_clone(
+                tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+                    .await
+                    .expect("events keep arriving")
+                    .expect("sequencer is open"),
+            );
+            assert_eq!(sequence, expected_sequence);
+
+            let (agent_id, status) = match event {
+                ApiEvent::WorkerStatusUpdate {
+                    agent_id, status, ..
+                } => (agent_id, status),
+                other => panic!("unexpected event {other:?}"),
+            };
+            let status: usize = status.parse().expect("numeric status");
+            let previous = last_status.insert(agent_id.clone(), status);
+            assert_eq!(
+                previous.map_or(0, |previous| previous + 1),
+                status,
+                "{agent_id} events out of order"
+            );
+        }
+        assert!(last_status.values().all(|&status| status == EVENTS - 1));
+
+        // An agent added later is picked up by the running pool.
+        let (tx, agent_rx) = broadcast::channel(4);
+        pool.register("late".into(), agent_rx, Arc::default());
+        tx.send(status("late", worker_id, 0))
+            .expect("forwarder is subscribed");
+        let sequence = rx.recv().await.expect("sequencer is open").sequence;
+        assert_eq!(sequence, (AGENTS * EVENTS) as u64 + 1);
+    }
+}
@@ -0,0 +1,24 @@
+//! Prometheus metrics on the control API.
+//!
+//! Counters are recorded where events happen. Gauges that come from API
+//! state, like the subscriber count, are refreshed on every scrape.
+
+use super::state::ApiState;
+
+use crate::telemetry::Metrics;
+
+use axum::extract::State;
+use axum::response::Response;
+use std::sync::Arc;
+
+/// The global metrics registry in the Prometheus text format.
+pub(super) async fn metrics(State(state): State<Arc<ApiState>>) -> Response {
+    let metrics = Metrics::global();
+    metrics
+        .sse_subscribers
+        .set(state.event_tx.receiver_count() as i64);
+    metrics
+        .uptime_seconds
+        .set(state.started_at.elapsed().as_secs() as i64);
+    crate::telemetry::metrics_response()
+}
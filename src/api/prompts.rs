@@ -0,0 +1,43 @@
+//! Manually reloading prompt overrides.
+//!
+//! The file watcher already reloads `{instance_dir}/prompts` on change. This
+//! endpoint is for when it can't: the directory was created after startup,
+//! or the filesystem doesn't deliver change events.
+
+use super::state::ApiState;
+
+use crate::prompts::text::OverrideReport;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use std::sync::Arc;
+
+/// Re-read prompt overrides and rebuild every agent's prompt engine.
+pub(super) async fn reload_prompts(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<OverrideReport>, StatusCode> {
+    let prompts_dir = state.instance_dir.load().join("prompts");
+    let report =
+        tokio::task::spawn_blocking(move || crate::prompts::text::reload_overrides(&prompts_dir))
+            .await
+            .map_err(|error| {
+                tracing::error!(%error, "prompt reload task failed");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+    for runtime_config in state.runtime_configs.load().values() {
+        runtime_config.reload_prompts();
+    }
+
+    // Agents created from now on start with the reloaded texts too.
+    let mut prompt_engine = state.prompt_engine.write().await;
+    if let Some(current) = prompt_engine.as_ref() {
+        match crate::prompts::PromptEngine::new(current.language()) {
+            Ok(engine) => *prompt_engine = Some(engine),
+            Err(error) => tracing::error!(%error, "failed to rebuild prompt engine"),
+        }
+    }
+
+    Ok(Json(report))
+}
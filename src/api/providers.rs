@@ -211,6 +211,7 @@ fn build_test_llm_config(provider: &str, credential: &str) -> crate::config::Llm
         moonshot_key: (provider == "moonshot").then(|| credential.to_string()),
         zai_coding_plan_key: (provider == "zai-coding-plan").then(|| credential.to_string()),
         providers,
+        prices: HashMap::new(),
     }
 }
 
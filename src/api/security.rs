@@ -0,0 +1,347 @@
+//! Admin endpoints for an agent's shell security lists.
+//!
+//! The built-in sensitive file patterns and secret env var names always
+//! apply. These endpoints only manage what an agent adds on top of them,
+//! stored under the agent's `shell` table in config.toml.
+
+use super::state::ApiState;
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// One of the two lists an agent can extend.
+#[derive(Debug, Clone, Copy)]
+enum SecurityList {
+    SensitiveFiles,
+    SecretEnvVars,
+}
+
+impl SecurityList {
+    /// The key under `shell` in config.toml.
+    fn key(self) -> &'static str {
+        match self {
+            Self::SensitiveFiles => "sensitive_files",
+            Self::SecretEnvVars => "secret_env_vars",
+        }
+    }
+
+    fn builtin(self) -> &'static [&'static str] {
+        match self {
+            Self::SensitiveFiles => crate::tools::shell::SENSITIVE_FILES,
+            Self::SecretEnvVars => crate::tools::shell::SECRET_ENV_VARS,
+        }
+    }
+
+    fn validate(self, entry: &str) -> Result<(), String> {
+        match self {
+            Self::SensitiveFiles => globset::Glob::new(entry)
+                .map(|_| ())
+                .map_err(|error| format!("invalid pattern '{entry}': {error}")),
+            Self::SecretEnvVars if crate::config::is_env_var_name(entry) => Ok(()),
+            Self::SecretEnvVars => Err(format!("invalid env var name '{entry}'")),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub(super) struct SecurityListResponse {
+    agent_id: String,
+    /// Always enforced; can't be removed.
+    builtin: Vec<String>,
+    /// Added for every agent under `[defaults.shell]`.
+    instance: Vec<String>,
+    /// Added for this agent only. What PUT replaces.
+    agent: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(super) struct SecurityListUpdate {
+    entries: Vec<String>,
+}
+
+/// Get the sensitive file patterns an agent's shell commands may not touch.
+pub(super) async fn get_sensitive_files(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<SecurityListResponse>, StatusCode> {
+    get_list(&state, &headers, agent_id, SecurityList::SensitiveFiles).await
+}
+
+/// Replace the sensitive file patterns an agent adds to the built-in set.
+pub(super) async fn update_sensitive_files(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<SecurityListUpdate>,
+) -> Result<Json<SecurityListResponse>, StatusCode> {
+    update_list(
+        &state,
+        &headers,
+        agent_id,
+        SecurityList::SensitiveFiles,
+        request,
+    )
+    .await
+}
+
+/// Get the env var names an agent's shell commands may not read.
+pub(super) async fn get_secret_vars(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<SecurityListResponse>, StatusCode> {
+    get_list(&state, &headers, agent_id, SecurityList::SecretEnvVars).await
+}
+
+/// Replace the secret env var names an agent adds to the built-in set.
+pub(super) async fn update_secret_vars(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<SecurityListUpdate>,
+) -> Result<Json<SecurityListResponse>, StatusCode> {
+    update_list(
+        &state,
+        &headers,
+        agent_id,
+        SecurityList::SecretEnvVars,
+        request,
+    )
+    .await
+}
+
+async fn get_list(
+    state: &ApiState,
+    headers: &HeaderMap,
+    agent_id: String,
+    list: SecurityList,
+) -> Result<Json<SecurityListResponse>, StatusCode> {
+    authorize_admin(state, headers)?;
+    if !state.runtime_configs.load().contains_key(&agent_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let doc = read_config(state).await?;
+    Ok(Json(list_response(&doc, agent_id, list)))
+}
+
+/// Write the agent's additions to config.toml and reload its runtime config,
+/// so workers started afterwards enforce them.
+async fn update_list(
+    state: &ApiState,
+    headers: &HeaderMap,
+    agent_id: String,
+    list: SecurityList,
+    request: SecurityListUpdate,
+) -> Result<Json<SecurityListResponse>, StatusCode> {
+    authorize_admin(state, headers)?;
+    let runtime_configs = state.runtime_configs.load();
+    let Some(runtime_config) = runtime_configs.get(&agent_id) else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    for entry in &request.entries {
+        if let Err(error) = list.validate(entry) {
+            tracing::info!(agent_id, %error, "rejected security list update");
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let mut doc = read_config(state).await?;
+    let agent_idx = super::config::find_or_create_agent_table(&mut doc, &agent_id)?;
+    let agent = super::config::get_agent_table_mut(&mut doc, agent_idx)?;
+    let shell = super::config::get_or_create_subtable(agent, "shell");
+    if request.entries.is_empty() {
+        shell.remove(list.key());
+    } else {
+        shell[list.key()] = toml_edit::value(toml_edit::Array::from_iter(&request.entries));
+    }
+
+    let config_path = state.config_path.read().await.clone();
+    tokio::fs::write(&config_path, doc.to_string())
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, "failed to write config.toml");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // Unlike other settings, a change here must not be reported as done
+    // while the agent still runs with the old list.
+    let new_config = crate::config::Config::load_from_path(&config_path).map_err(|error| {
+        tracing::error!(%error, agent_id, "config.toml written but failed to reload");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    runtime_config.reload_config(&new_config, &agent_id);
+
+    tracing::info!(
+        agent_id,
+        list = list.key(),
+        "agent security list updated via API"
+    );
+
+    Ok(Json(list_response(&doc, agent_id, list)))
+}
+
+/// Require the admin bearer token. 404 when none is configured, so the
+/// endpoints stay hidden unless an operator opts in.
+pub(super) fn authorize_admin(state: &ApiState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let token = state.admin_token.load();
+    let Some(token) = token.as_deref() else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    if !super::system::has_bearer_token(headers, token) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(())
+}
+
+async fn read_config(state: &ApiState) -> Result<toml_edit::DocumentMut, StatusCode> {
+    let config_path = state.config_path.read().await.clone();
+    if config_path.as_os_str().is_empty() {
+        tracing::error!("config_path not set in ApiState");
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    let config_content = tokio::fs::read_to_string(&config_path)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, "failed to read config.toml");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    config_content
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|error| {
+            tracing::warn!(%error, "failed to parse config.toml");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+fn list_response(
+    doc: &toml_edit::DocumentMut,
+    agent_id: String,
+    list: SecurityList,
+) -> SecurityListResponse {
+    let instance = doc
+        .get("defaults")
+        .and_then(|defaults| defaults.get("shell"))
+        .and_then(|shell| shell.get(list.key()));
+    let agent = doc
+        .get("agents")
+        .and_then(|agents| agents.as_array_of_tables())
+        .and_then(|agents| {
+            agents
+                .iter()
+                .find(|table| table.get("id").and_then(|id| id.as_str()) == Some(&agent_id))
+        })
+        .and_then(|table| table.get("shell"))
+        .and_then(|shell| shell.get(list.key()));
+
+    SecurityListResponse {
+        builtin: list
+            .builtin()
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect(),
+        instance: string_array(instance),
+        agent: string_array(agent),
+        agent_id,
+    }
+}
+
+fn string_array(item: Option<&toml_edit::Item>) -> Vec<String> {
+    item.and_then(|item| item.as_array())
+        .map(|array| {
+            array
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::shell::{ShellArgs, ShellTool};
+    use axum::http::header;
+    use rig::tool::Tool;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn added_sensitive_files_block_later_commands() {
+        let instance = tempfile::tempdir().expect("tempdir");
+        let workspace = instance.path().join("workspace");
+        std::fs::create_dir(&workspace).expect("workspace");
+        let config_path = instance.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            format!("[[agents]]\nid = \"main\"\nworkspace = {:?}\n", workspace),
+        )
+        .expect("config");
+
+        let config = crate::config::Config::load_from_path(&config_path).expect("config loads");
+        let resolved = config.agents[0].resolve(instance.path(), &config.defaults);
+        let runtime_config = Arc::new(crate::config::RuntimeConfig::new(
+            instance.path(),
+            &resolved,
+            &config.defaults,
+            crate::prompts::PromptEngine::new("en").unwrap(),
+            crate::identity::Identity::default(),
+            crate::skills::SkillSet::default(),
+        ));
+
+        let (provider_tx, _) = tokio::sync::mpsc::channel(1);
+        let (agent_tx, _) = tokio::sync::mpsc::channel(1);
+        let (agent_remove_tx, _) = tokio::sync::mpsc::channel(1);
+        let state = ApiState::new_with_provider_sender(provider_tx, agent_tx, agent_remove_tx);
+        state.set_config_path(config_path.clone()).await;
+        state.set_admin_token(Some("admin-secret".into()));
+        state.set_runtime_configs(HashMap::from([(
+            "main".to_string(),
+            runtime_config.clone(),
+        )]));
+
+        let mut headers = HeaderMap::new();
+        let unauthorized = update_list(
+            &state,
+            &headers,
+            "main".into(),
+            SecurityList::SensitiveFiles,
+            SecurityListUpdate {
+                entries: vec!["deploy.key".into()],
+            },
+        )
+        .await;
+        assert_eq!(unauthorized.unwrap_err(), StatusCode::UNAUTHORIZED);
+
+        headers.insert(
+            header::AUTHORIZATION,
+            "Bearer admin-secret".parse().unwrap(),
+        );
+        let Json(response) = update_list(
+            &state,
+            &headers,
+            "main".into(),
+            SecurityList::SensitiveFiles,
+            SecurityListUpdate {
+                entries: vec!["deploy.key".into()],
+            },
+        )
+        .await
+        .expect("update succeeds");
+        assert_eq!(response.agent, vec!["deploy.key"]);
+        assert!(response.builtin.contains(&"config.toml".to_string()));
+
+        let shell_config = (**runtime_config.shell_config.load()).clone();
+        let tool = ShellTool::new(instance.path().to_path_buf(), workspace)
+            .expect("workspace exists")
+            .with_config(shell_config);
+        let args: ShellArgs =
+            serde_json::from_value(serde_json::json!({ "command": "cat deploy.key" })).unwrap();
+        let error = tool.call(args).await.expect_err("command is blocked");
+        assert!(error.to_string().contains("ACCESS DENIED"), "{error}");
+    }
+}
@@ -2,8 +2,8 @@
 
 use super::state::ApiState;
 use super::{
-    agents, bindings, channels, config, cortex, cron, ingest, memories, messaging, models,
-    providers, settings, skills, system, webchat,
+    agents, bindings, channels, config, cortex, cron, ingest, memories, messaging, models, prompts,
+    providers, security, settings, skills, system, usage, webchat, websocket,
 };
 
 use axum::Router;
@@ -42,6 +42,9 @@ pub async fn start_http_server(
         .route("/status", get(system::status))
         .route("/overview", get(agents::instance_overview))
         .route("/events", get(system::events_sse))
+        .route("/ws", get(websocket::events_ws))
+        .route("/debug/replay", post(system::replay_events))
+        .route("/debug/manifest", get(system::event_manifest))
         .route(
             "/agents",
             get(agents::list_agents)
@@ -49,8 +52,17 @@ pub async fn start_http_server(
                 .delete(agents::delete_agent),
         )
         .route("/agents/overview", get(agents::agent_overview))
+        .route("/agents/status", get(agents::agent_status))
         .route("/channels", get(channels::list_channels))
         .route("/channels/messages", get(channels::channel_messages))
+        .route(
+            "/agents/{agent_id}/channels/{channel_id}/messages",
+            get(channels::agent_channel_messages),
+        )
+        .route(
+            "/agents/{agent_id}/channels/{channel_id}/send",
+            post(channels::send_channel_message),
+        )
         .route("/channels/status", get(channels::channel_status))
         .route("/agents/memories", get(memories::list_memories))
         .route("/agents/memories/search", get(memories::search_memories))
@@ -63,6 +75,9 @@ pub async fn start_http_server(
         .route("/cortex-chat/messages", get(cortex::cortex_chat_messages))
         .route("/cortex-chat/send", post(cortex::cortex_chat_send))
         .route("/agents/profile", get(agents::get_agent_profile))
+        .route("/agents/{agent_id}/tools", get(agents::list_agent_tools))
+        .route("/agents/{agent_id}/usage", get(agents::agent_usage))
+        .route("/usage/cost", get(usage::usage_cost))
         .route(
             "/agents/identity",
             get(agents::get_identity).put(agents::update_identity),
@@ -77,6 +92,14 @@ pub async fn start_http_server(
                 .post(cron::create_or_update_cron)
                 .delete(cron::delete_cron),
         )
+        .route(
+            "/agents/{agent_id}/security/sensitive-files",
+            get(security::get_sensitive_files).put(security::update_sensitive_files),
+        )
+        .route(
+            "/agents/{agent_id}/security/secret-vars",
+            get(security::get_secret_vars).put(security::update_secret_vars),
+        )
         .route("/agents/cron/executions", get(cron::cron_executions))
         .route("/agents/cron/trigger", post(cron::trigger_cron))
         .route("/agents/cron/toggle", put(cron::toggle_cron))
@@ -99,6 +122,7 @@ pub async fn start_http_server(
         .route("/providers/{provider}", delete(providers::delete_provider))
         .route("/models", get(models::get_models))
         .route("/models/refresh", post(models::refresh_models))
+        .route("/prompts/reload", post(prompts::reload_prompts))
         .route("/messaging/status", get(messaging::messaging_status))
         .route(
             "/messaging/disconnect",
@@ -127,6 +151,8 @@ pub async fn start_http_server(
         .route("/update/apply", post(settings::update_apply))
         .route("/webchat/send", post(webchat::webchat_send))
         .route("/webchat/history", get(webchat::webchat_history));
+    #[cfg(feature = "metrics")]
+    let api_routes = api_routes.route("/metrics", get(super::metrics::metrics));
 
     let app = Router::new()
         .nest("/api", api_routes)
@@ -1,5 +1,6 @@
 //! Shared state for the HTTP API.
 
+use crate::ProcessEvent;
 use crate::agent::channel::ChannelState;
 use crate::agent::cortex_chat::CortexChatSession;
 use crate::agent::status::StatusBlock;
@@ -11,15 +12,18 @@ use crate::messaging::MessagingManager;
 use crate::messaging::webchat::WebChatAdapter;
 use crate::prompts::PromptEngine;
 use crate::update::SharedUpdateStatus;
-use crate::{ProcessEvent, ProcessId};
+
+use super::events::{
+    self, AgentActivity, DEFAULT_EVENT_CAPACITY, EventSequencer, ForwarderPool, Severity,
+};
 
 use arc_swap::ArcSwap;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::{RwLock, broadcast, mpsc};
 
 /// Summary of an agent's configuration, exposed via the API.
@@ -37,7 +41,13 @@ pub struct AgentInfo {
 pub struct ApiState {
     pub started_at: Instant,
     /// Aggregated event stream from all agents. SSE clients subscribe here.
-    pub event_tx: broadcast::Sender<ApiEvent>,
+    pub event_tx: Arc<EventSequencer>,
+    /// Shared tasks forwarding agents' events into `event_tx`. None runs a
+    /// forwarder per agent.
+    pub event_forwarders: ArcSwap<Option<ForwarderPool>>,
+    /// When each agent's event stream was registered and last active, for
+    /// the agent status endpoint.
+    pub agent_activity: ArcSwap<HashMap<String, Arc<AgentActivity>>>,
     /// Per-agent SQLite pools for querying channel/conversation data.
     pub agent_pools: arc_swap::ArcSwap<HashMap<String, sqlx::SqlitePool>>,
     /// Per-agent config summaries for the agents list endpoint.
@@ -49,6 +59,9 @@ pub struct ApiState {
     /// Live channel states for active channels, keyed by channel_id.
     /// Used by the cancel API to abort workers and branches.
     pub channel_states: RwLock<HashMap<String, ChannelState>>,
+    /// When each channel in `channel_status_blocks` and `channel_states` was
+    /// last active, for evicting the entries of channels that went quiet.
+    pub channel_activity: std::sync::Mutex<ChannelActivity>,
     /// Per-agent cortex chat sessions.
     pub cortex_chat_sessions: arc_swap::ArcSwap<HashMap<String, Arc<CortexChatSession>>>,
     /// Per-agent workspace paths for identity file access.
@@ -89,10 +102,22 @@ pub struct ApiState {
     pub agent_remove_tx: mpsc::Sender<String>,
     /// Shared webchat adapter for session management from API handlers.
     pub webchat_adapter: ArcSwap<Option<Arc<WebChatAdapter>>>,
+    /// Bearer token guarding the debug replay endpoint. None disables it.
+    pub debug_replay_token: ArcSwap<Option<String>>,
+    /// Bearer token required to open the SSE stream. None leaves it open.
+    pub events_token: ArcSwap<Option<String>>,
+    /// Bearer token required by the admin endpoints. None disables them.
+    pub admin_token: ArcSwap<Option<String>>,
+    /// How long an SSE stream may go without events before a keepalive
+    /// comment is sent, so proxies don't drop the idle connection.
+    pub sse_keepalive: ArcSwap<Duration>,
 }
 
+/// Default for [`ApiState::sse_keepalive`].
+pub const DEFAULT_SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
 /// Events sent to SSE clients. Wraps ProcessEvents with agent context.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ApiEvent {
     /// An inbound message from a user.
@@ -166,8 +191,145 @@ pub enum ApiEvent {
         process_id: String,
         tool_name: String,
     },
+    /// A line of output from a running shell command.
+    ShellOutput {
+        agent_id: String,
+        channel_id: Option<String>,
+        process_type: String,
+        process_id: String,
+        stream: String,
+        line: String,
+    },
+    /// A reminder scheduled by a channel came due.
+    ReminderDue {
+        agent_id: String,
+        channel_id: String,
+        text: String,
+    },
+    /// A tool call was blocked, or its process terminated, by a safety check.
+    PolicyViolation {
+        agent_id: String,
+        channel_id: Option<String>,
+        process_type: String,
+        process_id: String,
+        tool_name: String,
+        reason: String,
+        terminated: bool,
+    },
     /// Configuration was reloaded (skills, identity, etc.).
     ConfigReloaded,
+    /// The client's stream skipped `skipped` events, because it fell behind
+    /// or they were no longer buffered when it reconnected. Sent to that
+    /// client alone, never broadcast, so it can re-fetch what it missed.
+    Gap { skipped: u64 },
+    /// A captured event re-fed through the debug replay endpoint. SSE clients
+    /// receive the inner event, marked `"replayed": true`.
+    Replayed { event: Box<ApiEvent> },
+}
+
+impl ApiEvent {
+    /// The severity the event is sent with unless its producer picks one.
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            ApiEvent::TypingState { .. }
+            | ApiEvent::ToolStarted { .. }
+            | ApiEvent::ToolCompleted { .. }
+            | ApiEvent::ShellOutput { .. } => Severity::Debug,
+            ApiEvent::Gap { .. } => Severity::Warn,
+            ApiEvent::PolicyViolation { terminated, .. } => {
+                if *terminated {
+                    Severity::Error
+                } else {
+                    Severity::Warn
+                }
+            }
+            ApiEvent::Replayed { event } => event.default_severity(),
+            _ => Severity::Info,
+        }
+    }
+
+    /// The `type` tag of every event other than `replayed`.
+    pub const TYPES: &[&str] = &[
+        "inbound_message",
+        "outbound_message",
+        "typing_state",
+        "worker_started",
+        "worker_status_update",
+        "worker_completed",
+        "branch_started",
+        "branch_completed",
+        "tool_started",
+        "tool_completed",
+        "shell_output",
+        "reminder_due",
+        "policy_violation",
+        "config_reloaded",
+        "gap",
+    ];
+
+    /// The event's `type` tag. Replayed events report the event they carry.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ApiEvent::InboundMessage { .. } => "inbound_message",
+            ApiEvent::OutboundMessage { .. } => "outbound_message",
+            ApiEvent::TypingState { .. } => "typing_state",
+            ApiEvent::WorkerStarted { .. } => "worker_started",
+            ApiEvent::WorkerStatusUpdate { .. } => "worker_status_update",
+            ApiEvent::WorkerCompleted { .. } => "worker_completed",
+            ApiEvent::BranchStarted { .. } => "branch_started",
+            ApiEvent::BranchCompleted { .. } => "branch_completed",
+            ApiEvent::ToolStarted { .. } => "tool_started",
+            ApiEvent::ToolCompleted { .. } => "tool_completed",
+            ApiEvent::ShellOutput { .. } => "shell_output",
+            ApiEvent::ReminderDue { .. } => "reminder_due",
+            ApiEvent::PolicyViolation { .. } => "policy_violation",
+            ApiEvent::ConfigReloaded => "config_reloaded",
+            ApiEvent::Gap { .. } => "gap",
+            ApiEvent::Replayed { event } => event.type_name(),
+        }
+    }
+
+    /// The agent the event belongs to. None for instance-wide events.
+    pub fn agent_id(&self) -> Option<&str> {
+        match self {
+            ApiEvent::InboundMessage { agent_id, .. }
+            | ApiEvent::OutboundMessage { agent_id, .. }
+            | ApiEvent::TypingState { agent_id, .. }
+            | ApiEvent::WorkerStarted { agent_id, .. }
+            | ApiEvent::WorkerStatusUpdate { agent_id, .. }
+            | ApiEvent::WorkerCompleted { agent_id, .. }
+            | ApiEvent::BranchStarted { agent_id, .. }
+            | ApiEvent::BranchCompleted { agent_id, .. }
+            | ApiEvent::ToolStarted { agent_id, .. }
+            | ApiEvent::ToolCompleted { agent_id, .. }
+            | ApiEvent::ShellOutput { agent_id, .. }
+            | ApiEvent::ReminderDue { agent_id, .. }
+            | ApiEvent::PolicyViolation { agent_id, .. } => Some(agent_id),
+            ApiEvent::ConfigReloaded | ApiEvent::Gap { .. } => None,
+            ApiEvent::Replayed { event } => event.agent_id(),
+        }
+    }
+
+    /// The channel the event happened in, if any.
+    pub fn channel_id(&self) -> Option<&str> {
+        match self {
+            ApiEvent::InboundMessage { channel_id, .. }
+            | ApiEvent::OutboundMessage { channel_id, .. }
+            | ApiEvent::TypingState { channel_id, .. }
+            | ApiEvent::BranchStarted { channel_id, .. }
+            | ApiEvent::BranchCompleted { channel_id, .. }
+            | ApiEvent::ReminderDue { channel_id, .. } => Some(channel_id),
+            ApiEvent::WorkerStarted { channel_id, .. }
+            | ApiEvent::WorkerStatusUpdate { channel_id, .. }
+            | ApiEvent::WorkerCompleted { channel_id, .. }
+            | ApiEvent::ToolStarted { channel_id, .. }
+            | ApiEvent::ToolCompleted { channel_id, .. }
+            | ApiEvent::ShellOutput { channel_id, .. }
+            | ApiEvent::PolicyViolation { channel_id, .. } => channel_id.as_deref(),
+            ApiEvent::ConfigReloaded | ApiEvent::Gap { .. } => None,
+            ApiEvent::Replayed { event } => event.channel_id(),
+        }
+    }
 }
 
 impl ApiState {
@@ -176,15 +338,17 @@ impl ApiState {
         agent_tx: mpsc::Sender<crate::Agent>,
         agent_remove_tx: mpsc::Sender<String>,
     ) -> Self {
-        let (event_tx, _) = broadcast::channel(512);
         Self {
             started_at: Instant::now(),
-            event_tx,
+            event_tx: Arc::new(EventSequencer::new(DEFAULT_EVENT_CAPACITY)),
+            event_forwarders: ArcSwap::from_pointee(None),
+            agent_activity: ArcSwap::from_pointee(HashMap::new()),
             agent_pools: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             agent_configs: arc_swap::ArcSwap::from_pointee(Vec::new()),
             memory_searches: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             channel_status_blocks: RwLock::new(HashMap::new()),
             channel_states: RwLock::new(HashMap::new()),
+            channel_activity: std::sync::Mutex::new(ChannelActivity::default()),
             cortex_chat_sessions: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             agent_workspaces: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             config_path: RwLock::new(PathBuf::new()),
@@ -205,9 +369,20 @@ impl ApiState {
             agent_tx,
             agent_remove_tx,
             webchat_adapter: ArcSwap::from_pointee(None),
+            debug_replay_token: ArcSwap::from_pointee(None),
+            events_token: ArcSwap::from_pointee(None),
+            admin_token: ArcSwap::from_pointee(None),
+            sse_keepalive: ArcSwap::from_pointee(DEFAULT_SSE_KEEPALIVE),
         }
     }
 
+    /// Let SSE clients fall up to `capacity` events behind the stream
+    /// instead of [`DEFAULT_EVENT_CAPACITY`]. See [`EventSequencer::new`].
+    pub fn with_event_capacity(mut self, capacity: usize) -> Self {
+        self.event_tx = Arc::new(EventSequencer::new(capacity));
+        self
+    }
+
     /// Register a channel's status block so the API can read snapshots.
     pub async fn register_channel_status(
         &self,
@@ -235,139 +410,70 @@ impl ApiState {
         self.channel_states.write().await.remove(channel_id);
     }
 
-    /// Register an agent's event stream. Spawns a task that forwards
-    /// ProcessEvents into the aggregated API event stream.
+    /// Record activity on a channel, registering its status block and state
+    /// if they aren't tracked yet or were evicted, then evict the entries of
+    /// other channels that are past the configured [`ChannelLimits`].
+    pub async fn touch_channel(&self, channel_id: &str, state: &ChannelState) {
+        let now = Instant::now();
+        let (untracked, expired) = {
+            let mut activity = self.channel_activity();
+            (
+                activity.touch(channel_id, now),
+                activity.expired(now, channel_id),
+            )
+        };
+        if untracked {
+            self.register_channel_status(channel_id.to_string(), state.status_block.clone())
+                .await;
+            self.register_channel_state(channel_id.to_string(), state.clone())
+                .await;
+        }
+        for channel_id in expired {
+            self.evict_channel(&channel_id).await;
+        }
+    }
+
+    /// Drop a channel's status block and state, unless it still has workers
+    /// or branches running, which the cancel and idle endpoints need to see.
+    async fn evict_channel(&self, channel_id: &str) {
+        if let Some(status_block) = self.channel_status_blocks.read().await.get(channel_id) {
+            let block = status_block.read().await;
+            if !block.active_workers.is_empty() || !block.active_branches.is_empty() {
+                return;
+            }
+        }
+        self.unregister_channel_status(channel_id).await;
+        self.unregister_channel_state(channel_id).await;
+        self.channel_activity().remove(channel_id);
+        tracing::debug!(channel_id, "evicted idle channel from the API state");
+    }
+
+    fn channel_activity(&self) -> std::sync::MutexGuard<'_, ChannelActivity> {
+        self.channel_activity
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Register an agent's event stream, forwarding its ProcessEvents into
+    /// the aggregated API event stream. Runs on the shared forwarder pool
+    /// when one is configured, in a task of its own otherwise.
     pub fn register_agent_events(
         &self,
         agent_id: String,
-        mut agent_event_rx: broadcast::Receiver<ProcessEvent>,
+        agent_event_rx: broadcast::Receiver<ProcessEvent>,
     ) {
-        let api_tx = self.event_tx.clone();
-        tokio::spawn(async move {
-            loop {
-                match agent_event_rx.recv().await {
-                    Ok(event) => {
-                        // Translate ProcessEvents into typed ApiEvents
-                        match &event {
-                            ProcessEvent::WorkerStarted {
-                                worker_id,
-                                channel_id,
-                                task,
-                                ..
-                            } => {
-                                api_tx
-                                    .send(ApiEvent::WorkerStarted {
-                                        agent_id: agent_id.clone(),
-                                        channel_id: channel_id.as_deref().map(|s| s.to_string()),
-                                        worker_id: worker_id.to_string(),
-                                        task: task.clone(),
-                                    })
-                                    .ok();
-                            }
-                            ProcessEvent::BranchStarted {
-                                branch_id,
-                                channel_id,
-                                description,
-                                ..
-                            } => {
-                                api_tx
-                                    .send(ApiEvent::BranchStarted {
-                                        agent_id: agent_id.clone(),
-                                        channel_id: channel_id.to_string(),
-                                        branch_id: branch_id.to_string(),
-                                        description: description.clone(),
-                                    })
-                                    .ok();
-                            }
-                            ProcessEvent::WorkerStatus {
-                                worker_id,
-                                channel_id,
-                                status,
-                                ..
-                            } => {
-                                api_tx
-                                    .send(ApiEvent::WorkerStatusUpdate {
-                                        agent_id: agent_id.clone(),
-                                        channel_id: channel_id.as_deref().map(|s| s.to_string()),
-                                        worker_id: worker_id.to_string(),
-                                        status: status.clone(),
-                                    })
-                                    .ok();
-                            }
-                            ProcessEvent::WorkerComplete {
-                                worker_id,
-                                channel_id,
-                                result,
-                                ..
-                            } => {
-                                api_tx
-                                    .send(ApiEvent::WorkerCompleted {
-                                        agent_id: agent_id.clone(),
-                                        channel_id: channel_id.as_deref().map(|s| s.to_string()),
-                                        worker_id: worker_id.to_string(),
-                                        result: result.clone(),
-                                    })
-                                    .ok();
-                            }
-                            ProcessEvent::BranchResult {
-                                branch_id,
-                                channel_id,
-                                conclusion,
-                                ..
-                            } => {
-                                api_tx
-                                    .send(ApiEvent::BranchCompleted {
-                                        agent_id: agent_id.clone(),
-                                        channel_id: channel_id.to_string(),
-                                        branch_id: branch_id.to_string(),
-                                        conclusion: conclusion.clone(),
-                                    })
-                                    .ok();
-                            }
-                            ProcessEvent::ToolStarted {
-                                process_id,
-                                channel_id,
-                                tool_name,
-                                ..
-                            } => {
-                                let (process_type, id_str) = process_id_info(process_id);
-                                api_tx
-                                    .send(ApiEvent::ToolStarted {
-                                        agent_id: agent_id.clone(),
-                                        channel_id: channel_id.as_deref().map(|s| s.to_string()),
-                                        process_type,
-                                        process_id: id_str,
-                                        tool_name: tool_name.clone(),
-                                    })
-                                    .ok();
-                            }
-                            ProcessEvent::ToolCompleted {
-                                process_id,
-                                channel_id,
-                                tool_name,
-                                ..
-                            } => {
-                                let (process_type, id_str) = process_id_info(process_id);
-                                api_tx
-                                    .send(ApiEvent::ToolCompleted {
-                                        agent_id: agent_id.clone(),
-                                        channel_id: channel_id.as_deref().map(|s| s.to_string()),
-                                        process_type,
-                                        process_id: id_str,
-                                        tool_name: tool_name.clone(),
-                                    })
-                                    .ok();
-                            }
-                            _ => {}
-                        }
-                    }
-                    Err(broadcast::error::RecvError::Lagged(count)) => {
-                        tracing::debug!(agent_id = %agent_id, count, "API event forwarder lagged, skipped events");
-                    }
-                    Err(broadcast::error::RecvError::Closed) => break,
-                }
-            }
+        let activity = Arc::new(AgentActivity::default());
+        self.agent_activity.rcu(|agents| {
+            let mut agents = (**agents).clone();
+            agents.insert(agent_id.clone(), activity.clone());
+            agents
         });
+        match self.event_forwarders.load().as_ref() {
+            Some(pool) => pool.register(agent_id, agent_event_rx, activity),
+            None => {
+                events::spawn_forwarder(self.event_tx.clone(), agent_id, agent_event_rx, activity)
+            }
+        }
     }
 
     /// Set the SQLite pools for all agents.
@@ -466,17 +572,174 @@ impl ApiState {
         self.webchat_adapter.store(Arc::new(Some(adapter)));
     }
 
+    /// Set the token that enables the debug replay endpoint.
+    pub fn set_debug_replay_token(&self, token: Option<String>) {
+        self.debug_replay_token.store(Arc::new(token));
+    }
+
+    /// Set the token SSE clients must present. None leaves the stream open.
+    pub fn set_events_token(&self, token: Option<String>) {
+        self.events_token.store(Arc::new(token));
+    }
+
+    /// Set the token admin endpoints require. None disables them.
+    pub fn set_admin_token(&self, token: Option<String>) {
+        self.admin_token.store(Arc::new(token));
+    }
+
+    /// Set how long SSE streams may stay silent before a keepalive is sent.
+    /// Applies to streams opened afterwards.
+    pub fn set_sse_keepalive(&self, interval: Duration) {
+        self.sse_keepalive.store(Arc::new(interval));
+    }
+
+    /// Run agents' event forwarders on `parallelism` shared tasks. Agents
+    /// registered afterwards use the pool; call before registering any.
+    pub fn set_event_forwarders(&self, parallelism: usize) {
+        let pool = ForwarderPool::new(parallelism, self.event_tx.clone());
+        self.event_forwarders.store(Arc::new(Some(pool)));
+    }
+
+    /// Bound the channels whose status block and state are kept.
+    pub fn set_channel_limits(&self, limits: ChannelLimits) {
+        self.channel_activity().limits = limits;
+    }
+
     /// Send an event to all SSE subscribers.
     pub fn send_event(&self, event: ApiEvent) {
         let _ = self.event_tx.send(event);
     }
 }
 
-/// Extract (process_type, id_string) from a ProcessId.
-fn process_id_info(id: &ProcessId) -> (String, String) {
-    match id {
-        ProcessId::Channel(channel_id) => ("channel".into(), channel_id.to_string()),
-        ProcessId::Branch(branch_id) => ("branch".into(), branch_id.to_string()),
-        ProcessId::Worker(worker_id) => ("worker".into(), worker_id.to_string()),
+/// Bounds on the channels tracked in [`ApiState`]. Channels with workers or
+/// branches running are kept regardless.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChannelLimits {
+    /// Evict channels that have been idle for longer than this.
+    pub idle_timeout: Option<Duration>,
+    /// Keep at most this many channels, evicting the least recently active.
+    pub max_channels: Option<usize>,
+}
+
+/// When each tracked channel was last active.
+#[derive(Debug, Default)]
+pub struct ChannelActivity {
+    last_active: HashMap<String, Instant>,
+    limits: ChannelLimits,
+}
+
+impl ChannelActivity {
+    /// Record activity on a channel. Returns true when it wasn't tracked,
+    /// so its state needs registering.
+    fn touch(&mut self, channel_id: &str, now: Instant) -> bool {
+        self.last_active
+            .insert(channel_id.to_string(), now)
+            .is_none()
+    }
+
+    /// Channels other than `current` that are past the limits: idle longer
+    /// than the idle timeout, or beyond `max_channels` counting from the most
+    /// recently active.
+    fn expired(&self, now: Instant, current: &str) -> Vec<String> {
+        let mut by_recency: Vec<(&String, &Instant)> = self
+            .last_active
+            .iter()
+            .filter(|(channel_id, _)| channel_id.as_str() != current)
+            .collect();
+        by_recency.sort_by(|left, right| right.1.cmp(left.1));
+        // `current` takes one of the slots.
+        let keep = self
+            .limits
+            .max_channels
+            .map_or(usize::MAX, |max| max.saturating_sub(1));
+
+        by_recency
+            .into_iter()
+            .enumerate()
+            .filter(|(index, (_, last_active))| {
+                *index >= keep
+                    || self
+                        .limits
+                        .idle_timeout
+                        .is_some_and(|timeout| now.duration_since(**last_active) > timeout)
+            })
+            .map(|(_, (channel_id, _))| channel_id.clone())
+            .collect()
+    }
+
+    fn remove(&mut self, channel_id: &str) {
+        self.last_active.remove(channel_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn events_beyond_capacity_lag_subscribers_instead_of_blocking() {
+        let (provider_tx, _provider_rx) = mpsc::channel(1);
+        let (agent_tx, _agent_rx) = mpsc::channel(1);
+        let (agent_remove_tx, _agent_remove_rx) = mpsc::channel(1);
+        let state = ApiState::new_with_provider_sender(provider_tx, agent_tx, agent_remove_tx)
+            .with_event_capacity(4);
+        let mut rx = state.event_tx.subscribe();
+
+        // Nobody reads, yet every send goes through.
+        for _ in 0..10 {
+            state
+                .event_tx
+                .send(ApiEvent::ConfigReloaded)
+                .expect("subscribed");
+        }
+
+        assert!(matches!(
+            rx.recv().await,
+            Err(broadcast::error::RecvError::Lagged(6))
+        ));
+        assert_eq!(rx.recv().await.expect("kept event").sequence, 7);
+    }
+
+    #[test]
+    fn idle_channels_expire_and_are_tracked_again_on_activity() {
+        let mut activity = ChannelActivity {
+            limits: ChannelLimits {
+                idle_timeout: Some(Duration::from_secs(60)),
+                max_channels: None,
+            },
+            ..Default::default()
+        };
+        let start = Instant::now();
+        assert!(activity.touch("quiet", start));
+        assert!(activity.touch("chatty", start));
+        assert!(!activity.touch("chatty", start + Duration::from_secs(30)));
+
+        let later = start + Duration::from_secs(61);
+        assert!(activity.touch("new", later));
+        assert_eq!(activity.expired(later, "new"), ["quiet"]);
+        activity.remove("quiet");
+
+        // The evicted channel is registered afresh on its next event.
+        let next = later + Duration::from_secs(1);
+        assert!(activity.touch("quiet", next));
+        assert!(activity.expired(next, "quiet").is_empty());
+    }
+
+    #[test]
+    fn least_recently_active_channels_expire_beyond_the_limit() {
+        let mut activity = ChannelActivity {
+            limits: ChannelLimits {
+                idle_timeout: None,
+                max_channels: Some(2),
+            },
+            ..Default::default()
+        };
+        let start = Instant::now();
+        for (offset, channel_id) in ["a", "b", "c"].into_iter().enumerate() {
+            activity.touch(channel_id, start + Duration::from_secs(offset as u64));
+        }
+        activity.touch("a", start + Duration::from_secs(3));
+
+        assert_eq!(activity.expired(start + Duration::from_secs(3), "a"), ["b"]);
     }
 }
@@ -1,10 +1,13 @@
+use super::events::{Backlog, DEFAULT_CONNECT_REPLAY, SequencedEvent, Severity};
 use super::state::{ApiEvent, ApiState};
 
 use axum::Json;
-use axum::extract::State;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode, header};
 use axum::response::Sse;
 use futures::stream::Stream;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::convert::Infallible;
 use std::sync::Arc;
 
@@ -62,49 +65,808 @@ pub(super) async fn status(State(state): State<Arc<ApiState>>) -> Json<StatusRes
     })
 }
 
-/// SSE endpoint streaming all agent events to connected clients.
+#[derive(Deserialize)]
+pub(super) struct EventsQuery {
+    /// Only stream events at least this severe (`debug`, `info`, `warn` or
+    /// `error`). Defaults to all of them.
+    min_severity: Option<Severity>,
+    /// Comma-separated ids of the agents whose events to stream. Events that
+    /// belong to no agent, like `config_reloaded`, are still sent.
+    agent_id: Option<String>,
+    /// Comma-separated event `type` tags to stream, e.g.
+    /// `inbound_message,outbound_message`. Defaults to all of them. Unknown
+    /// tags are ignored.
+    types: Option<String>,
+    /// How many of the most recent matching events a client connecting
+    /// without `Last-Event-ID` gets first, so it doesn't start blank.
+    /// Defaults to `DEFAULT_CONNECT_REPLAY`; 0 starts with the live stream.
+    pub(super) replay: Option<usize>,
+}
+
+impl EventsQuery {
+    /// Check the requested types against the `ApiEvent` tags, dropping
+    /// (and logging) the ones no event has, so a client built against a
+    /// newer or older server still gets the types both know.
+    pub(super) fn into_filter(self) -> EventFilter {
+        let types = self.types.map(|types| {
+            types
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .filter_map(|name| {
+                    let known = ApiEvent::TYPES.iter().find(|known| **known == name);
+                    if known.is_none() {
+                        tracing::warn!(name, "event client asked for an unknown event type");
+                    }
+                    known.copied()
+                })
+                .collect::<HashSet<_>>()
+        });
+        let agent_ids = self.agent_id.map(|ids| {
+            ids.split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        EventFilter {
+            min_severity: self.min_severity,
+            agent_ids,
+            types,
+        }
+    }
+}
+
+/// The events one SSE or WebSocket client asked for.
+pub(super) struct EventFilter {
+    min_severity: Option<Severity>,
+    agent_ids: Option<HashSet<String>>,
+    types: Option<HashSet<&'static str>>,
+}
+
+impl EventFilter {
+    pub(super) fn accepts(&self, event: &SequencedEvent) -> bool {
+        self.min_severity
+            .is_none_or(|min_severity| event.severity >= min_severity)
+            && self
+                .types
+                .as_ref()
+                .is_none_or(|types| types.contains(event.event.type_name()))
+            && self.agent_ids.as_ref().is_none_or(|agent_ids| {
+                event
+                    .event
+                    .agent_id()
+                    .is_none_or(|event_agent| agent_ids.contains(event_agent))
+            })
+    }
+}
+
+/// The `api.events_token` of a client that can't send headers.
+#[derive(Deserialize, Default)]
+pub(super) struct TokenQuery {
+    /// Browsers' `EventSource` and `WebSocket` can't set `Authorization`,
+    /// so they pass the token as `?token=` instead.
+    token: Option<String>,
+}
+
+/// Whether a client may open the event stream: `api.events_token` is unset,
+/// or the client sent it as a bearer token or as `?token=`.
+pub(super) fn authorize_events(
+    state: &ApiState,
+    headers: &HeaderMap,
+    query: &TokenQuery,
+) -> Result<(), StatusCode> {
+    let token = state.events_token.load();
+    let Some(token) = token.as_deref() else {
+        return Ok(());
+    };
+    let in_query = query
+        .token
+        .as_deref()
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), token.as_bytes()));
+    if in_query || has_bearer_token(headers, token) {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// SSE endpoint streaming agent events to connected clients, optionally
+/// filtered by agent, type and severity. A client reconnecting with
+/// `Last-Event-ID` first gets the buffered events it missed; a new one gets
+/// the most recent buffered events that pass its filter.
+///
+/// When `api.events_token` is configured, clients that send it neither as a
+/// bearer token nor as `?token=` get 401 and are never subscribed.
 pub(super) async fn events_sse(
     State(state): State<Arc<ApiState>>,
-) -> Sse<impl Stream<Item = Result<axum::response::sse::Event, Infallible>>> {
-    let mut rx = state.event_tx.subscribe();
+    headers: HeaderMap,
+    Query(query): Query<EventsQuery>,
+    Query(token): Query<TokenQuery>,
+) -> Result<Sse<impl Stream<Item = Result<axum::response::sse::Event, Infallible>>>, StatusCode> {
+    authorize_events(&state, &headers, &token)?;
+    let replay = query.replay;
+    let filter = query.into_filter();
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let (backlog, mut rx) = subscribe(&state, &filter, last_event_id, replay);
 
     let stream = async_stream::stream! {
+        if backlog.missed > 0 {
+            yield Ok(gap_event(backlog.missed));
+        }
+        for event in backlog.events.iter().filter(|event| filter.accepts(event)) {
+            if let Some(sse_event) = sse_event(event) {
+                yield Ok(sse_event);
+            }
+        }
         loop {
             match rx.recv().await {
                 Ok(event) => {
-                    if let Ok(json) = serde_json::to_string(&event) {
-                        let event_type = match &event {
-                            ApiEvent::InboundMessage { .. } => "inbound_message",
-                            ApiEvent::OutboundMessage { .. } => "outbound_message",
-                            ApiEvent::TypingState { .. } => "typing_state",
-                            ApiEvent::WorkerStarted { .. } => "worker_started",
-                            ApiEvent::WorkerStatusUpdate { .. } => "worker_status",
-                            ApiEvent::WorkerCompleted { .. } => "worker_completed",
-                            ApiEvent::BranchStarted { .. } => "branch_started",
-                            ApiEvent::BranchCompleted { .. } => "branch_completed",
-                            ApiEvent::ToolStarted { .. } => "tool_started",
-                            ApiEvent::ToolCompleted { .. } => "tool_completed",
-                            ApiEvent::ConfigReloaded => "config_reloaded",
-                        };
-                        yield Ok(axum::response::sse::Event::default()
-                            .event(event_type)
-                            .data(json));
+                    if !filter.accepts(&event) {
+                        continue;
+                    }
+                    if let Some(sse_event) = sse_event(&event) {
+                        yield Ok(sse_event);
                     }
                 }
                 Err(tokio::sync::broadcast::error::RecvError::Lagged(count)) => {
                     tracing::debug!(count, "SSE client lagged");
-                    yield Ok(axum::response::sse::Event::default()
-                        .event("lagged")
-                        .data(format!("{{\"skipped\":{count}}}")));
+                    #[cfg(feature = "metrics")]
+                    crate::telemetry::Metrics::global()
+                        .sse_events_dropped_total
+                        .inc_by(count);
+                    yield Ok(gap_event(count));
                 }
                 Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
             }
         }
     };
 
-    Sse::new(stream).keep_alive(
-        axum::response::sse::KeepAlive::new()
-            .interval(std::time::Duration::from_secs(15))
-            .text("ping"),
+    Ok(Sse::new(stream).keep_alive(keep_alive(**state.sse_keepalive.load())))
+}
+
+/// Subscribe a client to the event stream, with the buffered events it
+/// should get first: the ones after `last_event_id` when it's resuming, else
+/// the `replay` (default [`DEFAULT_CONNECT_REPLAY`]) most recent ones that
+/// pass `filter`.
+pub(super) fn subscribe(
+    state: &ApiState,
+    filter: &EventFilter,
+    last_event_id: Option<u64>,
+    replay: Option<usize>,
+) -> (
+    Backlog,
+    tokio::sync::broadcast::Receiver<Arc<SequencedEvent>>,
+) {
+    let replay = replay.unwrap_or(DEFAULT_CONNECT_REPLAY);
+    match last_event_id {
+        Some(last_event_id) => state.event_tx.subscribe_after(last_event_id),
+        None if replay > 0 => {
+            let (mut backlog, rx) = state.event_tx.subscribe_after(0);
+            backlog.events.retain(|event| filter.accepts(event));
+            let excess = backlog.events.len().saturating_sub(replay);
+            backlog.events.drain(..excess);
+            // History older than the buffer isn't a gap to a new client.
+            backlog.missed = 0;
+            (backlog, rx)
+        }
+        None => (Backlog::default(), state.event_tx.subscribe()),
+    }
+}
+
+/// Sends a `:keepalive` comment whenever the stream has been silent for
+/// `interval`. Comments aren't events, so clients never see them as data.
+fn keep_alive(interval: std::time::Duration) -> axum::response::sse::KeepAlive {
+    axum::response::sse::KeepAlive::new()
+        .interval(interval)
+        .text("keepalive")
+}
+
+fn sse_event(event: &SequencedEvent) -> Option<axum::response::sse::Event> {
+    let json = event.json.as_deref()?;
+    Some(
+        axum::response::sse::Event::default()
+            .id(event.sequence.to_string())
+            .event(event_name(&event.event))
+            .data(json),
     )
 }
+
+/// An [`ApiEvent::Gap`] for one client. It has no id, so a client that
+/// reconnects right after it still resumes from the last real event.
+fn gap_event(skipped: u64) -> axum::response::sse::Event {
+    let gap = ApiEvent::Gap { skipped };
+    axum::response::sse::Event::default()
+        .event(event_name(&gap))
+        .data(serde_json::to_string(&gap).unwrap_or_default())
+}
+
+/// SSE event name for an API event. Replayed events use the name of the
+/// event they carry so clients render them like live ones.
+fn event_name(event: &ApiEvent) -> &'static str {
+    match event {
+        ApiEvent::InboundMessage { .. } => "inbound_message",
+        ApiEvent::OutboundMessage { .. } => "outbound_message",
+        ApiEvent::TypingState { .. } => "typing_state",
+        ApiEvent::WorkerStarted { .. } => "worker_started",
+        ApiEvent::WorkerStatusUpdate { .. } => "worker_status",
+        ApiEvent::WorkerCompleted { .. } => "worker_completed",
+        ApiEvent::BranchStarted { .. } => "branch_started",
+        ApiEvent::BranchCompleted { .. } => "branch_completed",
+        ApiEvent::ToolStarted { .. } => "tool_started",
+        ApiEvent::ToolCompleted { .. } => "tool_completed",
+        ApiEvent::ShellOutput { .. } => "shell_output",
+        ApiEvent::ReminderDue { .. } => "reminder_due",
+        ApiEvent::PolicyViolation { .. } => "policy_violation",
+        ApiEvent::ConfigReloaded => "config_reloaded",
+        ApiEvent::Gap { .. } => "gap",
+        ApiEvent::Replayed { event } => event_name(event),
+    }
+}
+
+/// Most events a single replay request may inject.
+const MAX_REPLAY_EVENTS: usize = 1000;
+
+#[derive(Serialize)]
+pub(super) struct ReplayResponse {
+    replayed: usize,
+}
+
+/// Debug endpoint: broadcast a captured sequence of API events to connected
+/// SSE clients, each marked as replayed. Takes a bare array of events, sent
+/// at their default severity, or a manifest from [`event_manifest`], sent in
+/// its recorded order and severities.
+///
+/// Disabled (404) unless `api.debug_replay_token` is configured, and every
+/// request must carry that token as a bearer token.
+pub(super) async fn replay_events(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+    Json(request): Json<ReplayRequest>,
+) -> Result<Json<ReplayResponse>, StatusCode> {
+    authorize_debug(&state, &headers)?;
+
+    let events: Vec<(ApiEvent, Severity)> = match request {
+        ReplayRequest::Events(events) => events
+            .into_iter()
+            .map(|event| {
+                let severity = event.default_severity();
+                (event, severity)
+            })
+            .collect(),
+        ReplayRequest::Manifest(mut manifest) => {
+            manifest.events.sort_by_key(|entry| entry.sequence);
+            manifest
+                .events
+                .into_iter()
+                .map(|entry| (entry.event, entry.severity))
+                .collect()
+        }
+    };
+    if events.len() > MAX_REPLAY_EVENTS {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    if events
+        .iter()
+        .any(|(event, _)| matches!(event, ApiEvent::Replayed { .. } | ApiEvent::Gap { .. }))
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let replayed = events.len();
+    tracing::warn!(replayed, "replaying debug events into the SSE stream");
+    for (event, severity) in events {
+        let _ = state.event_tx.send_with_severity(
+            ApiEvent::Replayed {
+                event: Box::new(event),
+            },
+            severity,
+        );
+    }
+
+    Ok(Json(ReplayResponse { replayed }))
+}
+
+/// Body of a replay request.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(super) enum ReplayRequest {
+    Events(Vec<ApiEvent>),
+    Manifest(EventManifest),
+}
+
+/// A recorded span of the event stream, in a form the replay endpoint takes
+/// back to reproduce it.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EventManifest {
+    /// The agent the recording was limited to, if any.
+    #[serde(default)]
+    agent_id: Option<String>,
+    /// The channel the recording was limited to, if any.
+    #[serde(default)]
+    channel_id: Option<String>,
+    /// The recorded events in stream order.
+    events: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ManifestEntry {
+    sequence: u64,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    severity: Severity,
+    event: ApiEvent,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ManifestQuery {
+    agent_id: Option<String>,
+    channel_id: Option<String>,
+    /// Only record events after this sequence number.
+    after: Option<u64>,
+    /// Only record events up to and including this sequence number.
+    until: Option<u64>,
+}
+
+/// Debug endpoint: download the events still buffered for replay (see
+/// `api.event_replay_buffer`) as a manifest, optionally limited to an agent,
+/// a channel and a span of sequence numbers. Events that were themselves
+/// replayed aren't recorded. Guarded like [`replay_events`].
+pub(super) async fn event_manifest(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+    Query(query): Query<ManifestQuery>,
+) -> Result<([(header::HeaderName, &'static str); 1], Json<EventManifest>), StatusCode> {
+    authorize_debug(&state, &headers)?;
+
+    let events = state
+        .event_tx
+        .buffered()
+        .into_iter()
+        .filter(|recorded| {
+            !matches!(recorded.event, ApiEvent::Replayed { .. })
+                && query.after.is_none_or(|after| recorded.sequence > after)
+                && query.until.is_none_or(|until| recorded.sequence <= until)
+                && query
+                    .agent_id
+                    .as_deref()
+                    .is_none_or(|agent_id| recorded.event.agent_id() == Some(agent_id))
+                && query
+                    .channel_id
+                    .as_deref()
+                    .is_none_or(|channel_id| recorded.event.channel_id() == Some(channel_id))
+        })
+        .map(|recorded| ManifestEntry {
+            sequence: recorded.sequence,
+            timestamp: recorded.timestamp,
+            severity: recorded.severity,
+            event: recorded.event.clone(),
+        })
+        .collect();
+
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"events-manifest.json\"",
+        )],
+        Json(EventManifest {
+            agent_id: query.agent_id,
+            channel_id: query.channel_id,
+            events,
+        }),
+    ))
+}
+
+/// Check a debug request's bearer token: 404 when the debug endpoints are
+/// disabled, 401 when the token is missing or wrong.
+fn authorize_debug(state: &ApiState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let token = state.debug_replay_token.load();
+    let Some(token) = token.as_deref() else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    if !has_bearer_token(headers, token) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(())
+}
+
+/// Whether the request's `Authorization` header carries `token` as a bearer
+/// token.
+pub(super) fn has_bearer_token(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), token.as_bytes()))
+}
+
+/// Compare secrets without short-circuiting on the first differing byte.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::EventSequencer;
+
+    #[tokio::test(start_paused = true)]
+    async fn silent_streams_get_keepalive_comments() {
+        use axum::response::IntoResponse as _;
+        use futures::StreamExt as _;
+
+        let silence = futures::stream::pending::<Result<axum::response::sse::Event, Infallible>>();
+        let response = Sse::new(silence)
+            .keep_alive(keep_alive(std::time::Duration::from_secs(15)))
+            .into_response();
+        let mut body = response.into_body().into_data_stream();
+
+        let started = tokio::time::Instant::now();
+        for _ in 0..2 {
+            let chunk = body
+                .next()
+                .await
+                .expect("stream stays open")
+                .expect("readable");
+            assert_eq!(&chunk[..], b":keepalive\n\n");
+        }
+        assert_eq!(started.elapsed(), std::time::Duration::from_secs(30));
+    }
+
+    fn parse_filter(uri: &axum::http::Uri) -> EventFilter {
+        let Query(query) = Query::<EventsQuery>::try_from_uri(uri).expect("valid query");
+        query.into_filter()
+    }
+
+    #[test]
+    fn events_are_filtered_by_agent_and_type() {
+        let event = |sequence, event| SequencedEvent::new(sequence, Severity::Info, event);
+        let inbound = |agent_id: &str| ApiEvent::InboundMessage {
+            agent_id: agent_id.into(),
+            channel_id: "discord:1".into(),
+            sender_name: None,
+            sender_id: "user".into(),
+            text: "hi".into(),
+        };
+        let main_inbound = event(1, inbound("main"));
+        let other_inbound = event(2, inbound("other"));
+        let reloaded = event(3, ApiEvent::ConfigReloaded);
+        let replayed = event(
+            4,
+            ApiEvent::Replayed {
+                event: Box::new(inbound("main")),
+            },
+        );
+
+        let uri: axum::http::Uri =
+            "/api/events?agent_id=main&types=inbound_message,%20config_reloaded"
+                .parse()
+                .expect("valid uri");
+        let filter = parse_filter(&uri);
+        assert!(filter.accepts(&main_inbound));
+        assert!(!filter.accepts(&other_inbound));
+        assert!(filter.accepts(&reloaded));
+        assert!(filter.accepts(&replayed));
+
+        let uri: axum::http::Uri = "/api/events?agent_id=main,%20other"
+            .parse()
+            .expect("valid uri");
+        let filter = parse_filter(&uri);
+        assert!(filter.accepts(&main_inbound));
+        assert!(filter.accepts(&other_inbound));
+        assert!(!filter.accepts(&event(5, inbound("third"))));
+
+        let uri: axum::http::Uri = "/api/events?types=outbound_message"
+            .parse()
+            .expect("valid uri");
+        assert!(!parse_filter(&uri).accepts(&main_inbound));
+
+        // Unknown types are dropped; the known ones still apply.
+        let uri: axum::http::Uri = "/api/events?types=inbound_mesage,config_reloaded"
+            .parse()
+            .expect("valid uri");
+        let filter = parse_filter(&uri);
+        assert!(!filter.accepts(&main_inbound));
+        assert!(filter.accepts(&reloaded));
+
+        // The tags filtered on are the ones the events serialize with.
+        for item in [&main_inbound, &reloaded] {
+            let json = serde_json::to_value(&item.event).expect("serializable");
+            assert_eq!(json["type"], item.event.type_name());
+            assert!(ApiEvent::TYPES.contains(&item.event.type_name()));
+        }
+    }
+
+    #[tokio::test]
+    async fn min_severity_filters_out_less_severe_events() {
+        let sequencer = EventSequencer::new(8);
+        let mut rx = sequencer.subscribe();
+        sequencer
+            .send(ApiEvent::TypingState {
+                agent_id: "main".into(),
+                channel_id: "discord:1".into(),
+                is_typing: true,
+            })
+            .expect("subscribed");
+        sequencer
+            .send(ApiEvent::PolicyViolation {
+                agent_id: "main".into(),
+                channel_id: Some("discord:1".into()),
+                process_type: "worker".into(),
+                process_id: "w1".into(),
+                tool_name: "shell".into(),
+                reason: "Tool call blocked: arguments contained a secret.".into(),
+                terminated: false,
+            })
+            .expect("subscribed");
+        let typing = rx.recv().await.expect("typing event");
+        let violation = rx.recv().await.expect("policy violation");
+
+        let uri: axum::http::Uri = "/api/events?min_severity=warn".parse().expect("valid uri");
+        let filter = parse_filter(&uri);
+        assert!(!filter.accepts(&typing));
+        assert!(filter.accepts(&violation));
+
+        let uri: axum::http::Uri = "/api/events".parse().expect("valid uri");
+        assert!(parse_filter(&uri).accepts(&typing));
+
+        let json: serde_json::Value =
+            serde_json::from_str(violation.json.as_deref().expect("serializable"))
+                .expect("valid json");
+        assert_eq!(json["type"], "policy_violation");
+        assert_eq!(json["severity"], "warn");
+        assert_eq!(json["id"], 2);
+    }
+
+    #[tokio::test]
+    async fn recorded_manifests_replay_the_same_sequence() {
+        let (provider_tx, _provider_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_tx, _agent_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_remove_tx, _agent_remove_rx) = tokio::sync::mpsc::channel(1);
+        let state = Arc::new(ApiState::new_with_provider_sender(
+            provider_tx,
+            agent_tx,
+            agent_remove_tx,
+        ));
+        state.set_debug_replay_token(Some("token".into()));
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            "Bearer token".parse().expect("valid header"),
+        );
+
+        let message = |agent_id: &str, text: &str| ApiEvent::OutboundMessage {
+            agent_id: agent_id.into(),
+            channel_id: "discord:1".into(),
+            text: text.into(),
+        };
+        state.send_event(message("main", "first"));
+        state.send_event(message("other", "elsewhere"));
+        state
+            .event_tx
+            .send_with_severity(message("main", "second"), Severity::Warn)
+            .ok();
+        state.send_event(ApiEvent::TypingState {
+            agent_id: "main".into(),
+            channel_id: "discord:1".into(),
+            is_typing: false,
+        });
+
+        let uri: axum::http::Uri = "/api/debug/manifest?agent_id=main&until=3"
+            .parse()
+            .expect("valid uri");
+        let Query(query) = Query::<ManifestQuery>::try_from_uri(&uri).expect("valid query");
+        let (_, Json(manifest)) =
+            event_manifest(State(state.clone()), headers.clone(), Query(query))
+                .await
+                .expect("manifest is recorded");
+        let recorded: Vec<_> = manifest
+            .events
+            .iter()
+            .map(|entry| (entry.sequence, entry.severity))
+            .collect();
+        assert_eq!(recorded, [(1, Severity::Info), (3, Severity::Warn)]);
+
+        // Round-trip through JSON, as a downloaded manifest would be.
+        let body = serde_json::to_string(&manifest).expect("serializable");
+        let request: ReplayRequest = serde_json::from_str(&body).expect("manifest is accepted");
+        let mut rx = state.event_tx.subscribe();
+        let Json(response) = replay_events(State(state.clone()), headers.clone(), Json(request))
+            .await
+            .expect("manifest is replayed");
+        assert_eq!(response.replayed, 2);
+
+        for entry in &manifest.events {
+            let replayed = rx.recv().await.expect("replayed event");
+            assert_eq!(replayed.severity, entry.severity);
+            let ApiEvent::Replayed { event } = &replayed.event else {
+                panic!("expected a replayed event, got {:?}", replayed.event);
+            };
+            assert_eq!(
+                serde_json::to_value(event.as_ref()).expect("serializable"),
+                serde_json::to_value(&entry.event).expect("serializable")
+            );
+        }
+
+        // Replayed events are left out of later recordings.
+        let (_, Json(manifest)) = event_manifest(
+            State(state),
+            headers,
+            Query(ManifestQuery {
+                agent_id: None,
+                channel_id: None,
+                after: Some(4),
+                until: None,
+            }),
+        )
+        .await
+        .expect("manifest is recorded");
+        assert!(manifest.events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn lagging_clients_get_a_gap_marker() {
+        use axum::response::IntoResponse as _;
+        use futures::StreamExt as _;
+
+        let (provider_tx, _provider_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_tx, _agent_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_remove_tx, _agent_remove_rx) = tokio::sync::mpsc::channel(1);
+        let state = Arc::new(ApiState::new_with_provider_sender(
+            provider_tx,
+            agent_tx,
+            agent_remove_tx,
+        ));
+        let uri: axum::http::Uri = "/api/events".parse().expect("valid uri");
+        let Query(query) = Query::<EventsQuery>::try_from_uri(&uri).expect("valid query");
+        let response = events_sse(
+            State(state.clone()),
+            HeaderMap::new(),
+            Query(query),
+            Query(TokenQuery::default()),
+        )
+        .await
+        .expect("stream opens")
+        .into_response();
+        let mut body = response.into_body().into_data_stream();
+
+        // The client subscribed but reads nothing while 600 events go past a
+        // channel that holds 512.
+        for _ in 0..600 {
+            state.send_event(ApiEvent::ConfigReloaded);
+        }
+
+        let chunk = body
+            .next()
+            .await
+            .expect("stream is open")
+            .expect("readable");
+        let chunk = String::from_utf8(chunk.to_vec()).expect("utf-8");
+        assert!(chunk.starts_with("event: gap\n"), "{chunk}");
+        assert!(!chunk.contains("id:"), "{chunk}");
+        assert!(chunk.contains(r#"{"type":"gap","skipped":88}"#), "{chunk}");
+
+        let chunk = body
+            .next()
+            .await
+            .expect("stream is open")
+            .expect("readable");
+        let chunk = String::from_utf8(chunk.to_vec()).expect("utf-8");
+        assert!(chunk.contains("id: 89\n"), "{chunk}");
+    }
+
+    #[tokio::test]
+    async fn new_clients_get_recent_matching_events_first() {
+        use axum::response::IntoResponse as _;
+        use futures::FutureExt as _;
+        use futures::StreamExt as _;
+
+        let (provider_tx, _provider_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_tx, _agent_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_remove_tx, _agent_remove_rx) = tokio::sync::mpsc::channel(1);
+        let state = Arc::new(ApiState::new_with_provider_sender(
+            provider_tx,
+            agent_tx,
+            agent_remove_tx,
+        ));
+        // Nobody is connected yet; the events are only buffered.
+        for (agent_id, text) in [("main", "1"), ("other", "2"), ("main", "3"), ("main", "4")] {
+            state.send_event(ApiEvent::OutboundMessage {
+                agent_id: agent_id.into(),
+                channel_id: "discord:1".into(),
+                text: text.into(),
+            });
+        }
+        let open = |uri: &'static str| {
+            let uri: axum::http::Uri = uri.parse().expect("valid uri");
+            let Query(query) = Query::<EventsQuery>::try_from_uri(&uri).expect("valid query");
+            events_sse(
+                State(state.clone()),
+                HeaderMap::new(),
+                Query(query),
+                Query(TokenQuery::default()),
+            )
+        };
+
+        let response = open("/api/events?agent_id=main&replay=2")
+            .await
+            .expect("stream opens")
+            .into_response();
+        let mut body = response.into_body().into_data_stream();
+        for id in [3, 4] {
+            let chunk = body
+                .next()
+                .await
+                .expect("stream is open")
+                .expect("readable");
+            let chunk = String::from_utf8(chunk.to_vec()).expect("utf-8");
+            assert!(chunk.contains(&format!("id: {id}\n")), "{chunk}");
+        }
+        assert!(body.next().now_or_never().is_none());
+
+        let response = open("/api/events?replay=0")
+            .await
+            .expect("stream opens")
+            .into_response();
+        let mut body = response.into_body().into_data_stream();
+        assert!(body.next().now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn events_token_is_required_when_configured() {
+        let (provider_tx, _provider_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_tx, _agent_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_remove_tx, _agent_remove_rx) = tokio::sync::mpsc::channel(1);
+        let state = Arc::new(ApiState::new_with_provider_sender(
+            provider_tx,
+            agent_tx,
+            agent_remove_tx,
+        ));
+        let open = |headers: HeaderMap, uri: &str| {
+            let uri: axum::http::Uri = uri.parse().expect("valid uri");
+            let Query(query) = Query::<EventsQuery>::try_from_uri(&uri).expect("valid query");
+            let token = Query::<TokenQuery>::try_from_uri(&uri).expect("valid query");
+            events_sse(State(state.clone()), headers, Query(query), token)
+        };
+        let bearer = |token: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                header::AUTHORIZATION,
+                format!("Bearer {token}").parse().expect("valid header"),
+            );
+            headers
+        };
+
+        assert!(open(HeaderMap::new(), "/api/events").await.is_ok());
+
+        state.set_events_token(Some("secret".into()));
+        assert_eq!(
+            open(HeaderMap::new(), "/api/events").await.err(),
+            Some(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            open(bearer("wrong"), "/api/events").await.err(),
+            Some(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            open(HeaderMap::new(), "/api/events?token=wrong")
+                .await
+                .err(),
+            Some(StatusCode::UNAUTHORIZED)
+        );
+        assert!(open(bearer("secret"), "/api/events").await.is_ok());
+        // How the dashboard's EventSource sends it.
+        assert!(
+            open(HeaderMap::new(), "/api/events?replay=0&token=secret")
+                .await
+                .is_ok()
+        );
+    }
+}
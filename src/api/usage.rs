@@ -0,0 +1,127 @@
+//! Estimated LLM spend, from the token usage tracked since startup.
+
+use super::state::ApiState;
+use crate::llm::TokenUsage;
+use crate::llm::usage::ModelPrice;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+pub(super) struct UsageCostResponse {
+    /// When counting started: the time the process started.
+    since: String,
+    window_secs: u64,
+    currency: &'static str,
+    /// Summed over every agent. Models without a price count as nothing.
+    total_cost: f64,
+    agents: HashMap<String, AgentCost>,
+}
+
+#[derive(Serialize, Debug)]
+struct AgentCost {
+    cost: f64,
+    models: HashMap<String, ModelCost>,
+}
+
+#[derive(Serialize, Debug)]
+struct ModelCost {
+    #[serde(flatten)]
+    usage: TokenUsage,
+    /// `None` when the model has no known price.
+    cost: Option<f64>,
+    price_unknown: bool,
+}
+
+/// Estimate what each agent has spent on LLM calls since startup.
+pub(super) async fn usage_cost(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<UsageCostResponse>, StatusCode> {
+    let llm_manager = state.llm_manager.read().await.clone().ok_or_else(|| {
+        tracing::error!("LLM manager not available");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let agents = agent_costs(llm_manager.usage_snapshot(), |model| {
+        llm_manager.model_price(model)
+    });
+    let elapsed = state.started_at.elapsed();
+    let since = chrono::Utc::now() - chrono::Duration::from_std(elapsed).unwrap_or_default();
+
+    Ok(Json(UsageCostResponse {
+        since: since.to_rfc3339(),
+        window_secs: elapsed.as_secs(),
+        currency: "USD",
+        total_cost: agents.values().map(|agent| agent.cost).sum(),
+        agents,
+    }))
+}
+
+fn agent_costs(
+    snapshot: HashMap<String, HashMap<String, TokenUsage>>,
+    price: impl Fn(&str) -> Option<ModelPrice>,
+) -> HashMap<String, AgentCost> {
+    snapshot
+        .into_iter()
+        .map(|(agent_id, models)| {
+            let models: HashMap<String, ModelCost> = models
+                .into_iter()
+                .map(|(model, usage)| {
+                    let cost = price(&model).map(|price| usage.cost(&price));
+                    let model_cost = ModelCost {
+                        usage,
+                        cost,
+                        price_unknown: cost.is_none(),
+                    };
+                    (model, model_cost)
+                })
+                .collect();
+            let cost = models.values().filter_map(|model| model.cost).sum();
+            (agent_id, AgentCost { cost, models })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_models_are_flagged_not_dropped() {
+        let usage = |input_tokens, output_tokens| TokenUsage {
+            requests: 1,
+            input_tokens,
+            cached_input_tokens: 0,
+            output_tokens,
+        };
+        let snapshot = HashMap::from([(
+            "main".to_string(),
+            HashMap::from([
+                (
+                    "anthropic/claude-sonnet-4".to_string(),
+                    usage(1_000_000, 100_000),
+                ),
+                ("ollama/llama3".to_string(), usage(5_000, 500)),
+            ]),
+        )]);
+
+        let agents = agent_costs(snapshot, |model| {
+            crate::llm::usage::model_price(model, &HashMap::new())
+        });
+        let main = &agents["main"];
+        assert_eq!(main.cost, 4.5);
+
+        let sonnet = &main.models["anthropic/claude-sonnet-4"];
+        assert_eq!((sonnet.cost, sonnet.price_unknown), (Some(4.5), false));
+        let llama = &main.models["ollama/llama3"];
+        assert_eq!((llama.cost, llama.price_unknown), (None, true));
+
+        let json = serde_json::to_value(llama).unwrap();
+        assert_eq!(json["input_tokens"], 5_000);
+        assert_eq!(json["price_unknown"], true);
+    }
+}
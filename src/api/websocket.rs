@@ -0,0 +1,351 @@
+//! WebSocket transport for the event stream, for clients behind proxies that
+//! buffer SSE or that want to send commands over the same connection.
+//!
+//! A client opens `GET /api/ws` and sends a `subscribe` frame carrying the
+//! filters `/api/events` takes as query parameters. Every matching
+//! `ApiEvent` then arrives as one JSON text frame, the same JSON the SSE
+//! stream sends as event data. A `send_message` frame sends a message into
+//! a channel as the agent, like `POST
+//! /api/agents/{agent_id}/channels/{channel_id}/send`, and is answered with a
+//! `send_result` or `error` frame.
+//!
+//! Like `/api/events`, the upgrade needs `api.events_token` when one is set,
+//! as a bearer token or as `?token=`. `send_message` needs the admin token
+//! as a bearer token on the upgrade request; it is checked against the
+//! current admin token on every frame, so rotating or removing the token
+//! also cuts off sockets opened with the old one.
+
+use super::channels::SendMessageResponse;
+use super::events::SequencedEvent;
+use super::state::{ApiEvent, ApiState};
+use super::system::{EventFilter, EventsQuery, TokenQuery};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::Response;
+use futures::{SinkExt as _, StreamExt as _};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Largest frame a client may send. Frames are commands, not uploads.
+const MAX_CLIENT_FRAME_BYTES: usize = 64 * 1024;
+
+/// A frame from the client, tagged by `type`.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientFrame {
+    /// Start streaming events, filtered like `/api/events`: `agent_id`,
+    /// `types`, `min_severity` and `replay`.
+    Subscribe(EventsQuery),
+    /// Send `text` into a channel as `agent_id`. Needs the current admin
+    /// token on the upgrade request.
+    SendMessage {
+        /// Echoed in the reply, so a client can tell which request it
+        /// answers.
+        request_id: Option<String>,
+        agent_id: String,
+        channel_id: String,
+        text: String,
+    },
+}
+
+/// A reply to a client frame. Events are sent as their own JSON instead.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerFrame {
+    Subscribed,
+    SendResult {
+        request_id: Option<String>,
+        #[serde(flatten)]
+        response: SendMessageResponse,
+    },
+    Error {
+        request_id: Option<String>,
+        /// The HTTP status the same request would get from the REST API.
+        status: u16,
+        message: String,
+    },
+}
+
+impl ServerFrame {
+    fn error(request_id: Option<String>, status: StatusCode, message: impl Into<String>) -> Self {
+        Self::Error {
+            request_id,
+            status: status.as_u16(),
+            message: message.into(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// The filter and stream of a subscribed client.
+type Subscription = (EventFilter, broadcast::Receiver<Arc<SequencedEvent>>);
+
+/// One client connection and the credentials it presented on upgrade.
+struct Connection {
+    state: Arc<ApiState>,
+    /// The `Authorization` header of the upgrade request, checked against
+    /// the admin token on each `send_message`.
+    credentials: HeaderMap,
+    subscription: Option<Subscription>,
+}
+
+/// Upgrade to a WebSocket carrying the event stream and `send_message`
+/// commands.
+///
+/// When `api.events_token` is configured, clients that send it neither as a
+/// bearer token nor as `?token=` get 401 and are never upgraded.
+pub(super) async fn events_ws(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+    Query(token): Query<TokenQuery>,
+) -> Result<Response, StatusCode> {
+    super::system::authorize_events(&state, &headers, &token)?;
+    let mut credentials = HeaderMap::new();
+    if let Some(value) = headers.get(header::AUTHORIZATION) {
+        credentials.insert(header::AUTHORIZATION, value.clone());
+    }
+    let connection = Connection {
+        state,
+        credentials,
+        subscription: None,
+    };
+    Ok(ws
+        .max_message_size(MAX_CLIENT_FRAME_BYTES)
+        .on_upgrade(move |socket| serve(socket, connection)))
+}
+
+async fn serve(socket: WebSocket, mut connection: Connection) {
+    let (mut sink, mut frames) = socket.split();
+    loop {
+        let outgoing = tokio::select! {
+            frame = frames.next() => match frame {
+                Some(Ok(Message::Text(text))) => connection.handle_frame(text.as_str()).await,
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                // Pings are answered by axum; binary frames mean nothing here.
+                Some(Ok(_)) => continue,
+            },
+            event = next_event(&mut connection.subscription) => match event {
+                Ok(event) => connection.event_frame(&event).into_iter().collect(),
+                Err(broadcast::error::RecvError::Lagged(count)) => {
+                    tracing::debug!(count, "WebSocket client lagged");
+                    #[cfg(feature = "metrics")]
+                    crate::telemetry::Metrics::global()
+                        .sse_events_dropped_total
+                        .inc_by(count);
+                    vec![gap_frame(count)]
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+        for json in outgoing {
+            if sink.send(Message::Text(json.into())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// The next event for a subscribed client; never resolves before the client
+/// subscribes.
+async fn next_event(
+    subscription: &mut Option<Subscription>,
+) -> Result<Arc<SequencedEvent>, broadcast::error::RecvError> {
+    match subscription {
+        Some((_, rx)) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// An [`ApiEvent::Gap`] for one client, as on the SSE stream.
+fn gap_frame(skipped: u64) -> String {
+    serde_json::to_string(&ApiEvent::Gap { skipped }).unwrap_or_default()
+}
+
+impl Connection {
+    /// Act on one client frame, returning the frames to send back.
+    async fn handle_frame(&mut self, text: &str) -> Vec<String> {
+        let frame = match serde_json::from_str::<ClientFrame>(text) {
+            Ok(frame) => frame,
+            Err(error) => {
+                let reply = ServerFrame::error(
+                    None,
+                    StatusCode::BAD_REQUEST,
+                    format!("invalid frame: {error}"),
+                );
+                return vec![reply.to_json()];
+            }
+        };
+        match frame {
+            ClientFrame::Subscribe(_) if self.subscription.is_some() => {
+                let reply = ServerFrame::error(None, StatusCode::CONFLICT, "already subscribed");
+                vec![reply.to_json()]
+            }
+            ClientFrame::Subscribe(query) => {
+                let replay = query.replay;
+                let filter = query.into_filter();
+                let (backlog, rx) = super::system::subscribe(&self.state, &filter, None, replay);
+                let mut outgoing = vec![ServerFrame::Subscribed.to_json()];
+                outgoing.extend(
+                    backlog
+                        .events
+                        .iter()
+                        .filter_map(|event| event.json.as_deref().map(str::to_string)),
+                );
+                self.subscription = Some((filter, rx));
+                outgoing
+            }
+            ClientFrame::SendMessage {
+                request_id,
+                agent_id,
+                channel_id,
+                text,
+            } => {
+                let authorized = super::security::authorize_admin(&self.state, &self.credentials);
+                let result = match authorized {
+                    Ok(()) => {
+                        super::channels::send_as_agent(&self.state, agent_id, channel_id, text)
+                            .await
+                    }
+                    Err(status) => Err(status),
+                };
+                let reply = match result {
+                    Ok(response) => ServerFrame::SendResult {
+                        request_id,
+                        response,
+                    },
+                    Err(status) => ServerFrame::error(
+                        request_id,
+                        status,
+                        status.canonical_reason().unwrap_or("request failed"),
+                    ),
+                };
+                vec![reply.to_json()]
+            }
+        }
+    }
+
+    /// `event` as a frame, if it passes the client's filter.
+    fn event_frame(&self, event: &SequencedEvent) -> Option<String> {
+        let (filter, _) = self.subscription.as_ref()?;
+        if !filter.accepts(event) {
+            return None;
+        }
+        event.json.as_deref().map(str::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(state: &Arc<ApiState>, bearer: Option<&str>) -> Connection {
+        let mut credentials = HeaderMap::new();
+        if let Some(token) = bearer {
+            let value = format!("Bearer {token}").parse().expect("valid header");
+            credentials.insert(header::AUTHORIZATION, value);
+        }
+        Connection {
+            state: state.clone(),
+            credentials,
+            subscription: None,
+        }
+    }
+
+    fn test_state() -> Arc<ApiState> {
+        let (provider_tx, _provider_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_tx, _agent_rx) = tokio::sync::mpsc::channel(1);
+        let (agent_remove_tx, _agent_remove_rx) = tokio::sync::mpsc::channel(1);
+        Arc::new(ApiState::new_with_provider_sender(
+            provider_tx,
+            agent_tx,
+            agent_remove_tx,
+        ))
+    }
+
+    async fn send_status(connection: &mut Connection, text: &str) -> serde_json::Value {
+        let frame = serde_json::json!({
+            "type": "send_message",
+            "request_id": "r1",
+            "agent_id": "main",
+            "channel_id": "discord:1",
+            "text": text,
+        });
+        let reply = connection.handle_frame(&frame.to_string()).await;
+        let reply: serde_json::Value = serde_json::from_str(&reply[0]).expect("JSON");
+        assert_eq!(reply["type"], "error");
+        assert_eq!(reply["request_id"], "r1");
+        reply["status"].clone()
+    }
+
+    fn frame_type(json: &str) -> String {
+        let value: serde_json::Value = serde_json::from_str(json).expect("frames are JSON");
+        value["type"]
+            .as_str()
+            .expect("frames are tagged")
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn subscribers_get_filtered_events_and_send_needs_the_admin_token() {
+        let state = test_state();
+        state.set_admin_token(Some("admin".into()));
+        let message = |agent_id: &str, text: &str| ApiEvent::OutboundMessage {
+            agent_id: agent_id.into(),
+            channel_id: "discord:1".into(),
+            text: text.into(),
+        };
+        state.send_event(message("main", "before"));
+        state.send_event(message("other", "elsewhere"));
+
+        let mut connection = connection(&state, None);
+        let outgoing = connection
+            .handle_frame(r#"{"type":"subscribe","agent_id":"main","types":"outbound_message"}"#)
+            .await;
+        // The recent matching event is replayed after the acknowledgement.
+        assert_eq!(outgoing.len(), 2, "{outgoing:?}");
+        assert_eq!(frame_type(&outgoing[0]), "subscribed");
+        assert!(outgoing[1].contains("before"), "{}", outgoing[1]);
+
+        state.send_event(message("other", "filtered out"));
+        state.send_event(message("main", "live"));
+        let mut live = Vec::new();
+        while live.is_empty() {
+            let event = next_event(&mut connection.subscription)
+                .await
+                .expect("subscribed");
+            live.extend(connection.event_frame(&event));
+        }
+        assert!(live[0].contains("live"), "{}", live[0]);
+
+        let again = connection.handle_frame(r#"{"type":"subscribe"}"#).await;
+        assert_eq!(frame_type(&again[0]), "error");
+
+        assert_eq!(send_status(&mut connection, "hi").await, 401);
+
+        let invalid = connection.handle_frame(r#"{"type":"shout"}"#).await;
+        assert_eq!(frame_type(&invalid[0]), "error");
+    }
+
+    #[tokio::test]
+    async fn send_rights_follow_the_current_admin_token() {
+        let state = test_state();
+        state.set_admin_token(Some("old".into()));
+        let mut connection = connection(&state, Some("old"));
+
+        // Past the token check, a blank message is refused on its own merits.
+        assert_eq!(send_status(&mut connection, " ").await, 400);
+
+        state.set_admin_token(Some("new".into()));
+        assert_eq!(send_status(&mut connection, " ").await, 401);
+
+        state.set_admin_token(None);
+        assert_eq!(send_status(&mut connection, " ").await, 404);
+    }
+}
@@ -60,6 +60,33 @@ pub struct ApiConfig {
     pub port: u16,
     /// Address to bind the HTTP server on.
     pub bind: String,
+    /// Bearer token for `POST /api/debug/replay`, which injects events into
+    /// the SSE stream. The endpoint is disabled when unset.
+    pub debug_replay_token: Option<String>,
+    /// Bearer token SSE clients must send to `GET /api/events`. The stream is
+    /// open to anyone who can reach the API when unset.
+    pub events_token: Option<String>,
+    /// Bearer token for the admin endpoints that change an agent's security
+    /// settings. They're disabled when unset.
+    pub admin_token: Option<String>,
+    /// Forward agents' events to SSE clients on this many shared tasks
+    /// instead of one task per agent. None keeps one per agent.
+    pub event_forwarders: Option<usize>,
+    /// How many recent events to keep for SSE clients that reconnect with
+    /// `Last-Event-ID`. None keeps the default of 1000.
+    pub event_replay_buffer: Option<usize>,
+    /// How far a connected SSE client may fall behind the event stream
+    /// before it skips events. None keeps the default of 512.
+    pub event_channel_capacity: Option<usize>,
+    /// Send SSE clients a keepalive comment after this many seconds without
+    /// events. None keeps the default of 15.
+    pub sse_keepalive_secs: Option<u64>,
+    /// Drop a channel's live status and state from the API after it has
+    /// been idle this long. None keeps them as long as the process runs.
+    pub channel_idle_timeout_secs: Option<u64>,
+    /// Track at most this many channels in the API, dropping the least
+    /// recently active. None tracks every channel.
+    pub max_tracked_channels: Option<usize>,
 }
 
 impl Default for ApiConfig {
@@ -68,6 +95,15 @@ impl Default for ApiConfig {
             enabled: true,
             port: 19898,
             bind: "127.0.0.1".into(),
+            debug_replay_token: None,
+            events_token: None,
+            admin_token: None,
+            event_forwarders: None,
+            event_replay_buffer: None,
+            event_channel_capacity: None,
+            sse_keepalive_secs: None,
+            channel_idle_timeout_secs: None,
+            max_tracked_channels: None,
         }
     }
 }
@@ -151,6 +187,9 @@ pub struct LlmConfig {
     pub moonshot_key: Option<String>,
     pub zai_coding_plan_key: Option<String>,
     pub providers: HashMap<String, ProviderConfig>,
+    /// Prices by full model name, used over the built-in ones when
+    /// estimating spend.
+    pub prices: HashMap<String, crate::llm::usage::ModelPrice>,
 }
 
 impl LlmConfig {
@@ -203,6 +242,8 @@ pub struct DefaultsConfig {
     pub ingestion: IngestionConfig,
     pub cortex: CortexConfig,
     pub browser: BrowserConfig,
+    pub shell: ShellConfig,
+    pub web_fetch: WebFetchConfig,
     /// Brave Search API key for web search tool. Supports "env:VAR_NAME" references.
     pub brave_search_key: Option<String>,
     pub history_backfill_count: usize,
@@ -336,6 +377,185 @@ impl Default for BrowserConfig {
     }
 }
 
+/// Limits for the `web_fetch` tool.
+#[derive(Debug, Clone)]
+pub struct WebFetchConfig {
+    /// Whether `web_fetch` is available to workers.
+    pub enabled: bool,
+    /// Hosts requests may go to: `example.com`, or `*.example.com` for its
+    /// subdomains. Empty allows any public host.
+    pub allowed_hosts: Vec<String>,
+    /// Hosts requests may never go to, in the same form. Loopback, private,
+    /// link-local (cloud metadata included) and other non-public addresses
+    /// are refused regardless of either list.
+    pub blocked_hosts: Vec<String>,
+    /// Most bytes of a response body to read. The rest is dropped.
+    pub max_response_bytes: usize,
+    /// Seconds a request may take, redirects included.
+    pub timeout_secs: u64,
+}
+
+impl Default for WebFetchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_hosts: Vec::new(),
+            blocked_hosts: Vec::new(),
+            max_response_bytes: 1_000_000,
+            timeout_secs: 30,
+        }
+    }
+}
+
+/// Shell tool restrictions for workers.
+#[derive(Debug, Clone)]
+pub struct ShellConfig {
+    /// Executables a shell command may invoke. Every segment of a pipeline or
+    /// chain must start with one of these. None allows any command.
+    pub allowed_commands: Option<Vec<String>>,
+    /// Secret values replaced with `[REDACTED]` in command output, on top of
+    /// the runtime values of the well-known secret env vars. Messaging tokens
+    /// from the config file are added automatically.
+    pub redacted_values: Vec<String>,
+    /// Address space limit for each command, in megabytes (`RLIMIT_AS`).
+    /// Unix only; None leaves it unlimited.
+    pub max_memory_mb: Option<u64>,
+    /// CPU time limit for each command, in seconds (`RLIMIT_CPU`). Unix only;
+    /// None leaves it unlimited.
+    pub max_cpu_seconds: Option<u64>,
+    /// Directory commands run in when they don't pass `working_dir`, relative
+    /// to the workspace (e.g. the project root of a checked-out repo). Must
+    /// stay inside the workspace. None runs them in the workspace itself.
+    pub default_working_dir: Option<PathBuf>,
+    /// Append a non-interactive flag (e.g. `-y` for `apt install`) to
+    /// commands known to prompt for input, when that's safe. Off by default;
+    /// such commands otherwise only get a hint when they time out.
+    pub auto_non_interactive_flags: bool,
+    /// Glob patterns for more files commands may not touch, on top of the
+    /// built-in `config.toml`, `.env` and database names (e.g. `*.redb`,
+    /// `secrets/**`).
+    pub sensitive_files: Vec<String>,
+    /// More environment variable names commands may not read, on top of the
+    /// built-in API key and bot token names. Their runtime values are
+    /// redacted from output like the built-in ones.
+    pub secret_env_vars: Vec<String>,
+    /// What to do when a command's working directory doesn't exist.
+    pub missing_working_dir: MissingWorkingDir,
+    /// Most persistent shell sessions (`session_id`) a worker may have open
+    /// at once. 0 disables sessions.
+    pub max_sessions: usize,
+    /// Seconds a session may sit idle before its shell is shut down.
+    pub session_idle_timeout_secs: u64,
+    /// Give the model a generic "shell command failed" error with an ID
+    /// instead of the error's details (paths, rejected patterns), which are
+    /// logged under that ID instead. For less-trusted deployments.
+    pub generic_errors: bool,
+    /// Note how long a command ran when it finished without printing
+    /// anything after at least this many seconds, so the model can tell it
+    /// did work.
+    pub quiet_duration_notice_secs: u64,
+    /// Most commands an agent's workers may run per
+    /// `rate_limit_window_secs`, in bursts of up to that many. None leaves
+    /// commands unlimited.
+    pub rate_limit_commands: Option<u32>,
+    /// The window `rate_limit_commands` refills over.
+    pub rate_limit_window_secs: u64,
+    /// Append every command workers run or have rejected to
+    /// `logs/shell_audit.jsonl` in the instance directory.
+    pub audit_log: bool,
+    /// The shell commands run in (e.g. `/bin/bash`), by path or name on
+    /// `PATH`. None uses `sh`. A program that can't be found when the config
+    /// loads is dropped with a warning. Ignored on Windows, where commands
+    /// always run with `cmd /C`.
+    pub shell_program: Option<String>,
+    /// Arguments passed to `shell_program` before the command. None passes
+    /// `-c`. Ignored without a `shell_program`.
+    pub shell_args: Option<Vec<String>>,
+    /// Which of spacebot's environment variables commands get.
+    pub env_mode: ShellEnvMode,
+}
+
+impl Default for ShellConfig {
+    fn default() -> Self {
+        Self {
+            allowed_commands: None,
+            redacted_values: Vec::new(),
+            max_memory_mb: None,
+            max_cpu_seconds: None,
+            default_working_dir: None,
+            auto_non_interactive_flags: false,
+            sensitive_files: Vec::new(),
+            secret_env_vars: Vec::new(),
+            missing_working_dir: MissingWorkingDir::default(),
+            max_sessions: 4,
+            session_idle_timeout_secs: 600,
+            generic_errors: false,
+            quiet_duration_notice_secs: 5,
+            rate_limit_commands: None,
+            rate_limit_window_secs: 30,
+            audit_log: true,
+            shell_program: None,
+            shell_args: None,
+            env_mode: ShellEnvMode::default(),
+        }
+    }
+}
+
+/// Which of spacebot's own environment variables shell commands get.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ShellEnvMode {
+    /// All of them. Secret ones are kept out of output and commands by
+    /// name checks and redaction.
+    #[default]
+    Inherit,
+    /// `PATH`, `HOME` and these names; nothing else is passed through.
+    Allowlist(Vec<String>),
+    /// None. Commands only get the `PATH` the tool sets.
+    Empty,
+}
+
+impl ShellEnvMode {
+    /// Whether commands get spacebot's value of the variable `name`.
+    pub fn passes(&self, name: &str) -> bool {
+        match self {
+            Self::Inherit => true,
+            Self::Allowlist(names) => {
+                matches!(name, "PATH" | "HOME") || names.iter().any(|allowed| allowed == name)
+            }
+            Self::Empty => false,
+        }
+    }
+}
+
+/// How the shell tool handles a `working_dir` that doesn't exist.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingWorkingDir {
+    /// Reject the command.
+    #[default]
+    Error,
+    /// Create the directory, and any missing parents, first.
+    Create,
+    /// Run in the workspace root instead, with a warning in the output.
+    Workspace,
+}
+
+impl<'de> serde::Deserialize<'de> for MissingWorkingDir {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "error" => Ok(Self::Error),
+            "create" => Ok(Self::Create),
+            "workspace" => Ok(Self::Workspace),
+            other => Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(other),
+                &"one of \"error\", \"create\", or \"workspace\"",
+            )),
+        }
+    }
+}
+
 /// OpenCode subprocess worker configuration.
 #[derive(Debug, Clone)]
 pub struct OpenCodeConfig {
@@ -428,6 +648,8 @@ pub struct AgentConfig {
     pub ingestion: Option<IngestionConfig>,
     pub cortex: Option<CortexConfig>,
     pub browser: Option<BrowserConfig>,
+    pub shell: Option<ShellConfig>,
+    pub web_fetch: Option<WebFetchConfig>,
     /// Per-agent Brave Search API key override. None inherits from defaults.
     pub brave_search_key: Option<String>,
     /// Cron job definitions for this agent.
@@ -469,6 +691,8 @@ pub struct ResolvedAgentConfig {
     pub ingestion: IngestionConfig,
     pub cortex: CortexConfig,
     pub browser: BrowserConfig,
+    pub shell: ShellConfig,
+    pub web_fetch: WebFetchConfig,
     pub brave_search_key: Option<String>,
     /// Number of messages to fetch from the platform when a new channel is created.
     pub history_backfill_count: usize,
@@ -490,6 +714,8 @@ impl Default for DefaultsConfig {
             ingestion: IngestionConfig::default(),
             cortex: CortexConfig::default(),
             browser: BrowserConfig::default(),
+            shell: ShellConfig::default(),
+            web_fetch: WebFetchConfig::default(),
             brave_search_key: None,
             history_backfill_count: 50,
             cron: Vec::new(),
@@ -536,6 +762,11 @@ impl AgentConfig {
                 .browser
                 .clone()
                 .unwrap_or_else(|| defaults.browser.clone()),
+            shell: self.shell.clone().unwrap_or_else(|| defaults.shell.clone()),
+            web_fetch: self
+                .web_fetch
+                .clone()
+                .unwrap_or_else(|| defaults.web_fetch.clone()),
             brave_search_key: self
                 .brave_search_key
                 .clone()
@@ -719,6 +950,27 @@ pub struct MessagingConfig {
     pub twitch: Option<TwitchConfig>,
 }
 
+impl MessagingConfig {
+    /// Every platform token, for redaction from tool output.
+    pub fn secret_values(&self) -> Vec<String> {
+        let mut values = Vec::new();
+        if let Some(discord) = &self.discord {
+            values.push(discord.token.clone());
+        }
+        if let Some(slack) = &self.slack {
+            values.push(slack.bot_token.clone());
+            values.push(slack.app_token.clone());
+        }
+        if let Some(telegram) = &self.telegram {
+            values.push(telegram.token.clone());
+        }
+        if let Some(twitch) = &self.twitch {
+            values.push(twitch.oauth_token.clone());
+        }
+        values
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DiscordConfig {
     pub enabled: bool,
@@ -1050,6 +1302,15 @@ struct TomlApiConfig {
     port: u16,
     #[serde(default = "default_api_bind")]
     bind: String,
+    debug_replay_token: Option<String>,
+    events_token: Option<String>,
+    admin_token: Option<String>,
+    event_forwarders: Option<usize>,
+    event_replay_buffer: Option<usize>,
+    event_channel_capacity: Option<usize>,
+    sse_keepalive_secs: Option<u64>,
+    channel_idle_timeout_secs: Option<u64>,
+    max_tracked_channels: Option<usize>,
 }
 
 impl Default for TomlApiConfig {
@@ -1058,6 +1319,15 @@ impl Default for TomlApiConfig {
             enabled: default_api_enabled(),
             port: default_api_port(),
             bind: default_api_bind(),
+            debug_replay_token: None,
+            events_token: None,
+            admin_token: None,
+            event_forwarders: None,
+            event_replay_buffer: None,
+            event_channel_capacity: None,
+            sse_keepalive_secs: None,
+            channel_idle_timeout_secs: None,
+            max_tracked_channels: None,
         }
     }
 }
@@ -1129,6 +1399,8 @@ struct TomlLlmConfigFields {
     #[serde(default)]
     providers: HashMap<String, TomlProviderConfig>,
     #[serde(default)]
+    prices: HashMap<String, crate::llm::usage::ModelPrice>,
+    #[serde(default)]
     #[serde(flatten)]
     extra: HashMap<String, toml::Value>,
 }
@@ -1153,6 +1425,7 @@ struct TomlLlmConfig {
     moonshot_key: Option<String>,
     zai_coding_plan_key: Option<String>,
     providers: HashMap<String, TomlProviderConfig>,
+    prices: HashMap<String, crate::llm::usage::ModelPrice>,
 }
 
 impl<'de> Deserialize<'de> for TomlLlmConfig {
@@ -1204,6 +1477,7 @@ impl<'de> Deserialize<'de> for TomlLlmConfig {
             moonshot_key: fields.moonshot_key,
             zai_coding_plan_key: fields.zai_coding_plan_key,
             providers: fields.providers,
+            prices: fields.prices,
         })
     }
 }
@@ -1222,6 +1496,8 @@ struct TomlDefaultsConfig {
     ingestion: Option<TomlIngestionConfig>,
     cortex: Option<TomlCortexConfig>,
     browser: Option<TomlBrowserConfig>,
+    shell: Option<TomlShellConfig>,
+    web_fetch: Option<TomlWebFetchConfig>,
     brave_search_key: Option<String>,
     opencode: Option<TomlOpenCodeConfig>,
     worker_log_mode: Option<String>,
@@ -1298,6 +1574,51 @@ struct TomlBrowserConfig {
     screenshot_dir: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct TomlShellConfig {
+    allowed_commands: Option<Vec<String>>,
+    #[serde(default)]
+    redacted_values: Vec<String>,
+    max_memory_mb: Option<u64>,
+    max_cpu_seconds: Option<u64>,
+    default_working_dir: Option<String>,
+    auto_non_interactive_flags: Option<bool>,
+    #[serde(default)]
+    sensitive_files: Vec<String>,
+    #[serde(default)]
+    secret_env_vars: Vec<String>,
+    missing_working_dir: Option<MissingWorkingDir>,
+    max_sessions: Option<usize>,
+    session_idle_timeout_secs: Option<u64>,
+    generic_errors: Option<bool>,
+    quiet_duration_notice_secs: Option<u64>,
+    rate_limit_commands: Option<u32>,
+    rate_limit_window_secs: Option<u64>,
+    audit_log: Option<bool>,
+    shell_program: Option<String>,
+    shell_args: Option<Vec<String>>,
+    env_mode: Option<TomlShellEnvMode>,
+    env_allowlist: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum TomlShellEnvMode {
+    Inherit,
+    Allowlist,
+    Empty,
+}
+
+#[derive(Deserialize)]
+struct TomlWebFetchConfig {
+    enabled: Option<bool>,
+    allowed_hosts: Option<Vec<String>>,
+    #[serde(default)]
+    blocked_hosts: Vec<String>,
+    max_response_bytes: Option<usize>,
+    timeout_secs: Option<u64>,
+}
+
 #[derive(Deserialize)]
 struct TomlOpenCodeConfig {
     enabled: Option<bool>,
@@ -1333,6 +1654,8 @@ struct TomlAgentConfig {
     ingestion: Option<TomlIngestionConfig>,
     cortex: Option<TomlCortexConfig>,
     browser: Option<TomlBrowserConfig>,
+    shell: Option<TomlShellConfig>,
+    web_fetch: Option<TomlWebFetchConfig>,
     brave_search_key: Option<String>,
     #[serde(default)]
     cron: Vec<TomlCronDef>,
@@ -1453,6 +1776,14 @@ fn resolve_env_value(value: &str) -> Option<String> {
     }
 }
 
+/// Whether `name` can be an environment variable a shell command can expand:
+/// letters, digits and underscores, not starting with a digit.
+pub(crate) fn is_env_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 fn parse_otlp_headers(value: Option<String>) -> Result<HashMap<String, String>> {
     let Some(raw) = value else {
         return Ok(HashMap::new());
@@ -1528,6 +1859,54 @@ fn resolve_routing(toml: Option<TomlRoutingConfig>, base: &RoutingConfig) -> Rou
     }
 }
 
+/// The env mode a `[shell]` table asks for. `env_allowlist` on its own
+/// replaces the names of an inherited allowlist mode.
+fn resolve_env_mode(
+    mode: Option<TomlShellEnvMode>,
+    allowlist: Option<Vec<String>>,
+    base: &ShellEnvMode,
+) -> ShellEnvMode {
+    let base_allowlist = match base {
+        ShellEnvMode::Allowlist(names) => Some(names),
+        _ => None,
+    };
+    match mode {
+        Some(TomlShellEnvMode::Inherit) => ShellEnvMode::Inherit,
+        Some(TomlShellEnvMode::Empty) => ShellEnvMode::Empty,
+        Some(TomlShellEnvMode::Allowlist) => ShellEnvMode::Allowlist(
+            allowlist
+                .or_else(|| base_allowlist.cloned())
+                .unwrap_or_default(),
+        ),
+        None => match (base_allowlist, allowlist) {
+            (Some(_), Some(names)) => ShellEnvMode::Allowlist(names),
+            _ => base.clone(),
+        },
+    }
+}
+
+/// Drop `shell.shell_program` (and its arguments) with a warning if it can't
+/// be found, so commands fall back to the default shell instead of all
+/// failing to start.
+fn check_shell_program(shell: &mut ShellConfig, scope: &str) {
+    let Some(program) = shell.shell_program.as_deref() else {
+        return;
+    };
+    if cfg!(target_os = "windows") {
+        tracing::warn!(
+            scope,
+            program,
+            "shell.shell_program is ignored on Windows, using cmd /C"
+        );
+    } else if crate::tools::find_binary(program, std::env::var_os("PATH").as_deref()).is_none() {
+        tracing::warn!(scope, program, "shell.shell_program not found, using sh");
+    } else {
+        return;
+    }
+    shell.shell_program = None;
+    shell.shell_args = None;
+}
+
 impl Config {
     /// Resolve the instance directory from env or default (~/.spacebot).
     pub fn default_instance_dir() -> PathBuf {
@@ -1643,6 +2022,7 @@ impl Config {
             moonshot_key: std::env::var("MOONSHOT_API_KEY").ok(),
             zai_coding_plan_key: std::env::var("ZAI_CODING_PLAN_API_KEY").ok(),
             providers: HashMap::new(),
+            prices: HashMap::new(),
         };
 
         // Populate providers from env vars (same as from_toml does)
@@ -1773,6 +2153,8 @@ impl Config {
             ingestion: None,
             cortex: None,
             browser: None,
+            shell: None,
+            web_fetch: None,
             brave_search_key: None,
             cron: Vec::new(),
         }];
@@ -1836,6 +2218,117 @@ impl Config {
             }
         }
 
+        if toml.api.event_forwarders == Some(0) {
+            return Err(ConfigError::Invalid(
+                "api.event_forwarders must be at least 1".to_string(),
+            )
+            .into());
+        }
+        if toml.api.max_tracked_channels == Some(0) {
+            return Err(ConfigError::Invalid(
+                "api.max_tracked_channels must be at least 1".to_string(),
+            )
+            .into());
+        }
+        if toml.api.event_channel_capacity == Some(0) {
+            return Err(ConfigError::Invalid(
+                "api.event_channel_capacity must be at least 1".to_string(),
+            )
+            .into());
+        }
+        if toml.api.sse_keepalive_secs == Some(0) {
+            return Err(ConfigError::Invalid(
+                "api.sse_keepalive_secs must be at least 1".to_string(),
+            )
+            .into());
+        }
+
+        let shell_configs = toml
+            .defaults
+            .shell
+            .iter()
+            .chain(toml.agents.iter().filter_map(|agent| agent.shell.as_ref()));
+        for pattern in shell_configs.flat_map(|shell| &shell.sensitive_files) {
+            if let Err(e) = globset::Glob::new(pattern) {
+                return Err(ConfigError::Invalid(format!(
+                    "Invalid shell.sensitive_files pattern '{}': {}",
+                    pattern, e
+                ))
+                .into());
+            }
+        }
+        let shell_configs = toml
+            .defaults
+            .shell
+            .iter()
+            .chain(toml.agents.iter().filter_map(|agent| agent.shell.as_ref()));
+        for shell in shell_configs.clone() {
+            if shell.rate_limit_commands == Some(0) || shell.rate_limit_window_secs == Some(0) {
+                return Err(ConfigError::Invalid(
+                    "shell.rate_limit_commands and shell.rate_limit_window_secs must be at least 1"
+                        .to_string(),
+                )
+                .into());
+            }
+        }
+        for name in shell_configs
+            .clone()
+            .flat_map(|shell| &shell.secret_env_vars)
+        {
+            if !is_env_var_name(name) {
+                return Err(ConfigError::Invalid(format!(
+                    "Invalid shell.secret_env_vars name '{}'",
+                    name
+                ))
+                .into());
+            }
+        }
+        for name in shell_configs.flat_map(|shell| shell.env_allowlist.iter().flatten()) {
+            if !is_env_var_name(name) {
+                return Err(ConfigError::Invalid(format!(
+                    "Invalid shell.env_allowlist name '{}'",
+                    name
+                ))
+                .into());
+            }
+        }
+
+        let web_fetch_configs = toml.defaults.web_fetch.iter().chain(
+            toml.agents
+                .iter()
+                .filter_map(|agent| agent.web_fetch.as_ref()),
+        );
+        for web_fetch in web_fetch_configs {
+            if web_fetch.max_response_bytes == Some(0) || web_fetch.timeout_secs == Some(0) {
+                return Err(ConfigError::Invalid(
+                    "web_fetch.max_response_bytes and web_fetch.timeout_secs must be at least 1"
+                        .to_string(),
+                )
+                .into());
+            }
+            let hosts = web_fetch.allowed_hosts.iter().flatten();
+            for host in hosts.chain(&web_fetch.blocked_hosts) {
+                if !crate::tools::web_fetch::is_host_pattern(host) {
+                    return Err(ConfigError::Invalid(format!(
+                        "Invalid web_fetch host '{}': use a hostname like 'example.com' or '*.example.com'",
+                        host
+                    ))
+                    .into());
+                }
+            }
+        }
+
+        for (model, price) in &toml.llm.prices {
+            let valid = |rate: f64| rate.is_finite() && rate >= 0.0;
+            if !valid(price.input) || !valid(price.output) {
+                return Err(ConfigError::Invalid(format!(
+                    "Invalid llm.prices entry for '{}': prices must be non-negative numbers",
+                    model
+                ))
+                .into());
+            }
+        }
+
         let mut llm = LlmConfig {
             anthropic_key: toml
                 .llm
@@ -1956,6 +2449,7 @@ impl Config {
                     )
                 })
                 .collect(),
+            prices: toml.llm.prices,
         };
 
         if let Some(anthropic_key) = llm.anthropic_key.clone() {
@@ -2061,7 +2555,7 @@ impl Config {
         // Agents are initialized later when keys are added via API.
 
         let base_defaults = DefaultsConfig::default();
-        let defaults = DefaultsConfig {
+        let mut defaults = DefaultsConfig {
             routing: resolve_routing(toml.defaults.routing, &base_defaults.routing),
             max_concurrent_branches: toml
                 .defaults
@@ -2189,6 +2683,78 @@ impl Config {
                     }
                 })
                 .unwrap_or_else(|| base_defaults.browser.clone()),
+            shell: toml
+                .defaults
+                .shell
+                .map(|s| ShellConfig {
+                    allowed_commands: s
+                        .allowed_commands
+                        .or_else(|| base_defaults.shell.allowed_commands.clone()),
+                    redacted_values: s
+                        .redacted_values
+                        .iter()
+                        .filter_map(|value| resolve_env_value(value))
+                        .collect(),
+                    max_memory_mb: s.max_memory_mb.or(base_defaults.shell.max_memory_mb),
+                    max_cpu_seconds: s.max_cpu_seconds.or(base_defaults.shell.max_cpu_seconds),
+                    default_working_dir: s
+                        .default_working_dir
+                        .map(PathBuf::from)
+                        .or_else(|| base_defaults.shell.default_working_dir.clone()),
+                    auto_non_interactive_flags: s
+                        .auto_non_interactive_flags
+                        .unwrap_or(base_defaults.shell.auto_non_interactive_flags),
+                    sensitive_files: s.sensitive_files,
+                    secret_env_vars: s.secret_env_vars,
+                    missing_working_dir: s
+                        .missing_working_dir
+                        .unwrap_or(base_defaults.shell.missing_working_dir),
+                    max_sessions: s.max_sessions.unwrap_or(base_defaults.shell.max_sessions),
+                    session_idle_timeout_secs: s
+                        .session_idle_timeout_secs
+                        .unwrap_or(base_defaults.shell.session_idle_timeout_secs),
+                    generic_errors: s
+                        .generic_errors
+                        .unwrap_or(base_defaults.shell.generic_errors),
+                    quiet_duration_notice_secs: s
+                        .quiet_duration_notice_secs
+                        .unwrap_or(base_defaults.shell.quiet_duration_notice_secs),
+                    rate_limit_commands: s
+                        .rate_limit_commands
+                        .or(base_defaults.shell.rate_limit_commands),
+                    rate_limit_window_secs: s
+                        .rate_limit_window_secs
+                        .unwrap_or(base_defaults.shell.rate_limit_window_secs),
+                    audit_log: s.audit_log.unwrap_or(base_defaults.shell.audit_log),
+                    shell_program: s
+                        .shell_program
+                        .or_else(|| base_defaults.shell.shell_program.clone()),
+                    shell_args: s
+                        .shell_args
+                        .or_else(|| base_defaults.shell.shell_args.clone()),
+                    env_mode: resolve_env_mode(
+                        s.env_mode,
+                        s.env_allowlist,
+                        &base_defaults.shell.env_mode,
+                    ),
+                })
+                .unwrap_or_else(|| base_defaults.shell.clone()),
+            web_fetch: toml
+                .defaults
+                .web_fetch
+                .map(|w| {
+                    let base = &base_defaults.web_fetch;
+                    WebFetchConfig {
+                        enabled: w.enabled.unwrap_or(base.enabled),
+                        allowed_hosts: w
+                            .allowed_hosts
+                            .unwrap_or_else(|| base.allowed_hosts.clone()),
+                        blocked_hosts: w.blocked_hosts,
+                        max_response_bytes: w.max_response_bytes.unwrap_or(base.max_response_bytes),
+                        timeout_secs: w.timeout_secs.unwrap_or(base.timeout_secs),
+                    }
+                })
+                .unwrap_or_else(|| base_defaults.web_fetch.clone()),
             brave_search_key: toml
                 .defaults
                 .brave_search_key
@@ -2235,6 +2801,7 @@ impl Config {
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(base_defaults.worker_log_mode),
         };
+        check_shell_program(&mut defaults.shell, "defaults");
 
         let mut agents: Vec<AgentConfig> = toml
             .agents
@@ -2354,11 +2921,103 @@ impl Config {
                             .map(PathBuf::from)
                             .or_else(|| defaults.browser.screenshot_dir.clone()),
                     }),
+                    shell: a.shell.map(|s| ShellConfig {
+                        allowed_commands: s
+                            .allowed_commands
+                            .or_else(|| defaults.shell.allowed_commands.clone()),
+                        // Redaction only ever widens: agent values are added
+                        // to the instance-wide list rather than replacing it.
+                        redacted_values: defaults
+                            .shell
+                            .redacted_values
+                            .iter()
+                            .cloned()
+                            .chain(
+                                s.redacted_values
+                                    .iter()
+                                    .filter_map(|value| resolve_env_value(value)),
+                            )
+                            .collect(),
+                        max_memory_mb: s.max_memory_mb.or(defaults.shell.max_memory_mb),
+                        max_cpu_seconds: s.max_cpu_seconds.or(defaults.shell.max_cpu_seconds),
+                        default_working_dir: s
+                            .default_working_dir
+                            .map(PathBuf::from)
+                            .or_else(|| defaults.shell.default_working_dir.clone()),
+                        auto_non_interactive_flags: s
+                            .auto_non_interactive_flags
+                            .unwrap_or(defaults.shell.auto_non_interactive_flags),
+                        // Like redaction, protection only ever widens.
+                        sensitive_files: defaults
+                            .shell
+                            .sensitive_files
+                            .iter()
+                            .cloned()
+                            .chain(s.sensitive_files)
+                            .collect(),
+                        secret_env_vars: defaults
+                            .shell
+                            .secret_env_vars
+                            .iter()
+                            .cloned()
+                            .chain(s.secret_env_vars)
+                            .collect(),
+                        missing_working_dir: s
+                            .missing_working_dir
+                            .unwrap_or(defaults.shell.missing_working_dir),
+                        max_sessions: s.max_sessions.unwrap_or(defaults.shell.max_sessions),
+                        session_idle_timeout_secs: s
+                            .session_idle_timeout_secs
+                            .unwrap_or(defaults.shell.session_idle_timeout_secs),
+                        generic_errors: s.generic_errors.unwrap_or(defaults.shell.generic_errors),
+                        quiet_duration_notice_secs: s
+                            .quiet_duration_notice_secs
+                            .unwrap_or(defaults.shell.quiet_duration_notice_secs),
+                        rate_limit_commands: s
+                            .rate_limit_commands
+                            .or(defaults.shell.rate_limit_commands),
+                        rate_limit_window_secs: s
+                            .rate_limit_window_secs
+                            .unwrap_or(defaults.shell.rate_limit_window_secs),
+                        audit_log: s.audit_log.unwrap_or(defaults.shell.audit_log),
+                        shell_program: s
+                            .shell_program
+                            .or_else(|| defaults.shell.shell_program.clone()),
+                        shell_args: s.shell_args.or_else(|| defaults.shell.shell_args.clone()),
+                        env_mode: resolve_env_mode(
+                            s.env_mode,
+                            s.env_allowlist,
+                            &defaults.shell.env_mode,
+                        ),
+                    }),
+                    web_fetch: a.web_fetch.map(|w| WebFetchConfig {
+                        enabled: w.enabled.unwrap_or(defaults.web_fetch.enabled),
+                        allowed_hosts: w
+                            .allowed_hosts
+                            .unwrap_or_else(|| defaults.web_fetch.allowed_hosts.clone()),
+                        // Like redaction, blocking only ever widens.
+                        blocked_hosts: defaults
+                            .web_fetch
+                            .blocked_hosts
+                            .iter()
+                            .cloned()
+                            .chain(w.blocked_hosts)
+                            .collect(),
+                        max_response_bytes: w
+                            .max_response_bytes
+                            .unwrap_or(defaults.web_fetch.max_response_bytes),
+                        timeout_secs: w.timeout_secs.unwrap_or(defaults.web_fetch.timeout_secs),
+                    }),
                     brave_search_key: a.brave_search_key.as_deref().and_then(resolve_env_value),
                     cron,
                 }
             })
             .collect();
+        for agent in &mut agents {
+            if let Some(shell) = &mut agent.shell {
+                check_shell_program(shell, &agent.id);
+            }
+        }
 
         if agents.is_empty() {
             agents.push(AgentConfig {
@@ -2377,6 +3036,8 @@ impl Config {
                 ingestion: None,
                 cortex: None,
                 browser: None,
+                shell: None,
+                web_fetch: None,
                 brave_search_key: None,
                 cron: Vec::new(),
             });
@@ -2467,6 +3128,21 @@ impl Config {
             }),
         };
 
+        // Tokens written directly in config.toml never pass through the
+        // process environment, so the shell tool can't discover them itself.
+        let messaging_secrets = messaging.secret_values();
+        defaults
+            .shell
+            .redacted_values
+            .extend(messaging_secrets.iter().cloned());
+        for agent in &mut agents {
+            if let Some(shell) = &mut agent.shell {
+                shell
+                    .redacted_values
+                    .extend(messaging_secrets.iter().cloned());
+            }
+        }
+
         let bindings = toml
             .bindings
             .into_iter()
@@ -2485,6 +3161,19 @@ impl Config {
             enabled: toml.api.enabled,
             port: toml.api.port,
             bind: toml.api.bind,
+            debug_replay_token: toml
+                .api
+                .debug_replay_token
+                .as_deref()
+                .and_then(resolve_env_value),
+            events_token: toml.api.events_token.as_deref().and_then(resolve_env_value),
+            admin_token: toml.api.admin_token.as_deref().and_then(resolve_env_value),
+            event_forwarders: toml.api.event_forwarders,
+            event_replay_buffer: toml.api.event_replay_buffer,
+            event_channel_capacity: toml.api.event_channel_capacity,
+            sse_keepalive_secs: toml.api.sse_keepalive_secs,
+            channel_idle_timeout_secs: toml.api.channel_idle_timeout_secs,
+            max_tracked_channels: toml.api.max_tracked_channels,
         };
 
         let metrics = MetricsConfig {
@@ -2573,6 +3262,11 @@ pub struct RuntimeConfig {
     pub max_concurrent_branches: ArcSwap<usize>,
     pub max_concurrent_workers: ArcSwap<usize>,
     pub browser_config: ArcSwap<BrowserConfig>,
+    pub web_fetch_config: ArcSwap<WebFetchConfig>,
+    pub shell_config: ArcSwap<ShellConfig>,
+    /// Shared by every worker's shell tool so the agent's command rate limit
+    /// holds across workers. None when `shell.rate_limit_commands` is unset.
+    pub shell_rate_limiter: ArcSwap<Option<Arc<crate::tools::shell::RateLimiter>>>,
     pub history_backfill_count: ArcSwap<usize>,
     pub brave_search_key: ArcSwap<Option<String>>,
     pub cortex: ArcSwap<CortexConfig>,
@@ -2589,10 +3283,22 @@ pub struct RuntimeConfig {
     pub cron_store: ArcSwap<Option<Arc<crate::cron::CronStore>>>,
     /// Cron scheduler, set after agent initialization.
     pub cron_scheduler: ArcSwap<Option<Arc<crate::cron::Scheduler>>>,
+    /// Reminder scheduler, set after agent initialization.
+    pub reminders: ArcSwap<Option<Arc<crate::cron::ReminderScheduler>>>,
     /// Settings store for agent-specific configuration.
     pub settings: ArcSwap<Option<Arc<crate::settings::SettingsStore>>>,
 }
 
+/// A fresh limiter for `shell`'s rate limit, if it sets one.
+fn shell_rate_limiter(shell: &ShellConfig) -> Option<Arc<crate::tools::shell::RateLimiter>> {
+    shell.rate_limit_commands.map(|commands| {
+        Arc::new(crate::tools::shell::RateLimiter::new(
+            commands,
+            std::time::Duration::from_secs(shell.rate_limit_window_secs),
+        ))
+    })
+}
+
 impl RuntimeConfig {
     /// Build from a resolved agent config, loaded prompts, identity, and skills.
     pub fn new(
@@ -2624,6 +3330,9 @@ impl RuntimeConfig {
             max_concurrent_branches: ArcSwap::from_pointee(agent_config.max_concurrent_branches),
             max_concurrent_workers: ArcSwap::from_pointee(agent_config.max_concurrent_workers),
             browser_config: ArcSwap::from_pointee(agent_config.browser.clone()),
+            web_fetch_config: ArcSwap::from_pointee(agent_config.web_fetch.clone()),
+            shell_config: ArcSwap::from_pointee(agent_config.shell.clone()),
+            shell_rate_limiter: ArcSwap::from_pointee(shell_rate_limiter(&agent_config.shell)),
             history_backfill_count: ArcSwap::from_pointee(agent_config.history_backfill_count),
             brave_search_key: ArcSwap::from_pointee(agent_config.brave_search_key.clone()),
             cortex: ArcSwap::from_pointee(agent_config.cortex),
@@ -2635,6 +3344,7 @@ impl RuntimeConfig {
             opencode_server_pool: Arc::new(server_pool),
             cron_store: ArcSwap::from_pointee(None),
             cron_scheduler: ArcSwap::from_pointee(None),
+            reminders: ArcSwap::from_pointee(None),
             settings: ArcSwap::from_pointee(None),
         }
     }
@@ -2649,6 +3359,11 @@ impl RuntimeConfig {
         self.cron_scheduler.store(Arc::new(Some(scheduler)));
     }
 
+    /// Set the reminder scheduler after initialization.
+    pub fn set_reminders(&self, reminders: Arc<crate::cron::ReminderScheduler>) {
+        self.reminders.store(Arc::new(Some(reminders)));
+    }
+
     /// Set the settings store after initialization.
     pub fn set_settings(&self, settings: Arc<crate::settings::SettingsStore>) {
         self.settings.store(Arc::new(Some(settings)));
@@ -2684,6 +3399,20 @@ impl RuntimeConfig {
         self.max_concurrent_workers
             .store(Arc::new(resolved.max_concurrent_workers));
         self.browser_config.store(Arc::new(resolved.browser));
+        self.web_fetch_config.store(Arc::new(resolved.web_fetch));
+        // Keep the current buckets unless the limits themselves changed.
+        let limits = resolved.shell.rate_limit_commands.map(|commands| {
+            (
+                commands,
+                std::time::Duration::from_secs(resolved.shell.rate_limit_window_secs),
+            )
+        });
+        let current = self.shell_rate_limiter.load();
+        if current.as_ref().as_ref().map(|limiter| limiter.limits()) != limits {
+            self.shell_rate_limiter
+                .store(Arc::new(shell_rate_limiter(&resolved.shell)));
+        }
+        self.shell_config.store(Arc::new(resolved.shell));
         self.history_backfill_count
             .store(Arc::new(resolved.history_backfill_count));
         self.brave_search_key
@@ -2704,6 +3433,19 @@ impl RuntimeConfig {
         self.skills.store(Arc::new(skills));
         tracing::info!("skills reloaded");
     }
+
+    /// Rebuild the prompt engine so it picks up reloaded prompt overrides.
+    /// Keeps the current engine if the new one fails to build.
+    pub fn reload_prompts(&self) {
+        let language = self.prompts.load().language().to_string();
+        match crate::prompts::PromptEngine::new(&language) {
+            Ok(prompts) => {
+                self.prompts.store(Arc::new(prompts));
+                tracing::info!("prompts reloaded");
+            }
+            Err(error) => tracing::error!(%error, "failed to rebuild prompt engine"),
+        }
+    }
 }
 
 impl std::fmt::Debug for RuntimeConfig {
@@ -2776,6 +3518,14 @@ pub fn spawn_file_watcher(
             }
         }
 
+        // Watch instance-level prompt overrides
+        let prompts_dir = instance_dir.join("prompts");
+        if prompts_dir.is_dir() {
+            if let Err(error) = watcher.watch(&prompts_dir, RecursiveMode::Recursive) {
+                tracing::warn!(%error, path = %prompts_dir.display(), "failed to watch prompts dir");
+            }
+        }
+
         // Watch per-agent workspace directories (skills, identity)
         for (_, workspace, _) in &agents {
             for subdir in &["skills"] {
@@ -2829,9 +3579,10 @@ pub fn spawn_file_watcher(
             let skills_changed = changed_paths
                 .iter()
                 .any(|p| p.to_string_lossy().contains("skills"));
+            let prompts_changed = changed_paths.iter().any(|p| p.starts_with(&prompts_dir));
 
             // Skip entirely if nothing relevant changed
-            if !config_changed && !identity_changed && !skills_changed {
+            if !config_changed && !identity_changed && !skills_changed && !prompts_changed {
                 continue;
             }
 
@@ -2848,7 +3599,7 @@ pub fn spawn_file_watcher(
                 if current_hash == last_config_hash {
                     config_changed = false;
                     // If config was the only thing that "changed", skip entirely
-                    if !identity_changed && !skills_changed {
+                    if !identity_changed && !skills_changed && !prompts_changed {
                         continue;
                     }
                 } else {
@@ -2860,6 +3611,7 @@ pub fn spawn_file_watcher(
                 config_changed.then_some("config"),
                 identity_changed.then_some("identity"),
                 skills_changed.then_some("skills"),
+                prompts_changed.then_some("prompts"),
             ]
             .into_iter()
             .flatten()
@@ -2870,6 +3622,10 @@ pub fn spawn_file_watcher(
                 "file change detected, reloading"
             );
 
+            if prompts_changed {
+                crate::prompts::text::reload_overrides(&prompts_dir);
+            }
+
             // Reload config.toml if it changed
             let new_config = if config_changed {
                 match Config::load_from_path(&config_path) {
@@ -3051,6 +3807,10 @@ pub fn spawn_file_watcher(
                     ));
                     runtime_config.reload_skills(skills);
                 }
+
+                if prompts_changed {
+                    runtime_config.reload_prompts();
+                }
             }
         }
 
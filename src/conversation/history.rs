@@ -69,10 +69,12 @@ impl ConversationLogger {
         });
     }
 
-    /// Log a bot (assistant) message. Fire-and-forget.
-    pub fn log_bot_message(&self, channel_id: &ChannelId, content: &str) {
+    /// Log a bot (assistant) message. Fire-and-forget; returns the ID the
+    /// message is stored under.
+    pub fn log_bot_message(&self, channel_id: &ChannelId, content: &str) -> String {
         let pool = self.pool.clone();
         let id = uuid::Uuid::new_v4().to_string();
+        let message_id = id.clone();
         let channel_id = channel_id.to_string();
         let content = content.to_string();
 
@@ -90,6 +92,7 @@ impl ConversationLogger {
                 tracing::warn!(%error, "failed to persist bot message");
             }
         });
+        message_id
     }
 
     /// Load recent messages for a channel (oldest first).
@@ -168,6 +171,56 @@ impl ConversationLogger {
         Ok(messages)
     }
 
+    /// Load one page of a channel's messages (newest first): the `limit` most
+    /// recent ones, or with `before`, the `limit` that precede the message
+    /// with that ID. An unknown `before` ID yields an empty page.
+    pub async fn load_page(
+        &self,
+        channel_id: &str,
+        limit: i64,
+        before: Option<&str>,
+    ) -> crate::error::Result<Vec<ConversationMessage>> {
+        let before_clause = if before.is_some() {
+            "AND (created_at, id) < (SELECT created_at, id FROM conversation_messages WHERE id = ?3)"
+        } else {
+            ""
+        };
+
+        let query_str = format!(
+            "SELECT id, channel_id, role, sender_name, sender_id, content, metadata, created_at \
+             FROM conversation_messages \
+             WHERE channel_id = ?1 {before_clause} \
+             ORDER BY created_at DESC, id DESC \
+             LIMIT ?2"
+        );
+
+        let mut query = sqlx::query(&query_str).bind(channel_id).bind(limit);
+
+        if let Some(before_id) = before {
+            query = query.bind(before_id);
+        }
+
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ConversationMessage {
+                id: row.try_get("id").unwrap_or_default(),
+                channel_id: row.try_get("channel_id").unwrap_or_default(),
+                role: row.try_get("role").unwrap_or_default(),
+                sender_name: row.try_get("sender_name").ok(),
+                sender_id: row.try_get("sender_id").ok(),
+                content: row.try_get("content").unwrap_or_default(),
+                metadata: row.try_get("metadata").ok(),
+                created_at: row
+                    .try_get("created_at")
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+            })
+            .collect())
+    }
 }
 
 /// A unified timeline item combining messages, branch runs, and worker runs.
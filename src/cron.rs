@@ -1,7 +1,9 @@
 //! Cron scheduler for timed tasks.
 
+pub mod reminder;
 pub mod scheduler;
 pub mod store;
 
+pub use reminder::{Reminder, ReminderScheduler, ReminderStore};
 pub use scheduler::{CronConfig, CronContext, Scheduler};
 pub use store::{CronExecutionEntry, CronExecutionStats, CronStore};
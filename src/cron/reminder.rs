@@ -0,0 +1,414 @@
+//! One-shot reminders a channel schedules for itself.
+//!
+//! A reminder fires once, as a `ProcessEvent::ReminderDue` on the agent's
+//! event bus. The channel it belongs to picks the event up, adds the reminder
+//! to its history and re-triggers itself so it can act on it. Reminders are
+//! stored in SQLite and re-armed on startup; one that came due while the
+//! process was down fires immediately. If the conversation has no live
+//! channel at that moment, only API event subscribers see it.
+
+use crate::error::Result;
+use crate::{AgentId, ProcessEvent};
+use anyhow::Context as _;
+use sqlx::{Row as _, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{RwLock, broadcast};
+
+/// Maximum number of pending reminders per conversation.
+pub const MAX_REMINDERS_PER_CHANNEL: usize = 20;
+
+/// A pending reminder.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Reminder {
+    pub id: String,
+    pub channel_id: String,
+    pub text: String,
+    pub due_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Reminder store for persistence.
+#[derive(Debug)]
+pub struct ReminderStore {
+    pool: SqlitePool,
+}
+
+impl ReminderStore {
+    /// Create a new reminder store.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Save a reminder unless its channel already has `limit` pending, in
+    /// one statement so concurrent saves can't both slip under the limit.
+    /// Returns whether it was saved.
+    pub async fn save_within_limit(&self, reminder: &Reminder, limit: usize) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO reminders (id, channel_id, text, due_at)
+            SELECT ?, ?, ?, ?
+            WHERE (SELECT COUNT(*) FROM reminders WHERE channel_id = ?) < ?
+            "#,
+        )
+        .bind(&reminder.id)
+        .bind(&reminder.channel_id)
+        .bind(&reminder.text)
+        .bind(reminder.due_at)
+        .bind(&reminder.channel_id)
+        .bind(limit as i64)
+        .execute(&self.pool)
+        .await
+        .context("failed to save reminder")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Load every pending reminder across all channels.
+    pub async fn load_all(&self) -> Result<Vec<Reminder>> {
+        let rows =
+            sqlx::query("SELECT id, channel_id, text, due_at FROM reminders ORDER BY due_at ASC")
+                .fetch_all(&self.pool)
+                .await
+                .context("failed to load reminders")?;
+
+        Ok(rows.iter().map(row_to_reminder).collect())
+    }
+
+    /// Load the pending reminders of one channel, soonest first.
+    pub async fn load_for_channel(&self, channel_id: &str) -> Result<Vec<Reminder>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, channel_id, text, due_at
+            FROM reminders
+            WHERE channel_id = ?
+            ORDER BY due_at ASC
+            "#,
+        )
+        .bind(channel_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to load reminders")?;
+
+        Ok(rows.iter().map(row_to_reminder).collect())
+    }
+
+    /// Delete a reminder. Returns whether it existed.
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM reminders WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("failed to delete reminder")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a reminder only if it belongs to the given channel.
+    pub async fn delete_for_channel(&self, channel_id: &str, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM reminders WHERE id = ? AND channel_id = ?")
+            .bind(id)
+            .bind(channel_id)
+            .execute(&self.pool)
+            .await
+            .context("failed to delete reminder")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn row_to_reminder(row: &sqlx::sqlite::SqliteRow) -> Reminder {
+    Reminder {
+        id: row.try_get("id").unwrap_or_default(),
+        channel_id: row.try_get("channel_id").unwrap_or_default(),
+        text: row.try_get("text").unwrap_or_default(),
+        due_at: row.try_get("due_at").unwrap_or_else(|_| chrono::Utc::now()),
+    }
+}
+
+/// Owns one timer task per pending reminder.
+pub struct ReminderScheduler {
+    agent_id: AgentId,
+    store: Arc<ReminderStore>,
+    event_tx: broadcast::Sender<ProcessEvent>,
+    timers: Arc<RwLock<HashMap<String, tokio::task::JoinHandle<()>>>>,
+}
+
+impl std::fmt::Debug for ReminderScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReminderScheduler")
+            .field("agent_id", &self.agent_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReminderScheduler {
+    pub fn new(
+        agent_id: AgentId,
+        store: Arc<ReminderStore>,
+        event_tx: broadcast::Sender<ProcessEvent>,
+    ) -> Self {
+        Self {
+            agent_id,
+            store,
+            event_tx,
+            timers: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Arm every persisted reminder. Called once at startup.
+    pub async fn start(&self) -> Result<()> {
+        let reminders = self.store.load_all().await?;
+        let count = reminders.len();
+        for reminder in reminders {
+            self.arm(reminder).await;
+        }
+        tracing::info!(agent_id = %self.agent_id, count, "reminders armed");
+        Ok(())
+    }
+
+    /// Persist and arm a new reminder for a channel.
+    ///
+    /// Fails once the channel already has `MAX_REMINDERS_PER_CHANNEL` pending.
+    pub async fn create(
+        &self,
+        channel_id: &str,
+        text: String,
+        delay: std::time::Duration,
+    ) -> Result<Reminder> {
+        let delay = chrono::Duration::from_std(delay).context("reminder delay is too large")?;
+        let reminder = Reminder {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel_id: channel_id.to_string(),
+            text,
+            due_at: chrono::Utc::now() + delay,
+        };
+
+        if !self
+            .store
+            .save_within_limit(&reminder, MAX_REMINDERS_PER_CHANNEL)
+            .await?
+        {
+            return Err(crate::error::Error::Other(anyhow::anyhow!(
+                "this conversation already has {MAX_REMINDERS_PER_CHANNEL} pending reminders; \
+                 cancel one first"
+            )));
+        }
+        self.arm(reminder.clone()).await;
+
+        tracing::info!(
+            agent_id = %self.agent_id,
+            reminder_id = %reminder.id,
+            channel_id,
+            due_at = %reminder.due_at,
+            "reminder created"
+        );
+        Ok(reminder)
+    }
+
+    /// Pending reminders for a channel, soonest first.
+    pub async fn list(&self, channel_id: &str) -> Result<Vec<Reminder>> {
+        self.store.load_for_channel(channel_id).await
+    }
+
+    /// Cancel a channel's reminder. Returns false if the channel has no
+    /// reminder with that ID.
+    pub async fn cancel(&self, channel_id: &str, id: &str) -> Result<bool> {
+        if !self.store.delete_for_channel(channel_id, id).await? {
+            return Ok(false);
+        }
+
+        if let Some(handle) = self.timers.write().await.remove(id) {
+            handle.abort();
+        }
+
+        tracing::info!(agent_id = %self.agent_id, reminder_id = %id, channel_id, "reminder cancelled");
+        Ok(true)
+    }
+
+    /// Stop all reminder timers. Pending reminders stay in the store.
+    pub async fn shutdown(&self) {
+        let handles: Vec<_> = self.timers.write().await.drain().collect();
+        for (_, handle) in handles {
+            handle.abort();
+        }
+    }
+
+    /// Start the timer task that fires a reminder when it comes due.
+    async fn arm(&self, reminder: Reminder) {
+        let agent_id = self.agent_id.clone();
+        let store = self.store.clone();
+        let event_tx = self.event_tx.clone();
+        let timers = self.timers.clone();
+        let id = reminder.id.clone();
+
+        // Held across the spawn so a reminder that is already due can't
+        // remove its entry before it has been inserted.
+        let mut timer_map = self.timers.write().await;
+        let handle = tokio::spawn(async move {
+            // A reminder that came due while we were down fires right away.
+            let wait = (reminder.due_at - chrono::Utc::now())
+                .to_std()
+                .unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            timers.write().await.remove(&reminder.id);
+            match store.delete(&reminder.id).await {
+                // Cancelled between waking up and here.
+                Ok(false) => return,
+                Ok(true) => {}
+                Err(error) => {
+                    tracing::warn!(%error, reminder_id = %reminder.id, "failed to delete fired reminder");
+                }
+            }
+
+            tracing::info!(
+                agent_id = %agent_id,
+                reminder_id = %reminder.id,
+                channel_id = %reminder.channel_id,
+                "reminder due"
+            );
+            event_tx
+                .send(ProcessEvent::ReminderDue {
+                    agent_id,
+                    channel_id: Arc::from(reminder.channel_id.as_str()),
+                    reminder_id: reminder.id,
+                    text: reminder.text,
+                })
+                .ok();
+        });
+
+        // Replace rather than leak any timer already running under this ID.
+        if let Some(old_handle) = timer_map.insert(id, handle) {
+            old_handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn scheduler() -> (ReminderScheduler, broadcast::Receiver<ProcessEvent>) {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+
+        let (event_tx, event_rx) = broadcast::channel(16);
+        let scheduler = ReminderScheduler::new(
+            Arc::from("agent"),
+            Arc::new(ReminderStore::new(pool)),
+            event_tx,
+        );
+        (scheduler, event_rx)
+    }
+
+    #[tokio::test]
+    async fn reminder_fires_once_and_is_removed() {
+        let (scheduler, mut event_rx) = scheduler().await;
+        let reminder = scheduler
+            .create("discord:1:2", "follow up".into(), Duration::from_millis(10))
+            .await
+            .expect("create reminder");
+
+        let event = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
+            .await
+            .expect("reminder should fire")
+            .expect("event bus open");
+        match event {
+            ProcessEvent::ReminderDue {
+                channel_id,
+                reminder_id,
+                text,
+                ..
+            } => {
+                assert_eq!(&*channel_id, "discord:1:2");
+                assert_eq!(reminder_id, reminder.id);
+                assert_eq!(text, "follow up");
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        assert!(scheduler.list("discord:1:2").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_reminder_does_not_fire() {
+        let (scheduler, mut event_rx) = scheduler().await;
+        let reminder = scheduler
+            .create("channel", "never".into(), Duration::from_millis(50))
+            .await
+            .expect("create reminder");
+
+        assert!(
+            !scheduler
+                .cancel("other-channel", &reminder.id)
+                .await
+                .unwrap()
+        );
+        assert!(scheduler.cancel("channel", &reminder.id).await.unwrap());
+
+        let fired = tokio::time::timeout(Duration::from_millis(200), event_rx.recv()).await;
+        assert!(fired.is_err(), "cancelled reminder fired");
+    }
+
+    #[tokio::test]
+    async fn pending_reminders_are_capped_per_channel() {
+        let (scheduler, _event_rx) = scheduler().await;
+        for index in 0..MAX_REMINDERS_PER_CHANNEL {
+            scheduler
+                .create(
+                    "channel",
+                    format!("reminder {index}"),
+                    Duration::from_secs(3600),
+                )
+                .await
+                .expect("create reminder");
+        }
+
+        assert!(
+            scheduler
+                .create("channel", "one too many".into(), Duration::from_secs(3600))
+                .await
+                .is_err()
+        );
+        assert!(
+            scheduler
+                .create("another-channel", "fine".into(), Duration::from_secs(3600))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_creates_stay_within_the_cap() {
+        let (scheduler, _event_rx) = scheduler().await;
+        let creates = (0..MAX_REMINDERS_PER_CHANNEL * 2).map(|index| {
+            scheduler.create(
+                "channel",
+                format!("reminder {index}"),
+                Duration::from_secs(3600),
+            )
+        });
+        let created = futures::future::join_all(creates)
+            .await
+            .into_iter()
+            .filter(Result::is_ok)
+            .count();
+
+        assert_eq!(created, MAX_REMINDERS_PER_CHANNEL);
+        assert_eq!(
+            scheduler.list("channel").await.unwrap().len(),
+            MAX_REMINDERS_PER_CHANNEL
+        );
+    }
+}
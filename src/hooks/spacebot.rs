@@ -33,6 +33,19 @@ impl SpacebotHook {
         }
     }
 
+    /// Report a tool call stopped by a safety check.
+    fn send_policy_violation(&self, tool_name: &str, reason: &str, terminated: bool) {
+        let event = ProcessEvent::PolicyViolation {
+            agent_id: self.agent_id.clone(),
+            process_id: self.process_id.clone(),
+            channel_id: self.channel_id.clone(),
+            tool_name: tool_name.to_string(),
+            reason: reason.to_string(),
+            terminated,
+        };
+        let _ = self.event_tx.send(event);
+    }
+
     /// Send a status update event.
     pub fn send_status(&self, status: impl Into<String>) {
         let event = ProcessEvent::StatusUpdate {
@@ -142,8 +155,10 @@ where
                 leak_prefix = %&leak[..leak.len().min(8)],
                 "secret leak detected in tool arguments, blocking call"
             );
+            let reason = "Tool call blocked: arguments contained a secret.";
+            self.send_policy_violation(tool_name, reason, false);
             return ToolCallHookAction::Skip {
-                reason: "Tool call blocked: arguments contained a secret.".into(),
+                reason: reason.into(),
             };
         }
 
@@ -189,9 +204,11 @@ where
                 leak_prefix = %&leak[..leak.len().min(8)],
                 "secret leak detected in tool output, terminating agent"
             );
+            let reason =
+                "Tool output contained a secret. Agent terminated to prevent exfiltration.";
+            self.send_policy_violation(tool_name, reason, true);
             return HookAction::Terminate {
-                reason: "Tool output contained a secret. Agent terminated to prevent exfiltration."
-                    .into(),
+                reason: reason.into(),
             };
         }
 
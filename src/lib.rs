@@ -138,6 +138,33 @@ pub enum ProcessEvent {
         tool_name: String,
         result: String,
     },
+    /// A line of output from a shell command that is still running.
+    ShellOutput {
+        agent_id: AgentId,
+        process_id: ProcessId,
+        channel_id: Option<ChannelId>,
+        /// Which pipe the line came from: "stdout" or "stderr".
+        stream: String,
+        line: String,
+    },
+    /// A tool call was blocked, or its process terminated, by a safety check
+    /// such as leak detection.
+    PolicyViolation {
+        agent_id: AgentId,
+        process_id: ProcessId,
+        channel_id: Option<ChannelId>,
+        tool_name: String,
+        reason: String,
+        /// Whether the process was terminated rather than the call skipped.
+        terminated: bool,
+    },
+    /// A reminder scheduled by a channel came due.
+    ReminderDue {
+        agent_id: AgentId,
+        channel_id: ChannelId,
+        reminder_id: String,
+        text: String,
+    },
     MemorySaved {
         agent_id: AgentId,
         memory_id: String,
@@ -5,7 +5,9 @@ pub mod manager;
 pub mod model;
 pub mod providers;
 pub mod routing;
+pub mod usage;
 
 pub use manager::LlmManager;
 pub use model::SpacebotModel;
 pub use routing::RoutingConfig;
+pub use usage::TokenUsage;
@@ -11,6 +11,7 @@
 use crate::auth::OAuthCredentials;
 use crate::config::{LlmConfig, ProviderConfig};
 use crate::error::{LlmError, Result};
+use crate::llm::usage::{ModelPrice, TokenUsage, UsageTracker};
 
 use anyhow::Context as _;
 use arc_swap::ArcSwap;
@@ -30,6 +31,8 @@ pub struct LlmManager {
     instance_dir: Option<PathBuf>,
     /// Cached OAuth credentials (refreshed lazily).
     oauth_credentials: RwLock<Option<OAuthCredentials>>,
+    /// Tokens used per agent and model since startup.
+    usage: UsageTracker,
 }
 
 impl LlmManager {
@@ -46,6 +49,7 @@ impl LlmManager {
             rate_limited: Arc::new(RwLock::new(HashMap::new())),
             instance_dir: None,
             oauth_credentials: RwLock::new(None),
+            usage: UsageTracker::default(),
         })
     }
 
@@ -84,6 +88,7 @@ impl LlmManager {
             rate_limited: Arc::new(RwLock::new(HashMap::new())),
             instance_dir: Some(instance_dir),
             oauth_credentials: RwLock::new(oauth_credentials),
+            usage: UsageTracker::default(),
         })
     }
 
@@ -193,6 +198,22 @@ impl LlmManager {
         }
     }
 
+    /// Add a completion's token usage to `agent_id`'s count for `model`.
+    pub fn record_usage(&self, agent_id: &str, model: &str, usage: &rig::completion::Usage) {
+        self.usage.record(agent_id, model, usage);
+    }
+
+    /// Token usage since startup, by agent ID and then model name.
+    pub fn usage_snapshot(&self) -> HashMap<String, HashMap<String, TokenUsage>> {
+        self.usage.snapshot()
+    }
+
+    /// What `model` charges per million tokens, from `[llm.prices]` or the
+    /// built-in table.
+    pub fn model_price(&self, model: &str) -> Option<ModelPrice> {
+        crate::llm::usage::model_price(model, &self.config.load().prices)
+    }
+
     /// Clean up expired rate limit entries.
     pub async fn cleanup_rate_limits(&self, cooldown_secs: u64) {
         self.rate_limited
@@ -2,9 +2,7 @@
 
 use crate::config::{ApiType, ProviderConfig};
 use crate::llm::manager::LlmManager;
-use crate::llm::routing::{
-    self, MAX_FALLBACK_ATTEMPTS, MAX_RETRIES_PER_MODEL, RETRY_BASE_DELAY_MS, RoutingConfig,
-};
+use crate::llm::routing::{self, MAX_FALLBACK_ATTEMPTS, RetryPolicy, RoutingConfig};
 
 use rig::completion::{self, CompletionError, CompletionModel, CompletionRequest, GetTokenUsage};
 use rig::message::{
@@ -46,6 +44,9 @@ pub struct SpacebotModel {
     provider: String,
     full_model_name: String,
     routing: Option<RoutingConfig>,
+    retry_policy: RetryPolicy,
+    /// The agent whose token usage completions count towards.
+    agent_id: Option<crate::AgentId>,
 }
 
 impl SpacebotModel {
@@ -65,6 +66,30 @@ impl SpacebotModel {
         self
     }
 
+    /// Retry transient failures with `policy` instead of the default.
+    /// Applies to each model in the fallback chain.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Count this model's token usage towards `agent_id`.
+    pub fn with_agent(mut self, agent_id: crate::AgentId) -> Self {
+        self.agent_id = Some(agent_id);
+        self
+    }
+
+    fn record_usage(
+        &self,
+        model_name: &str,
+        response: &completion::CompletionResponse<RawResponse>,
+    ) {
+        if let Some(agent_id) = &self.agent_id {
+            self.llm_manager
+                .record_usage(agent_id, model_name, &response.usage);
+        }
+    }
+
     /// Direct call to the provider (no fallback logic).
     async fn attempt_completion(
         &self,
@@ -106,7 +131,8 @@ impl SpacebotModel {
         }
     }
 
-    /// Try a model with retries and exponential backoff on transient errors.
+    /// Try a model with retries and exponential backoff on transient errors,
+    /// as set by the retry policy.
     ///
     /// Returns `Ok(response)` on success, or `Err((last_error, was_rate_limit))`
     /// after exhausting retries. `was_rate_limit` indicates the final failure was
@@ -123,46 +149,44 @@ impl SpacebotModel {
             SpacebotModel::make(&self.llm_manager, model_name)
         };
 
-        let mut last_error = None;
-        for attempt in 0..MAX_RETRIES_PER_MODEL {
-            if attempt > 0 {
-                let delay_ms = RETRY_BASE_DELAY_MS * 2u64.pow((attempt - 1) as u32);
-                tracing::debug!(
-                    model = %model_name,
-                    attempt = attempt + 1,
-                    delay_ms,
-                    "retrying after backoff"
-                );
-                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-            }
-
-            match model.attempt_completion(request.clone()).await {
-                Ok(response) => return Ok(response),
-                Err(error) => {
-                    let error_str = error.to_string();
-                    if !routing::is_retriable_error(&error_str) {
-                        // Non-retriable (auth error, bad request, etc) — bail immediately
-                        return Err((error, false));
-                    }
-                    tracing::warn!(
-                        model = %model_name,
-                        attempt = attempt + 1,
-                        %error,
-                        "retriable error"
-                    );
-                    last_error = Some(error_str);
+        let mut attempt = 1;
+        loop {
+            let error = match model.attempt_completion(request.clone()).await {
+                Ok(response) => {
+                    self.record_usage(model_name, &response);
+                    return Ok(response);
                 }
+                Err(error) => error,
+            };
+
+            let error_str = error.to_string();
+            if !routing::is_retriable_error(&error_str) {
+                // Non-retriable (auth error, bad request, etc) — bail immediately
+                return Err((error, false));
             }
-        }
+            let was_rate_limit = routing::is_rate_limit_error(&error_str);
+            let Some(delay) = self.retry_policy.backoff(attempt, &error_str) else {
+                if attempt == 1 {
+                    return Err((error, was_rate_limit));
+                }
+                return Err((
+                    CompletionError::ProviderError(format!(
+                        "{model_name} failed after {attempt} attempts: {error_str}"
+                    )),
+                    was_rate_limit,
+                ));
+            };
 
-        let error_str = last_error.unwrap_or_default();
-        let was_rate_limit = routing::is_rate_limit_error(&error_str);
-        Err((
-            CompletionError::ProviderError(format!(
-                "{model_name} failed after {MAX_RETRIES_PER_MODEL} attempts: {error_str}"
-            )),
-            was_rate_limit,
-        ))
+            tracing::warn!(
+                model = %model_name,
+                attempt,
+                %error,
+                delay_ms = delay.as_millis() as u64,
+                "retriable error, retrying after backoff"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
     }
 }
 
@@ -192,6 +216,8 @@ impl CompletionModel for SpacebotModel {
             provider,
             full_model_name,
             routing: None,
+            retry_policy: RetryPolicy::default(),
+            agent_id: None,
         }
     }
 
@@ -204,8 +230,11 @@ impl CompletionModel for SpacebotModel {
 
         let result = async move {
             let Some(routing) = &self.routing else {
-                // No routing config — just call the model directly, no fallback/retry
-                return self.attempt_completion(request).await;
+                // No routing config — retry the model itself, but no fallbacks
+                return self
+                    .attempt_with_retries(&self.full_model_name, &request)
+                    .await
+                    .map_err(|(error, _)| error);
             };
 
             let cooldown = routing.rate_limit_cooldown_secs;
@@ -231,15 +260,23 @@ impl CompletionModel for SpacebotModel {
                     .attempt_with_retries(&self.full_model_name, &request)
                     .await
                 {
-                    Ok(response) => return Ok(response),
+                    Ok(response) => {
+                        tracing::debug!(
+                            model = %self.full_model_name,
+                            "primary model served request"
+                        );
+                        return Ok(response);
+                    }
                     Err((error, was_rate_limit)) => {
                         if was_rate_limit {
                             self.llm_manager
                                 .record_rate_limit(&self.full_model_name)
                                 .await;
                         }
-                        if fallbacks.is_empty() {
-                            // No fallbacks — this is the final error
+                        // A bad request or auth error would fail the same way
+                        // on any model, so only provider trouble falls back.
+                        if fallbacks.is_empty() || !routing::is_retriable_error(&error.to_string())
+                        {
                             return Err(error);
                         }
                         tracing::warn!(
@@ -251,8 +288,17 @@ impl CompletionModel for SpacebotModel {
                 }
             }
 
-            // Try fallback chain, each with their own retry loop
-            for (index, fallback_name) in fallbacks.iter().take(MAX_FALLBACK_ATTEMPTS).enumerate() {
+            // Try fallback chain, each with their own retry loop. Listing the
+            // primary again (or a model twice) can't loop: each entry is tried
+            // at most once, and at most MAX_FALLBACK_ATTEMPTS of them.
+            let chain = fallbacks
+                .iter()
+                .enumerate()
+                .filter(|(position, name)| {
+                    **name != self.full_model_name && !fallbacks[..*position].contains(*name)
+                })
+                .map(|(_, name)| name);
+            for (index, fallback_name) in chain.take(MAX_FALLBACK_ATTEMPTS).enumerate() {
                 if self
                     .llm_manager
                     .is_rate_limited(fallback_name, cooldown)
@@ -356,6 +402,7 @@ impl SpacebotModel {
             .map_err(|e| CompletionError::ProviderError(e.to_string()))?;
 
         let status = response.status();
+        let retry_after = routing::retry_after_notice(response.headers());
         let response_text = response.text().await.map_err(|e| {
             CompletionError::ProviderError(format!("failed to read response body: {e}"))
         })?;
@@ -373,7 +420,7 @@ impl SpacebotModel {
                 .as_str()
                 .unwrap_or("unknown error");
             return Err(CompletionError::ProviderError(format!(
-                "Anthropic API error ({status}): {message}"
+                "Anthropic API error ({status}): {message}{retry_after}"
             )));
         }
 
@@ -461,6 +508,7 @@ impl SpacebotModel {
             .map_err(|e| CompletionError::ProviderError(e.to_string()))?;
 
         let status = response.status();
+        let retry_after = routing::retry_after_notice(response.headers());
         let response_text = response.text().await.map_err(|e| {
             CompletionError::ProviderError(format!("failed to read response body: {e}"))
         })?;
@@ -478,7 +526,7 @@ impl SpacebotModel {
                 .as_str()
                 .unwrap_or("unknown error");
             return Err(CompletionError::ProviderError(format!(
-                "OpenAI API error ({status}): {message}"
+                "OpenAI API error ({status}): {message}{retry_after}"
             )));
         }
 
@@ -541,6 +589,7 @@ impl SpacebotModel {
             .map_err(|e| CompletionError::ProviderError(e.to_string()))?;
 
         let status = response.status();
+        let retry_after = routing::retry_after_notice(response.headers());
         let response_text = response.text().await.map_err(|e| {
             CompletionError::ProviderError(format!("failed to read response body: {e}"))
         })?;
@@ -558,7 +607,7 @@ impl SpacebotModel {
                 .as_str()
                 .unwrap_or("unknown error");
             return Err(CompletionError::ProviderError(format!(
-                "OpenAI Responses API error ({status}): {message}"
+                "OpenAI Responses API error ({status}): {message}{retry_after}"
             )));
         }
 
@@ -639,6 +688,7 @@ impl SpacebotModel {
             .map_err(|e| CompletionError::ProviderError(e.to_string()))?;
 
         let status = response.status();
+        let retry_after = routing::retry_after_notice(response.headers());
         let response_text = response.text().await.map_err(|e| {
             CompletionError::ProviderError(format!("failed to read response body: {e}"))
         })?;
@@ -656,7 +706,7 @@ impl SpacebotModel {
                 .as_str()
                 .unwrap_or("unknown error");
             return Err(CompletionError::ProviderError(format!(
-                "{provider_display_name} API error ({status}): {message}"
+                "{provider_display_name} API error ({status}): {message}{retry_after}"
             )));
         }
 
@@ -729,6 +779,7 @@ impl SpacebotModel {
             .map_err(|e| CompletionError::ProviderError(e.to_string()))?;
 
         let status = response.status();
+        let retry_after = routing::retry_after_notice(response.headers());
         let response_text = response.text().await.map_err(|e| {
             CompletionError::ProviderError(format!("failed to read response body: {e}"))
         })?;
@@ -746,7 +797,7 @@ impl SpacebotModel {
                 .as_str()
                 .unwrap_or("unknown error");
             return Err(CompletionError::ProviderError(format!(
-                "{provider_display_name} API error ({status}): {message}"
+                "{provider_display_name} API error ({status}): {message}{retry_after}"
             )));
         }
 
@@ -1331,6 +1382,68 @@ fn parse_openai_responses_response(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn only_provider_errors_fall_back() {
+        let error = |status: reqwest::StatusCode| {
+            CompletionError::ProviderError(format!("Anthropic API error ({status}): oops"))
+                .to_string()
+        };
+        for status in [
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            reqwest::StatusCode::BAD_GATEWAY,
+            reqwest::StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            assert!(routing::is_retriable_error(&error(status)), "{status}");
+            assert!(routing::is_retriable_status(status.as_u16()), "{status}");
+        }
+        for status in [
+            reqwest::StatusCode::BAD_REQUEST,
+            reqwest::StatusCode::UNAUTHORIZED,
+        ] {
+            assert!(!routing::is_retriable_error(&error(status)), "{status}");
+        }
+    }
+
+    #[test]
+    fn retries_back_off_with_jitter_and_honor_retry_after() {
+        let policy = RetryPolicy::default();
+        let overloaded = "Anthropic API error (503 Service Unavailable): overloaded";
+        for _ in 0..20 {
+            let first = policy.backoff(1, overloaded).expect("first retry");
+            assert!(first >= Duration::from_millis(250) && first < Duration::from_millis(750));
+            let second = policy.backoff(2, overloaded).expect("second retry");
+            assert!(second >= Duration::from_millis(500) && second < Duration::from_millis(1500));
+        }
+        assert_eq!(policy.backoff(3, overloaded), None);
+        assert_eq!(RetryPolicy::disabled().backoff(1, overloaded), None);
+
+        let error_with = |retry_after: &str| {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(reqwest::header::RETRY_AFTER, retry_after.parse().unwrap());
+            let notice = routing::retry_after_notice(&headers);
+            CompletionError::ProviderError(format!(
+                "OpenAI API error (429 Too Many Requests): slow down{notice}"
+            ))
+            .to_string()
+        };
+        assert_eq!(
+            policy.backoff(1, &error_with("7")),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            policy.backoff(1, &error_with("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+        // Too long to wait out; the fallback chain takes over instead.
+        assert_eq!(policy.backoff(1, &error_with("3600")), None);
+        assert_eq!(
+            routing::retry_after_notice(&reqwest::header::HeaderMap::new()),
+            ""
+        );
+    }
 
     #[test]
     fn reverse_map_restores_original_tool_names() {
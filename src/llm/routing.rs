@@ -2,6 +2,7 @@
 
 use crate::ProcessType;
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Model routing configuration. Lives on the agent config (via defaults).
 /// Determines which LLM model each process type uses, with task-type
@@ -101,7 +102,7 @@ impl RoutingConfig {
 
 /// Whether an HTTP status code should trigger a fallback to the next model.
 pub fn is_retriable_status(status: u16) -> bool {
-    matches!(status, 429 | 502 | 503 | 504)
+    matches!(status, 429 | 500 | 502 | 503 | 504)
 }
 
 /// Whether a completion error message indicates a retriable failure.
@@ -109,6 +110,7 @@ pub fn is_retriable_error(error_message: &str) -> bool {
     let lower = error_message.to_lowercase();
     // Rate limits and server errors
     lower.contains("429")
+        || lower.contains("500 internal server error")
         || lower.contains("502")
         || lower.contains("503")
         || lower.contains("504")
@@ -342,11 +344,89 @@ pub fn provider_from_model(model: &str) -> &str {
 /// Max number of fallback models to try before giving up.
 pub const MAX_FALLBACK_ATTEMPTS: usize = 3;
 
-/// Max retries per model (primary or fallback) on retriable errors.
-pub const MAX_RETRIES_PER_MODEL: usize = 3;
+/// Longest `Retry-After` a model waits out before giving up on it. A longer
+/// one is treated like running out of attempts, so the fallback chain takes
+/// over instead of the request stalling.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
 
-/// Base delay for exponential backoff between retries (milliseconds).
-pub const RETRY_BASE_DELAY_MS: u64 = 500;
+/// How a model retries transient failures (see [`is_retriable_error`]) before
+/// giving up or moving on to its fallbacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per model, including the first. 1 disables retries.
+    pub max_attempts: usize,
+    /// Delay before the first retry. It doubles for each retry after that.
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Try each model once.
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// How long to wait before retry number `retry` (starting at 1) after a
+    /// transient failure, or `None` if the model shouldn't be retried.
+    ///
+    /// A `Retry-After` from the provider is used as is. Otherwise the
+    /// exponential delay is jittered by up to half either way, so clients
+    /// that failed together don't all retry together.
+    pub fn backoff(&self, retry: usize, error_message: &str) -> Option<Duration> {
+        if retry >= self.max_attempts {
+            return None;
+        }
+        if let Some(retry_after) = retry_after(error_message) {
+            return (retry_after <= MAX_RETRY_AFTER).then_some(retry_after);
+        }
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1) as u32))
+            .min(MAX_RETRY_AFTER);
+        Some(exponential.mul_f64(rand::random_range(0.5..1.5)))
+    }
+}
+
+/// A suffix for a provider error message carrying its `Retry-After` header,
+/// in the form [`retry_after`] reads back. Empty without a usable header.
+pub fn retry_after_notice(headers: &reqwest::header::HeaderMap) -> String {
+    let Some(value) = headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+    else {
+        return String::new();
+    };
+    // Either delay-seconds or an HTTP date.
+    let seconds = value.parse::<u64>().ok().or_else(|| {
+        let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        let remaining = date.signed_duration_since(chrono::Utc::now()).num_seconds();
+        Some(remaining.max(0) as u64)
+    });
+    match seconds {
+        Some(seconds) => format!(" (retry after {seconds}s)"),
+        None => String::new(),
+    }
+}
+
+/// The delay a provider asked for in an error message built with
+/// [`retry_after_notice`].
+pub fn retry_after(error_message: &str) -> Option<Duration> {
+    let (_, rest) = error_message.rsplit_once("(retry after ")?;
+    let seconds = rest.split_once("s)")?.0.parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
 
 /// Whether an error indicates an actual rate limit (429) vs other transient failures.
 /// Only rate-limit errors should trigger cooldown — timeouts and 5xx errors are
@@ -0,0 +1,230 @@
+//! Token usage accumulated per agent and model.
+//!
+//! Every completion adds its response's usage here. Counters are atomics
+//! behind a read-mostly map, so recording only takes the write lock the
+//! first time an agent uses a model.
+//!
+//! Costs are estimated from per-model prices: `[llm.prices]` in config.toml,
+//! then [`BUILTIN_PRICES`] for common models.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Tokens used since startup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TokenUsage {
+    /// Completions that returned a response.
+    pub requests: u64,
+    /// Prompt tokens, including cached ones.
+    pub input_tokens: u64,
+    /// Prompt tokens the provider served from its cache.
+    pub cached_input_tokens: u64,
+    /// Completion tokens.
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Add another count to this one.
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.requests += other.requests;
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+
+    /// Estimated spend at `price`, in USD. Cached input is charged at the
+    /// full input rate, so this errs high for prompts that hit the cache.
+    pub fn cost(&self, price: &ModelPrice) -> f64 {
+        (self.input_tokens as f64 * price.input + self.output_tokens as f64 * price.output)
+            / 1_000_000.0
+    }
+}
+
+/// What a model charges, in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+}
+
+impl ModelPrice {
+    pub const fn new(input: f64, output: f64) -> Self {
+        Self { input, output }
+    }
+}
+
+/// List prices for common models, matched against the model name without
+/// its provider. The longest matching prefix wins, so dated releases and
+/// OpenRouter names are covered too.
+pub const BUILTIN_PRICES: &[(&str, ModelPrice)] = &[
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0)),
+    ("claude-opus-4-5", ModelPrice::new(5.0, 25.0)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-haiku-4-5", ModelPrice::new(1.0, 5.0)),
+    ("claude-3-5-haiku", ModelPrice::new(0.8, 4.0)),
+    ("gpt-5", ModelPrice::new(1.25, 10.0)),
+    ("gpt-5-mini", ModelPrice::new(0.25, 2.0)),
+    ("gpt-5-nano", ModelPrice::new(0.05, 0.4)),
+    ("gpt-4.1", ModelPrice::new(2.0, 8.0)),
+    ("gpt-4.1-mini", ModelPrice::new(0.4, 1.6)),
+    ("gpt-4.1-nano", ModelPrice::new(0.1, 0.4)),
+    ("gpt-4o", ModelPrice::new(2.5, 10.0)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.6)),
+    ("o3", ModelPrice::new(2.0, 8.0)),
+    ("o3-mini", ModelPrice::new(1.1, 4.4)),
+    ("o4-mini", ModelPrice::new(1.1, 4.4)),
+    ("deepseek-chat", ModelPrice::new(0.27, 1.1)),
+    ("deepseek-reasoner", ModelPrice::new(0.55, 2.19)),
+];
+
+/// The price of `model` (a full `provider/model` name): an exact match in
+/// `configured`, else the built-in price for its model name.
+pub fn model_price(model: &str, configured: &HashMap<String, ModelPrice>) -> Option<ModelPrice> {
+    if let Some(price) = configured.get(model) {
+        return Some(*price);
+    }
+    let name = model.rsplit('/').next().unwrap_or(model);
+    BUILTIN_PRICES
+        .iter()
+        .filter(|(prefix, _)| name.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, price)| *price)
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    requests: AtomicU64,
+    input_tokens: AtomicU64,
+    cached_input_tokens: AtomicU64,
+    output_tokens: AtomicU64,
+}
+
+impl Counters {
+    fn add(&self, usage: &rig::completion::Usage) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.input_tokens
+            .fetch_add(usage.input_tokens, Ordering::Relaxed);
+        self.cached_input_tokens
+            .fetch_add(usage.cached_input_tokens, Ordering::Relaxed);
+        self.output_tokens
+            .fetch_add(usage.output_tokens, Ordering::Relaxed);
+    }
+
+    fn load(&self) -> TokenUsage {
+        TokenUsage {
+            requests: self.requests.load(Ordering::Relaxed),
+            input_tokens: self.input_tokens.load(Ordering::Relaxed),
+            cached_input_tokens: self.cached_input_tokens.load(Ordering::Relaxed),
+            output_tokens: self.output_tokens.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Usage counters keyed by agent ID, then model name.
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    counters: RwLock<HashMap<String, HashMap<String, Arc<Counters>>>>,
+}
+
+impl UsageTracker {
+    /// Add one completion's usage by `agent_id` on `model`.
+    pub fn record(&self, agent_id: &str, model: &str, usage: &rig::completion::Usage) {
+        let existing = self
+            .counters
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(agent_id)
+            .and_then(|models| models.get(model))
+            .cloned();
+        let counters = existing.unwrap_or_else(|| {
+            self.counters
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .entry(agent_id.to_string())
+                .or_default()
+                .entry(model.to_string())
+                .or_default()
+                .clone()
+        });
+        counters.add(usage);
+    }
+
+    /// Usage so far, by agent ID and then model name.
+    pub fn snapshot(&self) -> HashMap<String, HashMap<String, TokenUsage>> {
+        self.counters
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(agent_id, models)| {
+                let models = models
+                    .iter()
+                    .map(|(model, counters)| (model.clone(), counters.load()))
+                    .collect();
+                (agent_id.clone(), models)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_accumulates_per_agent_and_model() {
+        let tracker = UsageTracker::default();
+        let usage = |input_tokens, output_tokens| rig::completion::Usage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+            cached_input_tokens: 0,
+        };
+        tracker.record("main", "anthropic/claude-sonnet-4", &usage(100, 20));
+        tracker.record("main", "anthropic/claude-sonnet-4", &usage(50, 5));
+        tracker.record("main", "openai/gpt-4.1-mini", &usage(10, 1));
+        tracker.record("other", "anthropic/claude-sonnet-4", &usage(7, 3));
+
+        let snapshot = tracker.snapshot();
+        assert_eq!(
+            snapshot["main"]["anthropic/claude-sonnet-4"],
+            TokenUsage {
+                requests: 2,
+                input_tokens: 150,
+                cached_input_tokens: 0,
+                output_tokens: 25,
+            }
+        );
+        assert_eq!(snapshot["main"]["openai/gpt-4.1-mini"].requests, 1);
+        assert_eq!(
+            snapshot["other"]["anthropic/claude-sonnet-4"].input_tokens,
+            7
+        );
+    }
+
+    #[test]
+    fn prices_prefer_config_then_the_longest_builtin_prefix() {
+        let configured = HashMap::from([(
+            "anthropic/claude-sonnet-4-20250514".to_string(),
+            ModelPrice::new(1.0, 2.0),
+        )]);
+        let price = |model| model_price(model, &configured).map(|price| price.input);
+
+        assert_eq!(price("anthropic/claude-sonnet-4-20250514"), Some(1.0));
+        assert_eq!(price("anthropic/claude-sonnet-4-5"), Some(3.0));
+        assert_eq!(price("openrouter/openai/gpt-4.1-mini"), Some(0.4));
+        assert_eq!(price("openai/gpt-4.1"), Some(2.0));
+        assert_eq!(price("ollama/llama3"), None);
+
+        let usage = TokenUsage {
+            requests: 1,
+            input_tokens: 2_000_000,
+            cached_input_tokens: 0,
+            output_tokens: 100_000,
+        };
+        assert_eq!(usage.cost(&ModelPrice::new(3.0, 15.0)), 7.5);
+    }
+}
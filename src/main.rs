@@ -122,6 +122,8 @@ struct ActiveChannel {
     /// routing task so status updates (e.g. typing indicators) target the
     /// most recent message rather than the first one the channel ever received.
     latest_message: Arc<tokio::sync::RwLock<spacebot::InboundMessage>>,
+    /// Re-registered with the API when its entry there was evicted as idle.
+    state: spacebot::agent::channel::ChannelState,
     /// Retained so the outbound routing task stays alive.
     _outbound_handle: tokio::task::JoinHandle<()>,
 }
@@ -610,11 +612,31 @@ async fn run(
     let (agent_remove_tx, mut agent_remove_rx) = mpsc::channel::<String>(8);
 
     // Start HTTP API server if enabled
-    let api_state = Arc::new(spacebot::api::ApiState::new_with_provider_sender(
-        provider_tx,
-        agent_tx,
-        agent_remove_tx,
-    ));
+    let mut api_state =
+        spacebot::api::ApiState::new_with_provider_sender(provider_tx, agent_tx, agent_remove_tx);
+    if let Some(capacity) = config.api.event_channel_capacity {
+        api_state = api_state.with_event_capacity(capacity);
+    }
+    let api_state = Arc::new(api_state);
+    api_state.set_debug_replay_token(config.api.debug_replay_token.clone());
+    api_state.set_events_token(config.api.events_token.clone());
+    api_state.set_admin_token(config.api.admin_token.clone());
+    if let Some(parallelism) = config.api.event_forwarders {
+        api_state.set_event_forwarders(parallelism);
+    }
+    if let Some(size) = config.api.event_replay_buffer {
+        api_state.event_tx.set_replay_buffer(size);
+    }
+    if let Some(secs) = config.api.sse_keepalive_secs {
+        api_state.set_sse_keepalive(std::time::Duration::from_secs(secs));
+    }
+    api_state.set_channel_limits(spacebot::api::ChannelLimits {
+        idle_timeout: config
+            .api
+            .channel_idle_timeout_secs
+            .map(std::time::Duration::from_secs),
+        max_channels: config.api.max_tracked_channels,
+    });
 
     // Start background update checker
     spacebot::update::spawn_update_checker(api_state.update_status.clone());
@@ -681,7 +703,11 @@ async fn run(
     // Initialize the language for all text lookups (must happen before PromptEngine/tools)
     spacebot::prompts::text::init("en").with_context(|| "failed to initialize language")?;
 
-    // Create the PromptEngine with bundled templates (no file watching, no user overrides)
+    // Overrides in {instance_dir}/prompts replace bundled texts; the file
+    // watcher reloads them on change.
+    spacebot::prompts::text::reload_overrides(&config.instance_dir.join("prompts"));
+
+    // Create the PromptEngine with bundled templates and any overrides
     let prompt_engine = spacebot::prompts::PromptEngine::new("en")
         .with_context(|| "failed to initialize prompt engine")?;
 
@@ -838,17 +864,7 @@ async fn run(
                         agent.config.logs_dir(),
                     );
 
-                    // Register the channel's status block with the API for snapshot queries
-                    api_state.register_channel_status(
-                        conversation_id.clone(),
-                        channel.state.status_block.clone(),
-                    ).await;
-
-                    // Register the channel state for API-driven cancellation
-                    api_state.register_channel_state(
-                        conversation_id.clone(),
-                        channel.state.clone(),
-                    ).await;
+                    let channel_state = channel.state.clone();
 
                     // Backfill recent message history from the platform
                     let backfill_count = agent.config.history_backfill_count();
@@ -970,6 +986,7 @@ async fn run(
                     active_channels.insert(conversation_id.clone(), ActiveChannel {
                         message_tx: channel_tx,
                         latest_message,
+                        state: channel_state,
                         _outbound_handle: outbound_handle,
                     });
 
@@ -986,6 +1003,11 @@ async fn run(
                     // (typing indicators, reactions) targets this message
                     *active.latest_message.write().await = message.clone();
 
+                    // Register the channel's status block and state with the
+                    // API for snapshot queries and cancellation, or keep them
+                    // from being evicted as idle
+                    api_state.touch_channel(&conversation_id, &active.state).await;
+
                     // Emit inbound message to SSE clients
                     let sender_name = message.formatted_author.clone().or_else(|| {
                         message
@@ -1529,6 +1551,17 @@ async fn initialize_agents(
             }
         }
 
+        // Re-arm persisted reminders; channels pick the scheduler up from RuntimeConfig
+        let reminders = Arc::new(spacebot::cron::ReminderScheduler::new(
+            agent.deps.agent_id.clone(),
+            Arc::new(spacebot::cron::ReminderStore::new(agent.db.sqlite.clone())),
+            agent.deps.event_tx.clone(),
+        ));
+        if let Err(error) = reminders.start().await {
+            tracing::warn!(agent_id = %agent_id, %error, "failed to load reminders from database");
+        }
+        agent.deps.runtime_config.set_reminders(reminders);
+
         // Store cron tool on deps so each channel can register it on its own tool server
         let cron_tool = spacebot::tools::CronTool::new(store.clone(), scheduler.clone());
         agent.deps.cron_tool = Some(cron_tool);
@@ -1577,7 +1610,9 @@ async fn initialize_agents(
         let mut sessions = std::collections::HashMap::new();
         for (agent_id, agent) in agents.iter() {
             let browser_config = (**agent.deps.runtime_config.browser_config.load()).clone();
+            let shell_config = (**agent.deps.runtime_config.shell_config.load()).clone();
             let brave_search_key = (**agent.deps.runtime_config.brave_search_key.load()).clone();
+            let web_fetch_config = (**agent.deps.runtime_config.web_fetch_config.load()).clone();
             let conversation_logger =
                 spacebot::conversation::history::ConversationLogger::new(agent.db.sqlite.clone());
             let channel_store = spacebot::conversation::ChannelStore::new(agent.db.sqlite.clone());
@@ -1586,11 +1621,13 @@ async fn initialize_agents(
                 conversation_logger,
                 channel_store,
                 browser_config,
+                shell_config,
                 agent.config.screenshot_dir(),
                 brave_search_key,
+                web_fetch_config,
                 agent.deps.runtime_config.workspace_dir.clone(),
                 agent.deps.runtime_config.instance_dir.clone(),
-            );
+            )?;
             let store = spacebot::agent::cortex_chat::CortexChatStore::new(agent.db.sqlite.clone());
             let session = spacebot::agent::cortex_chat::CortexChatSession::new(
                 agent.deps.clone(),
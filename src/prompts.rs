@@ -2,4 +2,4 @@ pub mod engine;
 pub mod text;
 
 pub use engine::{PromptEngine, SkillInfo};
-pub use text::{get as get_text, init as init_language};
+pub use text::{get as get_text, init as init_language, render as render_text};
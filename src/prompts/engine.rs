@@ -6,9 +6,10 @@ use std::sync::Arc;
 
 /// Template engine for rendering system prompts with dynamic variables.
 ///
-/// Prompts are bundled in the binary as `include_str!` embedded templates.
-/// Language selection is done at initialization and templates are not
-/// reloadable at runtime (no file watching, no hot reload).
+/// Templates come from [`crate::prompts::text`]: bundled in the binary,
+/// unless overridden in the instance's prompts directory. An engine is
+/// fixed once built; reloading overrides means building a new one (see
+/// `RuntimeConfig::reload_prompts`).
 #[derive(Clone)]
 pub struct PromptEngine {
     /// The MiniJinja environment holding all templates for the configured language.
@@ -171,6 +172,7 @@ impl PromptEngine {
         &self,
         browser_enabled: bool,
         web_search_enabled: bool,
+        web_fetch_enabled: bool,
         opencode_enabled: bool,
     ) -> Result<String> {
         self.render(
@@ -178,6 +180,7 @@ impl PromptEngine {
             context! {
                 browser_enabled => browser_enabled,
                 web_search_enabled => web_search_enabled,
+                web_fetch_enabled => web_fetch_enabled,
                 opencode_enabled => opencode_enabled,
             },
         )
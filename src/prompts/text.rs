@@ -3,6 +3,12 @@
 //! This module provides compile-time embedding of all language variants,
 //! with runtime selection via a global OnceLock.
 //!
+//! Any text can be overridden without a rebuild by a file at
+//! `{instance_dir}/prompts/{language}/{key}.md.j2`, e.g.
+//! `prompts/en/tools/shell.md.j2`. [`reload_overrides`] reads them into a
+//! map that is swapped in whole, so lookups see either the old overrides or
+//! the new ones, never a mix.
+//!
 //! # Usage
 //!
 //! ```rust
@@ -12,12 +18,28 @@
 //! // Anywhere:
 //! let desc = prompts::text::get("tools/file");
 //! let prompt = prompts::text::get("channel");
+//!
+//! // With `{{variable}}` placeholders filled in:
+//! let vars = HashMap::from([("workspace", "/data/workspace")]);
+//! let desc = prompts::text::render("tools/file", &vars);
 //! ```
 
-use std::sync::OnceLock;
+use arc_swap::ArcSwap;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, OnceLock};
 
 static LANGUAGE: OnceLock<String> = OnceLock::new();
 
+/// Override texts by key. Leaked so [`get`] can keep handing out
+/// `&'static str`; only files whose content changed are leaked again, so
+/// this grows with operator edits, not with reloads.
+type OverrideStore = ArcSwap<HashMap<String, &'static str>>;
+
+/// The overrides [`get`] serves. Only [`reload_overrides`] writes them.
+static OVERRIDES: LazyLock<OverrideStore> = LazyLock::new(|| ArcSwap::from_pointee(HashMap::new()));
+
 /// Initialize the language for text lookups.
 /// Must be called once at startup before any text lookups occur.
 /// Returns Err if the language code is not supported.
@@ -46,7 +68,176 @@ fn language() -> &'static str {
 /// Get text for the given key in the configured language.
 /// Falls back to English if the language or key is not found.
 pub fn get(key: &str) -> &'static str {
-    lookup(language(), key)
+    get_from(&OVERRIDES, key)
+}
+
+fn get_from(overrides: &OverrideStore, key: &str) -> &'static str {
+    if let Some(text) = overrides.load().get(key) {
+        return text;
+    }
+    let text = lookup(language(), key);
+    if text.is_empty() {
+        tracing::error!(key, "unknown text key");
+    }
+    text
+}
+
+/// Get text for the given key with its `{{name}}` placeholders replaced by
+/// the matching values in `vars`.
+///
+/// Placeholders with no value are left as they are and logged, so a typo
+/// shows up in the output instead of silently vanishing. `\{{` produces a
+/// literal `{{`.
+pub fn render(key: &str, vars: &HashMap<&str, &str>) -> String {
+    interpolate(key, get(key), vars)
+}
+
+fn interpolate(key: &str, template: &str, vars: &HashMap<&str, &str>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '\\']) {
+        output.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if let Some(escaped) = rest.strip_prefix("\\{{") {
+            output.push_str("{{");
+            rest = escaped;
+        } else if let Some((name, after)) = placeholder(rest) {
+            match vars.get(name) {
+                Some(value) => output.push_str(value),
+                None => {
+                    tracing::warn!(key, name, "no value for prompt placeholder");
+                    output.push_str(&rest[..rest.len() - after.len()]);
+                }
+            }
+            rest = after;
+        } else {
+            // A lone `{` or `\`, or the outer brace of `{{{name}}}`.
+            output.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Split a leading `{{ name }}` off `text` into the name and what follows.
+fn placeholder(text: &str) -> Option<(&str, &str)> {
+    let inner = text.strip_prefix("{{")?;
+    let end = inner.find("}}")?;
+    let name = inner[..end].trim();
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    valid.then(|| (name, &inner[end + 2..]))
+}
+
+/// What a [`reload_overrides`] call picked up.
+#[derive(Debug, Default, Serialize)]
+pub struct OverrideReport {
+    /// Keys now served from an override file.
+    pub loaded: Vec<String>,
+    /// Files that were skipped, keyed by relative path.
+    pub rejected: Vec<RejectedOverride>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RejectedOverride {
+    pub path: String,
+    pub error: String,
+}
+
+/// Re-read the override files under `prompts_dir` for the configured
+/// language and swap them in.
+///
+/// A file that can't be read, doesn't name a known key, or isn't a valid
+/// template is skipped: its key keeps the override it had before, or the
+/// bundled text if it had none. A missing directory clears all overrides.
+pub fn reload_overrides(prompts_dir: &Path) -> OverrideReport {
+    reload_into(&OVERRIDES, prompts_dir)
+}
+
+fn reload_into(store: &OverrideStore, prompts_dir: &Path) -> OverrideReport {
+    let language_dir = prompts_dir.join(language());
+    let current = store.load();
+    let mut overrides = HashMap::new();
+    let mut report = OverrideReport::default();
+
+    let mut files = Vec::new();
+    collect_templates(&language_dir, &mut files);
+    for path in files {
+        let relative = override_path(path.strip_prefix(&language_dir).unwrap_or(&path));
+        let key = relative.trim_end_matches(".md.j2").to_string();
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|content| validate_override(&key, &content).map(|()| content));
+        match content {
+            Ok(content) => {
+                let text: &'static str = match current.get(&key) {
+                    Some(existing) if *existing == content => existing,
+                    _ => Box::leak(content.into_boxed_str()),
+                };
+                overrides.insert(key.clone(), text);
+                report.loaded.push(key);
+            }
+            Err(error) => {
+                tracing::warn!(path = %path.display(), %error, "skipping prompt override");
+                if let Some(existing) = current.get(&key) {
+                    overrides.insert(key, *existing);
+                }
+                report.rejected.push(RejectedOverride {
+                    path: relative,
+                    error,
+                });
+            }
+        }
+    }
+
+    report.loaded.sort();
+    store.store(Arc::new(overrides));
+    tracing::info!(
+        loaded = report.loaded.len(),
+        rejected = report.rejected.len(),
+        "prompt overrides reloaded"
+    );
+    report
+}
+
+/// `relative` with `/` between its components whatever the platform, so
+/// `tools\reminder.md.j2` on Windows names the `tools/reminder` key.
+fn override_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Every `*.md.j2` file under `dir`, recursively.
+fn collect_templates(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_templates(&path, files);
+        } else if path.to_string_lossy().ends_with(".md.j2") {
+            files.push(path);
+        }
+    }
+}
+
+fn validate_override(key: &str, content: &str) -> Result<(), String> {
+    if lookup("en", key).is_empty() {
+        return Err(format!("unknown text key '{key}'"));
+    }
+    minijinja::Environment::new()
+        .template_from_str(content)
+        .map(|_| ())
+        .map_err(|error| format!("invalid template: {error}"))
 }
 
 /// Lookup function generated by the macro.
@@ -134,11 +325,29 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         }
         ("en", "tools/shell") => include_str!("../../prompts/en/tools/shell_description.md.j2"),
         ("en", "tools/file") => include_str!("../../prompts/en/tools/file_description.md.j2"),
+        ("en", "tools/read_file") => {
+            include_str!("../../prompts/en/tools/read_file_description.md.j2")
+        }
+        ("en", "tools/read_structured") => {
+            include_str!("../../prompts/en/tools/read_structured_description.md.j2")
+        }
+        ("en", "tools/write_file") => {
+            include_str!("../../prompts/en/tools/write_file_description.md.j2")
+        }
+        ("en", "tools/edit_file") => {
+            include_str!("../../prompts/en/tools/edit_file_description.md.j2")
+        }
+        ("en", "tools/search_files") => {
+            include_str!("../../prompts/en/tools/search_files_description.md.j2")
+        }
         ("en", "tools/exec") => include_str!("../../prompts/en/tools/exec_description.md.j2"),
         ("en", "tools/browser") => include_str!("../../prompts/en/tools/browser_description.md.j2"),
         ("en", "tools/web_search") => {
             include_str!("../../prompts/en/tools/web_search_description.md.j2")
         }
+        ("en", "tools/web_fetch") => {
+            include_str!("../../prompts/en/tools/web_fetch_description.md.j2")
+        }
         ("en", "tools/memory_save") => {
             include_str!("../../prompts/en/tools/memory_save_description.md.j2")
         }
@@ -155,6 +364,9 @@ fn lookup(lang: &str, key: &str) -> &'static str {
             include_str!("../../prompts/en/tools/send_file_description.md.j2")
         }
         ("en", "tools/cron") => include_str!("../../prompts/en/tools/cron_description.md.j2"),
+        ("en", "tools/reminder") => {
+            include_str!("../../prompts/en/tools/reminder_description.md.j2")
+        }
         ("en", "tools/send_message_to_another_channel") => {
             include_str!("../../prompts/en/tools/send_message_description.md.j2")
         }
@@ -170,9 +382,75 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         }
 
         // Unknown key in English
-        _ => {
-            tracing::error!(key, "unknown text key");
-            ""
-        }
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_interpolated() {
+        let vars = HashMap::from([("name", "Spacebot"), ("workspace", "/data/ws")]);
+        let render = |template| interpolate("test", template, &vars);
+
+        assert_eq!(
+            render("I am {{name}}, working in {{ workspace }}."),
+            "I am Spacebot, working in /data/ws."
+        );
+        assert_eq!(render("no placeholders"), "no placeholders");
+        // Unknown placeholders stay put.
+        assert_eq!(render("{{name}} uses {{tools}}"), "Spacebot uses {{tools}}");
+        // Extra braces around a placeholder are kept as literals.
+        assert_eq!(render("{{{name}}}"), "{Spacebot}");
+        assert_eq!(render("{{{{name}}}}"), "{{Spacebot}}");
+        assert_eq!(render("{ {{name}} }"), "{ Spacebot }");
+        assert_eq!(render("{\"json\": {{name}}}"), "{\"json\": Spacebot}");
+        // Not placeholders at all.
+        assert_eq!(render("{{ a b }} {{}} {{name"), "{{ a b }} {{}} {{name");
+        // Escaping.
+        assert_eq!(render("\\{{name}} is {{name}}"), "{{name}} is Spacebot");
+        assert_eq!(render("C:\\path {{name}}"), "C:\\path Spacebot");
+    }
+
+    #[test]
+    fn overrides_replace_bundled_text_and_skip_malformed_files() {
+        let bundled = lookup("en", "tools/reminder");
+        let prompts = tempfile::tempdir().expect("tempdir");
+        let tools = prompts.path().join("en/tools");
+        std::fs::create_dir_all(&tools).expect("prompts dir");
+        let write = |name: &str, content: &str| {
+            std::fs::write(tools.join(name), content).expect("write override")
+        };
+
+        // A store of its own, so tests reading the real texts in parallel
+        // never see these.
+        let store = OverrideStore::from_pointee(HashMap::new());
+
+        write("reminder.md.j2", "Set a reminder.");
+        write("no_such_tool.md.j2", "Unused.");
+        let report = reload_into(&store, prompts.path());
+        assert_eq!(report.loaded, vec!["tools/reminder"]);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].path, "tools/no_such_tool.md.j2");
+        assert_eq!(get_from(&store, "tools/reminder"), "Set a reminder.");
+        assert_eq!(get("tools/reminder"), bundled);
+
+        // A broken edit keeps the last good override.
+        write("reminder.md.j2", "Set a {% if reminder.");
+        let report = reload_into(&store, prompts.path());
+        assert!(report.loaded.is_empty());
+        assert_eq!(get_from(&store, "tools/reminder"), "Set a reminder.");
+
+        std::fs::remove_dir_all(prompts.path().join("en")).expect("remove overrides");
+        reload_into(&store, prompts.path());
+        assert_eq!(get_from(&store, "tools/reminder"), bundled);
+    }
+
+    #[test]
+    fn override_keys_use_forward_slashes() {
+        let relative: std::path::PathBuf = ["tools", "reminder.md.j2"].iter().collect();
+        assert_eq!(override_path(&relative), "tools/reminder.md.j2");
     }
 }
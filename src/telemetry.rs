@@ -4,4 +4,4 @@ mod registry;
 mod server;
 
 pub use registry::Metrics;
-pub use server::start_metrics_server;
+pub use server::{metrics_response, start_metrics_server};
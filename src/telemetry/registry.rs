@@ -1,7 +1,8 @@
 //! Global metrics registry and metric handle definitions.
 
 use prometheus::{
-    Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGaugeVec, Opts, Registry,
+    Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry,
 };
 
 use std::sync::LazyLock;
@@ -31,6 +32,21 @@ pub struct Metrics {
     /// Total memory save (write) operations.
     pub memory_writes_total: IntCounter,
 
+    /// Total events sent on the API event stream.
+    /// Labels: agent_id (empty for instance-wide events), event_type.
+    pub api_events_total: IntCounterVec,
+
+    /// Total inbound and outbound messages seen on the API event stream.
+    /// Labels: agent_id, direction ("inbound" or "outbound").
+    pub messages_total: IntCounterVec,
+
+    /// ProcessEvents an agent's API forwarder skipped because it fell behind.
+    /// Label: agent_id.
+    pub api_events_lagged_total: IntCounterVec,
+
+    /// API events SSE clients missed because they fell behind.
+    pub sse_events_dropped_total: IntCounter,
+
     // -- Histograms --
     /// LLM request duration in seconds.
     pub llm_request_duration_seconds: HistogramVec,
@@ -48,6 +64,12 @@ pub struct Metrics {
     // TODO: Not wired to any call site. Needs periodic store queries or
     // inc/dec in MemoryStore::save()/delete() to reflect actual counts.
     pub memory_entry_count: IntGaugeVec,
+
+    /// Receivers subscribed to the API event stream. Set when scraped.
+    pub sse_subscribers: IntGauge,
+
+    /// Seconds since the control API started. Set when scraped.
+    pub uptime_seconds: IntGauge,
 }
 
 impl Metrics {
@@ -81,6 +103,36 @@ impl Metrics {
         )
         .expect("hardcoded metric descriptor");
 
+        let api_events_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_api_events_total",
+                "Total events sent on the API event stream",
+            ),
+            &["agent_id", "event_type"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let messages_total = IntCounterVec::new(
+            Opts::new("spacebot_messages_total", "Total messages per agent"),
+            &["agent_id", "direction"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let api_events_lagged_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_api_events_lagged_total",
+                "Agent events skipped by a lagging API forwarder",
+            ),
+            &["agent_id"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let sse_events_dropped_total = IntCounter::new(
+            "spacebot_sse_events_dropped_total",
+            "API events missed by lagging SSE clients",
+        )
+        .expect("hardcoded metric descriptor");
+
         let llm_request_duration_seconds = HistogramVec::new(
             HistogramOpts::new(
                 "spacebot_llm_request_duration_seconds",
@@ -130,6 +182,30 @@ impl Metrics {
         registry
             .register(Box::new(memory_writes_total.clone()))
             .expect("hardcoded metric");
+        let sse_subscribers = IntGauge::new(
+            "spacebot_sse_subscribers",
+            "Receivers subscribed to the API event stream",
+        )
+        .expect("hardcoded metric descriptor");
+
+        let uptime_seconds = IntGauge::new(
+            "spacebot_uptime_seconds",
+            "Seconds since the control API started",
+        )
+        .expect("hardcoded metric descriptor");
+
+        registry
+            .register(Box::new(api_events_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(messages_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(api_events_lagged_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(sse_events_dropped_total.clone()))
+            .expect("hardcoded metric");
         registry
             .register(Box::new(llm_request_duration_seconds.clone()))
             .expect("hardcoded metric");
@@ -142,6 +218,12 @@ impl Metrics {
         registry
             .register(Box::new(memory_entry_count.clone()))
             .expect("hardcoded metric");
+        registry
+            .register(Box::new(sse_subscribers.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(uptime_seconds.clone()))
+            .expect("hardcoded metric");
 
         Self {
             registry,
@@ -149,10 +231,16 @@ impl Metrics {
             tool_calls_total,
             memory_reads_total,
             memory_writes_total,
+            api_events_total,
+            messages_total,
+            api_events_lagged_total,
+            sse_events_dropped_total,
             llm_request_duration_seconds,
             tool_call_duration_seconds,
             active_workers,
             memory_entry_count,
+            sse_subscribers,
+            uptime_seconds,
         }
     }
 
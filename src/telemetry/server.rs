@@ -5,7 +5,7 @@ use crate::config::MetricsConfig;
 
 use axum::Router;
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use prometheus::Encoder as _;
 use tokio::sync::watch;
@@ -58,6 +58,12 @@ pub async fn start_metrics_server(
 }
 
 async fn metrics_handler() -> impl IntoResponse {
+    metrics_response()
+}
+
+/// The global registry in the Prometheus text format, for any server that
+/// exposes it.
+pub fn metrics_response() -> Response {
     let metrics = Metrics::global();
     let encoder = prometheus::TextEncoder::new();
     let mut buffer = Vec::new();
@@ -6,17 +6,20 @@
 //! ## ToolServer Topology
 //!
 //! **Channel ToolServer** (one per channel):
-//! - `reply`, `branch`, `spawn_worker`, `route`, `cancel`, `skip`, `react` — added
-//!   dynamically per conversation turn via `add_channel_tools()` /
-//!   `remove_channel_tools()` because they hold per-channel state.
+//! - `reply`, `branch`, `spawn_worker`, `route`, `cancel`, `skip`, `react`,
+//!   `reminder` — added dynamically per conversation turn via
+//!   `add_channel_tools()` / `remove_channel_tools()` because they hold
+//!   per-channel state.
 //! - No memory tools — the channel delegates memory work to branches.
 //!
 //! **Branch ToolServer** (one per branch, isolated):
 //! - `memory_save` + `memory_recall` + `memory_delete` — registered at creation
 //!
 //! **Worker ToolServer** (one per worker, created at spawn time):
-//! - `shell`, `file`, `exec` — stateless, registered at creation
+//! - `shell`, `file`, `read_file`, `read_structured`, `write_file`,
+//!   `edit_file`, `search_files`, `exec` — stateless, registered at creation
 //! - `set_status` — per-worker instance, registered at creation
+//! - `browser`, `web_search`, `web_fetch` — when enabled in the agent config
 //!
 //! **Cortex ToolServer** (one per agent):
 //! - `memory_save` — registered at startup
@@ -26,21 +29,28 @@ pub mod browser;
 pub mod cancel;
 pub mod channel_recall;
 pub mod cron;
+pub mod edit_file;
 pub mod exec;
 pub mod file;
 pub mod memory_delete;
 pub mod memory_recall;
 pub mod memory_save;
 pub mod react;
+pub mod read_file;
+pub mod read_structured;
+pub mod reminder;
 pub mod reply;
 pub mod route;
+pub mod search_files;
 pub mod send_file;
 pub mod send_message_to_another_channel;
 pub mod set_status;
 pub mod shell;
 pub mod skip;
 pub mod spawn_worker;
+pub mod web_fetch;
 pub mod web_search;
+pub mod write_file;
 
 pub use branch_tool::{BranchArgs, BranchError, BranchOutput, BranchTool};
 pub use browser::{
@@ -52,6 +62,7 @@ pub use channel_recall::{
     ChannelRecallArgs, ChannelRecallError, ChannelRecallOutput, ChannelRecallTool,
 };
 pub use cron::{CronArgs, CronError, CronOutput, CronTool};
+pub use edit_file::{EditFileArgs, EditFileError, EditFileOutput, EditFileTool, RejectedHunk};
 pub use exec::{EnvVar, ExecArgs, ExecError, ExecOutput, ExecResult, ExecTool};
 pub use file::{FileArgs, FileEntry, FileEntryOutput, FileError, FileOutput, FileTool, FileType};
 pub use memory_delete::{
@@ -64,25 +75,40 @@ pub use memory_save::{
     AssociationInput, MemorySaveArgs, MemorySaveError, MemorySaveOutput, MemorySaveTool,
 };
 pub use react::{ReactArgs, ReactError, ReactOutput, ReactTool};
+pub use read_file::{ReadFileArgs, ReadFileError, ReadFileOutput, ReadFileTool};
+pub use read_structured::{
+    ReadStructuredArgs, ReadStructuredError, ReadStructuredOutput, ReadStructuredTool,
+};
+pub use reminder::{ReminderArgs, ReminderError, ReminderOutput, ReminderTool};
 pub use reply::{RepliedFlag, ReplyArgs, ReplyError, ReplyOutput, ReplyTool, new_replied_flag};
 pub use route::{RouteArgs, RouteError, RouteOutput, RouteTool};
+pub use search_files::{
+    SearchArgs, SearchFilesError, SearchFilesOutput, SearchFilesTool, SearchMatch,
+};
 pub use send_file::{SendFileArgs, SendFileError, SendFileOutput, SendFileTool};
 pub use send_message_to_another_channel::{
     SendMessageArgs, SendMessageError, SendMessageOutput, SendMessageTool,
 };
 pub use set_status::{SetStatusArgs, SetStatusError, SetStatusOutput, SetStatusTool};
-pub use shell::{ShellArgs, ShellError, ShellOutput, ShellResult, ShellTool};
+pub use shell::{
+    AUDIT_LOG_PATH, AuditLog, RateLimiter, ShellArgs, ShellError, ShellOutput, ShellResult,
+    ShellTool, WorkspaceError,
+};
 pub use skip::{SkipArgs, SkipError, SkipFlag, SkipOutput, SkipTool, new_skip_flag};
 pub use spawn_worker::{SpawnWorkerArgs, SpawnWorkerError, SpawnWorkerOutput, SpawnWorkerTool};
+pub use web_fetch::{WebFetchArgs, WebFetchError, WebFetchOutput, WebFetchTool};
 pub use web_search::{SearchResult, WebSearchArgs, WebSearchError, WebSearchOutput, WebSearchTool};
+pub use write_file::{WriteFileArgs, WriteFileError, WriteFileOutput, WriteFileTool};
 
 use crate::agent::channel::ChannelState;
-use crate::config::BrowserConfig;
+use crate::config::{BrowserConfig, ShellConfig, WebFetchConfig};
 use crate::memory::MemorySearch;
-use crate::{AgentId, ChannelId, OutboundResponse, ProcessEvent, WorkerId};
+use crate::{AgentId, ChannelId, OutboundResponse, ProcessEvent, ProcessId, WorkerId};
 use rig::tool::Tool as _;
 use rig::tool::server::{ToolServer, ToolServerHandle};
-use std::path::PathBuf;
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
 
@@ -93,31 +119,275 @@ pub const MAX_TOOL_OUTPUT_BYTES: usize = 50_000;
 /// Maximum number of entries returned by directory listings.
 pub const MAX_DIR_ENTRIES: usize = 500;
 
-/// Truncate a string to a byte limit, appending a notice if truncated.
+/// Tools that shell out to external programs declare them here, so a missing
+/// dependency is reported up front instead of failing halfway through a task.
+pub trait RequiresBinaries: rig::tool::Tool {
+    /// The configuration that decides which programs the tool runs.
+    type Config;
+
+    /// Executables that must be on PATH, or paths to them, for the tool to
+    /// work under `config`.
+    fn required_binaries(config: &Self::Config) -> Vec<String>;
+}
+
+/// Dependency health of a single tool, as reported by the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStatus {
+    pub name: String,
+    pub required_binaries: Vec<String>,
+    pub missing_binaries: Vec<String>,
+    /// True when any required binary is missing. The tool stays registered but
+    /// calls will fail until the dependency is installed.
+    pub degraded: bool,
+}
+
+impl ToolStatus {
+    /// Check a tool's declared binaries against the given PATH value.
+    pub fn check(name: &str, required_binaries: &[&str], search_path: Option<&OsStr>) -> Self {
+        let missing_binaries: Vec<String> = required_binaries
+            .iter()
+            .filter(|binary| find_binary(binary, search_path).is_none())
+            .map(|binary| binary.to_string())
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            required_binaries: required_binaries.iter().map(|b| b.to_string()).collect(),
+            degraded: !missing_binaries.is_empty(),
+            missing_binaries,
+        }
+    }
+
+    /// Check the binaries tool `T` declares for `config`.
+    pub fn of<T: RequiresBinaries>(config: &T::Config, search_path: Option<&OsStr>) -> Self {
+        let binaries = T::required_binaries(config);
+        let binaries: Vec<&str> = binaries.iter().map(String::as_str).collect();
+        Self::check(T::NAME, &binaries, search_path)
+    }
+
+    /// The error a call to this tool should fail with, if it is degraded.
+    pub fn error_message(&self) -> Option<String> {
+        if self.missing_binaries.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "{} requires {} on PATH",
+            self.name,
+            self.missing_binaries.join(", ")
+        ))
+    }
+}
+
+/// Verify a tool's declared binaries on the current PATH before using it.
+pub fn require_binaries<T: RequiresBinaries>(config: &T::Config) -> Result<(), String> {
+    let path = std::env::var_os("PATH");
+    let status = ToolStatus::of::<T>(config, path.as_deref());
+    match status.error_message() {
+        Some(message) => Err(message),
+        None => Ok(()),
+    }
+}
+
+/// Locate an executable in a PATH-style list of directories.
+pub(crate) fn find_binary(binary: &str, search_path: Option<&OsStr>) -> Option<PathBuf> {
+    let candidate = Path::new(binary);
+    if candidate.components().count() > 1 {
+        return is_executable(candidate).then(|| candidate.to_path_buf());
+    }
+
+    std::env::split_paths(search_path?)
+        .flat_map(|dir| {
+            let plain = dir.join(binary);
+            let windows = dir.join(format!("{binary}.exe"));
+            [plain, windows]
+        })
+        .find(|path| is_executable(path))
+}
+
+/// Whether `path` is a file the current user could execute. A file on PATH
+/// without an exec bit can't be run, so it doesn't count.
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Dependency status for every tool a worker of this agent would get.
 ///
-/// Cuts at the last valid char boundary before `max_bytes` so we never split
-/// a multi-byte character. The truncation notice tells the LLM the original
-/// size and how to get the rest (pipe through head/tail or read with offset).
+/// Mirrors the tool list in [`create_worker_tool_server`]. Tools without
+/// external dependencies are included so the list is complete.
+pub fn worker_tool_statuses(runtime_config: &crate::config::RuntimeConfig) -> Vec<ToolStatus> {
+    let path = std::env::var_os("PATH");
+    let path = path.as_deref();
+
+    let mut statuses = vec![
+        ToolStatus::of::<ShellTool>(&runtime_config.shell_config.load(), path),
+        ToolStatus::check(FileTool::NAME, &[], path),
+        ToolStatus::check(ReadFileTool::NAME, &[], path),
+        ToolStatus::check(ReadStructuredTool::NAME, &[], path),
+        ToolStatus::check(WriteFileTool::NAME, &[], path),
+        ToolStatus::check(EditFileTool::NAME, &[], path),
+        ToolStatus::check(SearchFilesTool::NAME, &[], path),
+        ToolStatus::check(ExecTool::NAME, &[], path),
+        ToolStatus::check(SetStatusTool::NAME, &[], path),
+    ];
+
+    let browser_config = runtime_config.browser_config.load();
+    if browser_config.enabled {
+        statuses.push(ToolStatus::of::<BrowserTool>(&browser_config, path));
+    }
+
+    if runtime_config.brave_search_key.load().is_some() {
+        statuses.push(ToolStatus::check(WebSearchTool::NAME, &[], path));
+    }
+
+    if runtime_config.web_fetch_config.load().enabled {
+        statuses.push(ToolStatus::check(WebFetchTool::NAME, &[], path));
+    }
+
+    statuses
+}
+
+/// The description of a tool that works in `workspace`, with the
+/// `{{workspace}}` placeholder filled in so the model knows the path.
+pub(crate) fn workspace_description(key: &str, workspace: &Path) -> String {
+    let workspace = workspace.display().to_string();
+    let vars = std::collections::HashMap::from([("workspace", workspace.as_str())]);
+    crate::prompts::text::render(key, &vars)
+}
+
+/// Truncate a string to a byte limit, keeping its start and its end.
+///
+/// The end of command output is usually what matters (the failing test, the
+/// stack trace, the build error), so 60% of the budget goes to the tail and
+/// 40% to the head, with a marker in the middle saying how much was cut. Both
+/// cuts land on char boundaries so we never split a multi-byte character.
 pub fn truncate_output(value: &str, max_bytes: usize) -> String {
     if value.len() <= max_bytes {
         return value.to_string();
     }
 
-    // Find the last char boundary at or before max_bytes
-    let mut end = max_bytes;
-    while end > 0 && !value.is_char_boundary(end) {
-        end -= 1;
+    let head_bytes = max_bytes * 2 / 5;
+    let mut head_end = head_bytes;
+    while !value.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = value.len() - (max_bytes - head_bytes);
+    while !value.is_char_boundary(tail_start) {
+        tail_start += 1;
     }
 
-    let total = value.len();
-    let truncated_bytes = total - end;
+    let truncated_bytes = tail_start - head_end;
     format!(
-        "{}\n\n[output truncated: showed {end} of {total} bytes ({truncated_bytes} bytes omitted). \
-         Use head/tail/offset to read specific sections]",
-        &value[..end]
+        "{}\n...[truncated {truncated_bytes} bytes]...\n{}",
+        &value[..head_end],
+        &value[tail_start..]
     )
 }
 
+/// Strip NUL bytes and other non-printable control characters from tool
+/// output, keeping newlines, tabs and carriage returns.
+///
+/// Binary-ish output otherwise breaks JSON serialization of tool results or
+/// confuses the model. Returns whether anything was removed so callers can
+/// tell the LLM the output was altered.
+pub fn sanitize_output(value: &str) -> (String, bool) {
+    let is_allowed = |c: char| !c.is_control() || matches!(c, '\n' | '\t' | '\r');
+    if value.chars().all(is_allowed) {
+        return (value.to_string(), false);
+    }
+    (value.chars().filter(|&c| is_allowed(c)).collect(), true)
+}
+
+/// Share of invalid UTF-8 and NUL bytes above which output counts as binary.
+const BINARY_OUTPUT_THRESHOLD: f64 = 0.3;
+
+/// Placeholder for command output that is mostly not text (an image, an
+/// archive), or None for text. Lossy decoding would otherwise flood the
+/// context with replacement characters. Output with the odd stray NUL byte
+/// still counts as text and goes through [`sanitize_output`].
+pub fn binary_output_notice(bytes: &[u8]) -> Option<String> {
+    if bytes.is_empty() {
+        return None;
+    }
+    let non_text: usize = bytes
+        .utf8_chunks()
+        .map(|chunk| chunk.invalid().len() + chunk.valid().bytes().filter(|&b| b == 0).count())
+        .sum();
+    (non_text as f64 / bytes.len() as f64 > BINARY_OUTPUT_THRESHOLD)
+        .then(|| format!("[binary output, {} bytes, not displayed]", bytes.len()))
+}
+
+/// Canonicalize as much of the path as possible, for checking it against the
+/// workspace boundary.
+///
+/// When the path doesn't exist yet (e.g. writing a new file), the deepest
+/// existing ancestor is canonicalized and the remaining components are applied
+/// lexically. Those don't exist on disk, so they can't be symlinks, and `..`
+/// steps back out of the resolved path instead of being kept verbatim.
+pub fn best_effort_canonicalize(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+
+    let components: Vec<Component> = path.components().collect();
+    for split in (1..components.len()).rev() {
+        let ancestor: PathBuf = components[..split].iter().collect();
+        if let Ok(base) = ancestor.canonicalize() {
+            return push_lexically(base, &components[split..]);
+        }
+    }
+    push_lexically(PathBuf::new(), &components)
+}
+
+fn push_lexically(mut base: PathBuf, components: &[Component]) -> PathBuf {
+    for component in components {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                base.pop();
+            }
+            other => base.push(other.as_os_str()),
+        }
+    }
+    base
+}
+
+/// Resolve a workspace-relative path for the file reading and writing tools,
+/// refusing paths that leave the workspace (after following symlinks) or that
+/// match one of the shell's sensitive file patterns.
+pub(crate) fn resolve_workspace_file(
+    workspace: &Path,
+    raw: &str,
+    sensitive_files: &shell::SensitiveFiles,
+) -> Result<PathBuf, String> {
+    let path = best_effort_canonicalize(&workspace.join(raw));
+    let workspace_canonical = best_effort_canonicalize(workspace);
+    let Ok(relative) = path.strip_prefix(&workspace_canonical) else {
+        return Err(format!(
+            "ACCESS DENIED: {raw} is outside the workspace. Only files under {} can be \
+             accessed. Do not attempt to reproduce, guess, or fabricate its contents.",
+            workspace.display()
+        ));
+    };
+
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    if sensitive_files.matches(raw) || sensitive_files.matches(&relative) {
+        return Err(format!(
+            "ACCESS DENIED: {raw} is a protected file and can't be accessed."
+        ));
+    }
+
+    Ok(path)
+}
+
 /// Add per-turn tools to a channel's ToolServer.
 ///
 /// Called when a conversation turn begins. These tools hold per-turn state
@@ -132,6 +402,10 @@ pub async fn add_channel_tools(
     replied_flag: RepliedFlag,
     cron_tool: Option<CronTool>,
 ) -> Result<(), rig::tool::server::ToolServerError> {
+    let reminder_tool = (**state.deps.runtime_config.reminders.load())
+        .clone()
+        .map(|scheduler| ReminderTool::new(scheduler, state.channel_id.clone()));
+
     handle
         .add_tool(ReplyTool::new(
             response_tx.clone(),
@@ -163,6 +437,9 @@ pub async fn add_channel_tools(
     if let Some(cron) = cron_tool {
         handle.add_tool(cron).await?;
     }
+    if let Some(reminder) = reminder_tool {
+        handle.add_tool(reminder).await?;
+    }
     Ok(())
 }
 
@@ -181,8 +458,9 @@ pub async fn remove_channel_tools(
     handle.remove_tool(SkipTool::NAME).await?;
     handle.remove_tool(SendFileTool::NAME).await?;
     handle.remove_tool(ReactTool::NAME).await?;
-    // Cron and send_message removal is best-effort since not all channels have them
+    // Cron, reminder and send_message removal is best-effort since not all channels have them
     let _ = handle.remove_tool(CronTool::NAME).await;
+    let _ = handle.remove_tool(ReminderTool::NAME).await;
     let _ = handle.remove_tool(SendMessageTool::NAME).await;
     Ok(())
 }
@@ -212,22 +490,65 @@ pub fn create_branch_tool_server(
 /// is included when browser automation is enabled in the agent config.
 ///
 /// File operations are restricted to `workspace`. Shell and exec commands are
-/// blocked from accessing sensitive files in `instance_dir`.
+/// blocked from accessing sensitive files in `instance_dir`, and shell commands
+/// are further limited by `shell_config` when an allowlist is configured, and
+/// by the agent's shared `shell_rate_limiter` when it has one. Commands are
+/// recorded in the instance's shell audit log unless `shell_config` turns it
+/// off. Fails if `workspace` doesn't exist.
 pub fn create_worker_tool_server(
     agent_id: AgentId,
     worker_id: WorkerId,
     channel_id: Option<ChannelId>,
     event_tx: broadcast::Sender<ProcessEvent>,
     browser_config: BrowserConfig,
+    shell_config: ShellConfig,
+    shell_rate_limiter: Option<Arc<RateLimiter>>,
     screenshot_dir: PathBuf,
     brave_search_key: Option<String>,
+    web_fetch_config: WebFetchConfig,
     workspace: PathBuf,
     instance_dir: PathBuf,
-) -> ToolServerHandle {
+) -> Result<ToolServerHandle, WorkspaceError> {
+    let audit_log = if shell_config.audit_log {
+        AuditLog::new(instance_dir.join(AUDIT_LOG_PATH))
+    } else {
+        AuditLog::disabled()
+    };
+    let mut shell_tool = ShellTool::new(instance_dir.clone(), workspace.clone())?
+        .with_config(shell_config)
+        .with_audit_log(agent_id.clone(), audit_log)
+        .with_output_events(
+            agent_id.clone(),
+            ProcessId::Worker(worker_id),
+            channel_id.clone(),
+            event_tx.clone(),
+        );
+    if let Some(limiter) = shell_rate_limiter {
+        shell_tool = shell_tool.with_rate_limiter(agent_id.clone(), limiter);
+    }
+    let search_files_tool = SearchFilesTool::new(instance_dir.clone(), workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+    let exec_tool = ExecTool::new(instance_dir, workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone())
+        .with_secret_env_vars(shell_tool.secret_env_vars());
+    let read_file_tool = ReadFileTool::new(workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+    let read_structured_tool = ReadStructuredTool::new(workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+    let write_file_tool = WriteFileTool::new(workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+    let edit_file_tool = EditFileTool::new(workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+
     let mut server = ToolServer::new()
-        .tool(ShellTool::new(instance_dir.clone(), workspace.clone()))
-        .tool(FileTool::new(workspace.clone()))
-        .tool(ExecTool::new(instance_dir, workspace))
+        .tool(shell_tool)
+        .tool(FileTool::new(workspace))
+        .tool(read_file_tool)
+        .tool(read_structured_tool)
+        .tool(write_file_tool)
+        .tool(edit_file_tool)
+        .tool(search_files_tool)
+        .tool(exec_tool)
         .tool(SetStatusTool::new(
             agent_id, worker_id, channel_id, event_tx,
         ));
@@ -240,7 +561,11 @@ pub fn create_worker_tool_server(
         server = server.tool(WebSearchTool::new(key));
     }
 
-    server.run()
+    if web_fetch_config.enabled {
+        server = server.tool(WebFetchTool::new(web_fetch_config));
+    }
+
+    Ok(server.run())
 }
 
 /// Create a ToolServer for the cortex process.
@@ -255,27 +580,52 @@ pub fn create_cortex_tool_server(memory_search: Arc<MemorySearch>) -> ToolServer
 
 /// Create a ToolServer for cortex chat sessions.
 ///
-/// Combines branch tools (memory) with worker tools (shell, file, exec) to give
-/// the interactive cortex full capabilities. Does not include channel-specific
-/// tools (reply, react, skip) since the cortex chat doesn't talk to platforms.
+/// Combines branch tools (memory) with worker tools (shell, file, read_file,
+/// read_structured, write_file, edit_file, search_files, exec) to give the interactive cortex full
+/// capabilities. Does not include channel-specific tools (reply, react, skip)
+/// since the cortex chat doesn't talk to platforms. Fails if `workspace`
+/// doesn't exist.
 pub fn create_cortex_chat_tool_server(
     memory_search: Arc<MemorySearch>,
     conversation_logger: crate::conversation::history::ConversationLogger,
     channel_store: crate::conversation::ChannelStore,
     browser_config: BrowserConfig,
+    shell_config: ShellConfig,
     screenshot_dir: PathBuf,
     brave_search_key: Option<String>,
+    web_fetch_config: WebFetchConfig,
     workspace: PathBuf,
     instance_dir: PathBuf,
-) -> ToolServerHandle {
+) -> Result<ToolServerHandle, WorkspaceError> {
+    let shell_tool =
+        ShellTool::new(instance_dir.clone(), workspace.clone())?.with_config(shell_config);
+    let search_files_tool = SearchFilesTool::new(instance_dir.clone(), workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+    let exec_tool = ExecTool::new(instance_dir, workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone())
+        .with_secret_env_vars(shell_tool.secret_env_vars());
+    let read_file_tool = ReadFileTool::new(workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+    let read_structured_tool = ReadStructuredTool::new(workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+    let write_file_tool = WriteFileTool::new(workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+    let edit_file_tool = EditFileTool::new(workspace.clone())
+        .with_sensitive_files(shell_tool.sensitive_files().clone());
+
     let mut server = ToolServer::new()
         .tool(MemorySaveTool::new(memory_search.clone()))
         .tool(MemoryRecallTool::new(memory_search.clone()))
         .tool(MemoryDeleteTool::new(memory_search))
         .tool(ChannelRecallTool::new(conversation_logger, channel_store))
-        .tool(ShellTool::new(instance_dir.clone(), workspace.clone()))
-        .tool(FileTool::new(workspace.clone()))
-        .tool(ExecTool::new(instance_dir, workspace));
+        .tool(shell_tool)
+        .tool(FileTool::new(workspace))
+        .tool(read_file_tool)
+        .tool(read_structured_tool)
+        .tool(write_file_tool)
+        .tool(edit_file_tool)
+        .tool(search_files_tool)
+        .tool(exec_tool);
 
     if browser_config.enabled {
         server = server.tool(BrowserTool::new(browser_config, screenshot_dir));
@@ -285,5 +635,203 @@ pub fn create_cortex_chat_tool_server(
         server = server.tool(WebSearchTool::new(key));
     }
 
-    server.run()
+    if web_fetch_config.enabled {
+        server = server.tool(WebFetchTool::new(web_fetch_config));
+    }
+
+    Ok(server.run())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_binary_marks_tool_degraded() {
+        let directory = tempfile::tempdir().expect("failed to create temp dir");
+        let status = ToolStatus::check(
+            "transcode",
+            &["ffmpeg-that-does-not-exist"],
+            Some(directory.path().as_os_str()),
+        );
+
+        assert!(status.degraded);
+        assert_eq!(status.missing_binaries, vec!["ffmpeg-that-does-not-exist"]);
+        assert_eq!(
+            status.error_message().as_deref(),
+            Some("transcode requires ffmpeg-that-does-not-exist on PATH")
+        );
+    }
+
+    #[test]
+    fn present_binary_is_not_degraded() {
+        let directory = tempfile::tempdir().expect("failed to create temp dir");
+        let binary = directory.path().join("ffmpeg");
+        std::fs::write(&binary, b"").expect("failed to write binary");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+            std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755))
+                .expect("failed to mark binary executable");
+        }
+        let status =
+            ToolStatus::check("transcode", &["ffmpeg"], Some(directory.path().as_os_str()));
+
+        assert!(!status.degraded);
+        assert!(status.error_message().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_executable_file_on_path_counts_as_missing() {
+        let directory = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::write(directory.path().join("ffmpeg"), b"").expect("failed to write binary");
+        let status =
+            ToolStatus::check("transcode", &["ffmpeg"], Some(directory.path().as_os_str()));
+
+        assert!(status.degraded);
+        assert_eq!(status.missing_binaries, vec!["ffmpeg"]);
+    }
+
+    #[test]
+    fn declared_binaries_follow_the_tool_config() {
+        let directory = tempfile::tempdir().expect("failed to create temp dir");
+        let search_path = Some(directory.path().as_os_str());
+
+        let browser = BrowserConfig {
+            executable_path: Some("/nonexistent/chromium".into()),
+            ..Default::default()
+        };
+        let status = ToolStatus::of::<BrowserTool>(&browser, search_path);
+        assert!(status.degraded);
+        assert_eq!(
+            status.error_message().as_deref(),
+            Some("browser requires /nonexistent/chromium on PATH")
+        );
+        // Without a configured executable, Chrome is located at launch.
+        assert!(!ToolStatus::of::<BrowserTool>(&BrowserConfig::default(), search_path).degraded);
+
+        if !cfg!(windows) {
+            let shell = ShellConfig {
+                shell_program: Some("zsh-that-does-not-exist".into()),
+                ..Default::default()
+            };
+            let status = ToolStatus::of::<ShellTool>(&shell, search_path);
+            assert_eq!(status.missing_binaries, vec!["zsh-that-does-not-exist"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn workspace_tool_descriptions_name_the_workspace() {
+        let directory = tempfile::tempdir().expect("failed to create temp dir");
+        let workspace = directory.path().to_path_buf();
+        let shell = ShellTool::new(PathBuf::from("/nonexistent-instance"), workspace.clone())
+            .expect("workspace exists");
+
+        let descriptions = [
+            shell.definition(String::new()).await.description,
+            FileTool::new(workspace.clone())
+                .definition(String::new())
+                .await
+                .description,
+            ReadFileTool::new(workspace.clone())
+                .definition(String::new())
+                .await
+                .description,
+        ];
+        // The shell tool may have canonicalized the path.
+        let names = [
+            workspace.display().to_string(),
+            workspace.canonicalize().unwrap().display().to_string(),
+        ];
+        for description in descriptions {
+            assert!(
+                names
+                    .iter()
+                    .any(|name| description.contains(&format!("`{name}`"))),
+                "{description}"
+            );
+            assert!(!description.contains("{{workspace}}"), "{description}");
+        }
+    }
+
+    #[test]
+    fn best_effort_canonicalize_resolves_parent_dirs_of_missing_paths() {
+        let directory = tempfile::tempdir().expect("failed to create temp dir");
+        let root = directory.path().canonicalize().unwrap();
+        let workspace = root.join("workspace");
+        std::fs::create_dir(&workspace).expect("failed to create workspace");
+
+        assert_eq!(
+            best_effort_canonicalize(&workspace.join("missing/new.txt")),
+            workspace.join("missing/new.txt")
+        );
+        assert_eq!(
+            best_effort_canonicalize(&workspace.join("missing/../../etc")),
+            root.join("etc")
+        );
+    }
+
+    #[test]
+    fn binary_output_is_detected_by_share_of_non_text_bytes() {
+        let png_header =
+            b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01\0\0\0\x01\0\x08\x06\0\0\0\x5c\x72\xa8\x66";
+        assert_eq!(
+            binary_output_notice(png_header),
+            Some(format!(
+                "[binary output, {} bytes, not displayed]",
+                png_header.len()
+            ))
+        );
+        assert_eq!(
+            binary_output_notice("plain text, ünïcode\n".as_bytes()),
+            None
+        );
+        assert_eq!(binary_output_notice(b"a stray\0NUL\n"), None);
+        assert_eq!(binary_output_notice(b""), None);
+    }
+
+    #[test]
+    fn truncate_output_keeps_short_output_whole() {
+        assert_eq!(truncate_output("well under", 100), "well under");
+        let at_limit = "x".repeat(100);
+        assert_eq!(truncate_output(&at_limit, 100), at_limit);
+    }
+
+    #[test]
+    fn truncate_output_keeps_head_and_tail() {
+        let value = format!("{}{}{}", "h".repeat(40), "m".repeat(900), "t".repeat(60));
+        assert_eq!(
+            truncate_output(&value, 100),
+            format!(
+                "{}\n...[truncated 900 bytes]...\n{}",
+                "h".repeat(40),
+                "t".repeat(60)
+            )
+        );
+
+        // Two-byte characters: neither cut may land inside one.
+        let value = "é".repeat(100);
+        let truncated = truncate_output(&value, 25);
+        assert_eq!(
+            truncated,
+            format!(
+                "{}\n...[truncated 176 bytes]...\n{}",
+                "é".repeat(5),
+                "é".repeat(7)
+            )
+        );
+    }
+
+    #[test]
+    fn sanitize_output_strips_control_characters() {
+        assert_eq!(
+            sanitize_output("ok\tline\r\n\0bin\u{1b}[0mary\u{7f}"),
+            ("ok\tline\r\nbin[0mary".to_string(), true)
+        );
+        assert_eq!(
+            sanitize_output("plain\ttext\n"),
+            ("plain\ttext\n".to_string(), false)
+        );
+    }
 }
@@ -366,6 +366,16 @@ impl Tool for BrowserTool {
     }
 }
 
+/// A configured Chrome executable must exist. Without one, chromiumoxide
+/// looks in the usual install locations when the browser is launched.
+impl crate::tools::RequiresBinaries for BrowserTool {
+    type Config = BrowserConfig;
+
+    fn required_binaries(config: &BrowserConfig) -> Vec<String> {
+        config.executable_path.iter().cloned().collect()
+    }
+}
+
 impl BrowserTool {
     async fn handle_launch(&self) -> Result<BrowserOutput, BrowserError> {
         let mut state = self.state.lock().await;
@@ -374,6 +384,8 @@ impl BrowserTool {
             return Ok(BrowserOutput::success("Browser already running"));
         }
 
+        crate::tools::require_binaries::<Self>(&self.config).map_err(BrowserError::new)?;
+
         let mut builder = ChromeConfig::builder().no_sandbox();
 
         if !self.config.headless {
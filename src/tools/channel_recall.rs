@@ -148,7 +148,8 @@ impl Tool for ChannelRecallTool {
             .map(|message| TranscriptMessage {
                 role: message.role.clone(),
                 sender: message.sender_name.clone(),
-                content: message.content.clone(),
+                // Stored messages can carry pasted binary; keep it out of the JSON.
+                content: crate::tools::sanitize_output(&message.content).0,
                 timestamp: message.created_at.to_rfc3339(),
             })
             .collect();
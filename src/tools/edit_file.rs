@@ -0,0 +1,498 @@
+//! Edit file tool: apply a unified diff to a workspace file (task workers
+//! only).
+//!
+//! Hunks are located by their context rather than trusted line numbers. Each
+//! hunk is looked for nearest to where its header says it starts, comparing
+//! lines without trailing whitespace; when that fails, up to [`MAX_FUZZ`]
+//! context lines are dropped from either end of the hunk and the search is
+//! repeated, like `patch`'s fuzz factor. Hunks that still don't match are
+//! reported back with the reason instead of failing the whole edit, so the
+//! model can fix and resend just those. Paths are checked like `write_file`'s
+//! and the result is written the same atomic way.
+
+use crate::tools::shell::SensitiveFiles;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Most context lines dropped from each end of a hunk to make it fit.
+const MAX_FUZZ: usize = 2;
+
+/// Tool for editing files in the agent's workspace with unified diffs.
+#[derive(Debug, Clone)]
+pub struct EditFileTool {
+    workspace: PathBuf,
+    sensitive_files: SensitiveFiles,
+}
+
+impl EditFileTool {
+    /// Create an edit file tool restricted to the given workspace directory.
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            sensitive_files: SensitiveFiles::default(),
+        }
+    }
+
+    /// Use the shell tool's sensitive file patterns, so both refuse the same
+    /// files.
+    pub fn with_sensitive_files(mut self, sensitive_files: SensitiveFiles) -> Self {
+        self.sensitive_files = sensitive_files;
+        self
+    }
+}
+
+/// Error type for edit file tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Edit file failed: {0}")]
+pub struct EditFileError(String);
+
+/// Arguments for edit file tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EditFileArgs {
+    /// The file path, relative to the workspace root.
+    pub path: String,
+    /// A unified diff against the file.
+    pub diff: String,
+}
+
+/// Output from edit file tool.
+#[derive(Debug, Serialize)]
+pub struct EditFileOutput {
+    /// The resolved file path.
+    pub path: String,
+    /// Number of hunks applied.
+    pub hunks_applied: usize,
+    /// Hunks that couldn't be placed, to be fixed and sent again.
+    pub rejected_hunks: Vec<RejectedHunk>,
+}
+
+/// A hunk whose context wasn't found in the file.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct RejectedHunk {
+    /// Position of the hunk in the diff, counting from 1.
+    pub number: usize,
+    /// The hunk as it was sent, header included.
+    pub hunk: String,
+    pub reason: String,
+}
+
+impl Tool for EditFileTool {
+    const NAME: &'static str = "edit_file";
+
+    type Error = EditFileError;
+    type Args = EditFileArgs;
+    type Output = EditFileOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::tools::workspace_description("tools/edit_file", &self.workspace),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "The file to edit, relative to the workspace root."
+                    },
+                    "diff": {
+                        "type": "string",
+                        "description": "A unified diff against the file: '@@ -start,count +start,count @@' hunk headers followed by ' ' context, '-' removed and '+' added lines. ---/+++ file headers are optional."
+                    }
+                },
+                "required": ["path", "diff"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = crate::tools::resolve_workspace_file(
+            &self.workspace,
+            &args.path,
+            &self.sensitive_files,
+        )
+        .map_err(EditFileError)?;
+        let hunks = parse_diff(&args.diff).map_err(EditFileError)?;
+
+        let original = match tokio::fs::read_to_string(&path).await {
+            Ok(original) => original,
+            // A diff made only of additions can create the file.
+            Err(error)
+                if error.kind() == std::io::ErrorKind::NotFound
+                    && hunks.iter().all(|hunk| hunk.old_lines().next().is_none()) =>
+            {
+                String::new()
+            }
+            Err(error) => {
+                return Err(EditFileError(format!(
+                    "Failed to read {}: {error}",
+                    args.path
+                )));
+            }
+        };
+
+        let (edited, hunks_applied, rejected_hunks) = apply(&original, &hunks);
+        if hunks_applied > 0 {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| EditFileError(format!("Failed to create directory: {e}")))?;
+            }
+            super::write_file::replace_atomically(&path, edited.as_bytes())
+                .await
+                .map_err(|e| EditFileError(format!("Failed to write {}: {e}", args.path)))?;
+        }
+
+        Ok(EditFileOutput {
+            path: path.to_string_lossy().to_string(),
+            hunks_applied,
+            rejected_hunks,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Context,
+    Removed,
+    Added,
+}
+
+#[derive(Debug)]
+struct Hunk {
+    /// Where the header says the hunk starts in the original, counting from
+    /// 0. None when the header has no line numbers.
+    old_start: Option<usize>,
+    lines: Vec<(LineKind, String)>,
+    /// The hunk as sent, for reporting it back.
+    text: String,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> impl Iterator<Item = &str> {
+        self.lines
+            .iter()
+            .filter(|(kind, _)| *kind != LineKind::Added)
+            .map(|(_, line)| line.as_str())
+    }
+
+    /// The hunk's lines without up to `fuzz` context lines at either end.
+    fn trimmed(&self, fuzz: usize) -> (usize, &[(LineKind, String)]) {
+        let leading = self
+            .lines
+            .iter()
+            .take_while(|(kind, _)| *kind == LineKind::Context)
+            .count()
+            .min(fuzz);
+        let trailing = self.lines[leading..]
+            .iter()
+            .rev()
+            .take_while(|(kind, _)| *kind == LineKind::Context)
+            .count()
+            .min(fuzz);
+        (leading, &self.lines[leading..self.lines.len() - trailing])
+    }
+}
+
+/// Split a unified diff into hunks. File headers and `\ No newline` markers
+/// are skipped; a diff for more than one file is refused. Header line counts
+/// are ignored, since models often get them wrong.
+fn parse_diff(diff: &str) -> Result<Vec<Hunk>, String> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut files = 0;
+    let mut lines = diff.lines().peekable();
+    while let Some(line) = lines.next() {
+        // A `---` line is only a file header when a `+++` line follows, so a
+        // removed line that happens to start with `-- ` still counts.
+        if line.starts_with("--- ") && lines.peek().is_some_and(|next| next.starts_with("+++ ")) {
+            lines.next();
+            files += 1;
+            if files > 1 {
+                return Err(
+                    "The diff changes more than one file. Send one edit_file call per file."
+                        .to_string(),
+                );
+            }
+            continue;
+        }
+        if let Some(header) = line.strip_prefix("@@") {
+            hunks.push(Hunk {
+                old_start: parse_old_start(header),
+                lines: Vec::new(),
+                text: format!("{line}\n"),
+            });
+            continue;
+        }
+        if line.starts_with("diff ") || line.starts_with("index ") {
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            continue;
+        };
+        let (kind, text) = match line.chars().next() {
+            Some(' ') => (LineKind::Context, &line[1..]),
+            Some('-') => (LineKind::Removed, &line[1..]),
+            Some('+') => (LineKind::Added, &line[1..]),
+            Some('\\') => continue,
+            // Blank context lines often lose their leading space.
+            None => (LineKind::Context, ""),
+            Some(_) => {
+                return Err(format!(
+                    "Unexpected line in the diff: {line:?}. Hunk lines start with ' ', '-' or '+'."
+                ));
+            }
+        };
+        hunk.lines.push((kind, text.to_string()));
+        hunk.text.push_str(line);
+        hunk.text.push('\n');
+    }
+
+    hunks.retain(|hunk| !hunk.lines.is_empty());
+    if hunks.is_empty() {
+        return Err("The diff has no hunks. Each change needs an @@ header.".to_string());
+    }
+    Ok(hunks)
+}
+
+/// The old start line from a `@@ -12,5 +12,6 @@` header, as an index.
+fn parse_old_start(header: &str) -> Option<usize> {
+    let old = header.split_whitespace().next()?.strip_prefix('-')?;
+    let start: usize = old.split(',').next()?.parse().ok()?;
+    Some(start.saturating_sub(1))
+}
+
+/// Apply hunks in order to `original`. Returns the edited text, the number
+/// of hunks applied and the ones that were rejected.
+fn apply(original: &str, hunks: &[Hunk]) -> (String, usize, Vec<RejectedHunk>) {
+    let newline = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    let trailing_newline = original.is_empty() || original.ends_with('\n');
+
+    let mut applied = 0;
+    let mut rejected = Vec::new();
+    // Lines added minus lines removed so far, to shift later hunks' headers.
+    let mut offset: isize = 0;
+    // Hunks apply in order, so each is searched for after the previous one.
+    let mut floor = 0;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let expected = hunk
+            .old_start
+            .map(|start| start.saturating_add_signed(offset));
+        let placed = (0..=MAX_FUZZ).find_map(|fuzz| {
+            let (skipped, trimmed) = hunk.trimmed(fuzz);
+            let expected = expected.map(|expected| expected + skipped);
+            locate(&lines, trimmed, expected, floor).map(|position| (position, trimmed))
+        });
+
+        let Some((position, trimmed)) = placed else {
+            rejected.push(RejectedHunk {
+                number: index + 1,
+                hunk: hunk.text.clone(),
+                reason: rejection_reason(&lines, hunk, expected, floor),
+            });
+            continue;
+        };
+
+        let mut replacement = Vec::new();
+        let mut cursor = position;
+        for (kind, line) in trimmed {
+            match kind {
+                // Keep the file's own version of context lines.
+                LineKind::Context => {
+                    replacement.push(lines[cursor].clone());
+                    cursor += 1;
+                }
+                LineKind::Removed => cursor += 1,
+                LineKind::Added => replacement.push(line.clone()),
+            }
+        }
+        let removed = cursor - position;
+        let added = replacement.len();
+        lines.splice(position..cursor, replacement);
+
+        offset += added as isize - removed as isize;
+        floor = position + added;
+        applied += 1;
+    }
+
+    let mut edited = lines.join(newline);
+    if trailing_newline && !lines.is_empty() {
+        edited.push_str(newline);
+    }
+    (edited, applied, rejected)
+}
+
+/// Where the old side of `hunk_lines` occurs in `lines`, at or after
+/// `floor`, choosing the match nearest `expected`.
+fn locate(
+    lines: &[String],
+    hunk_lines: &[(LineKind, String)],
+    expected: Option<usize>,
+    floor: usize,
+) -> Option<usize> {
+    let old: Vec<&str> = hunk_lines
+        .iter()
+        .filter(|(kind, _)| *kind != LineKind::Added)
+        .map(|(_, line)| line.trim_end())
+        .collect();
+
+    if old.is_empty() {
+        // Nothing to match against: only a header position can place it.
+        return expected.map(|expected| expected.clamp(floor, lines.len().max(floor)));
+    }
+    if lines.len() < old.len() {
+        return None;
+    }
+
+    let matches_at = |start: usize| {
+        lines[start..start + old.len()]
+            .iter()
+            .zip(&old)
+            .all(|(line, old)| line.trim_end() == *old)
+    };
+    let expected = expected.unwrap_or(floor);
+    (floor..=lines.len() - old.len())
+        .filter(|&start| matches_at(start))
+        .min_by_key(|&start| start.abs_diff(expected))
+}
+
+/// Explain why a hunk didn't fit, pointing at the first line of its context
+/// that isn't where the header says.
+fn rejection_reason(
+    lines: &[String],
+    hunk: &Hunk,
+    expected: Option<usize>,
+    floor: usize,
+) -> String {
+    let old: Vec<&str> = hunk.old_lines().collect();
+    if old.is_empty() {
+        return "The hunk only adds lines and its header has no line number to place them at."
+            .to_string();
+    }
+
+    let mut reason = format!(
+        "Its context and removed lines weren't found in the file{}.",
+        if floor > 0 {
+            " after the previous hunk"
+        } else {
+            ""
+        }
+    );
+    if let Some(start) = expected
+        && let Some((line_number, (actual, wanted))) = lines
+            .iter()
+            .skip(start)
+            .zip(&old)
+            .enumerate()
+            .find(|(_, (actual, wanted))| actual.trim_end() != wanted.trim_end())
+    {
+        reason.push_str(&format!(
+            " At line {} the file has {actual:?}, the hunk expects {wanted:?}.",
+            start + line_number + 1
+        ));
+    }
+    reason
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(original: &str, diff: &str) -> (String, usize, Vec<RejectedHunk>) {
+        apply(original, &parse_diff(diff).expect("valid diff"))
+    }
+
+    #[test]
+    fn applies_hunks_with_shifted_and_fuzzy_context() {
+        let original = "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{a}\");\n}\n";
+
+        // The header is off by a few lines and the diff's trailing
+        // whitespace doesn't match the file's.
+        let (edited, applied, rejected) = edit(
+            original,
+            "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -7,3 +7,3 @@\n     let a = 1;\n-    let b = 2;\n+    let b = 3;\n     println!(\"{a}\");  \n",
+        );
+        assert_eq!((applied, rejected), (1, vec![]));
+        assert_eq!(
+            edited,
+            "fn main() {\n    let a = 1;\n    let b = 3;\n    println!(\"{a}\");\n}\n"
+        );
+
+        // A stale first context line is dropped by the fuzz.
+        let (edited, applied, _) = edit(
+            original,
+            "@@ -1,3 +1,3 @@\n fn main() -> Result<()> {\n     let a = 1;\n-    let b = 2;\n+    let b = 4;\n",
+        );
+        assert_eq!(applied, 1);
+        assert!(edited.contains("let b = 4;"));
+    }
+
+    #[test]
+    fn reports_hunks_that_do_not_match() {
+        let original = "one\ntwo\nthree\n";
+        let (edited, applied, rejected) = edit(
+            original,
+            "@@ -1,2 +1,2 @@\n one\n-two\n+2\n@@ -3,1 +3,1 @@\n-four\n+4\n",
+        );
+
+        assert_eq!(applied, 1);
+        assert_eq!(edited, "one\n2\nthree\n");
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].number, 2);
+        assert_eq!(rejected[0].hunk, "@@ -3,1 +3,1 @@\n-four\n+4\n");
+        assert!(
+            rejected[0]
+                .reason
+                .contains("the file has \"three\", the hunk expects \"four\""),
+            "{}",
+            rejected[0].reason
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_sensitive_files_and_multi_file_diffs() {
+        let workspace = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::write(workspace.path().join("config.toml"), "a = 1\n")
+            .expect("failed to write file");
+        let tool = EditFileTool::new(workspace.path().into());
+
+        let error = tool
+            .call(EditFileArgs {
+                path: "config.toml".into(),
+                diff: "@@ -1 +1 @@\n-a = 1\n+a = 2\n".into(),
+            })
+            .await
+            .expect_err("sensitive file");
+        assert!(error.0.contains("protected"), "{}", error.0);
+
+        let error = tool
+            .call(EditFileArgs {
+                path: "notes.txt".into(),
+                diff: "--- a/a\n+++ b/a\n@@ -0,0 +1 @@\n+a\n--- a/b\n+++ b/b\n@@ -0,0 +1 @@\n+b\n"
+                    .into(),
+            })
+            .await
+            .expect_err("two files");
+        assert!(error.0.contains("more than one file"), "{}", error.0);
+
+        let output = tool
+            .call(EditFileArgs {
+                path: "notes.txt".into(),
+                diff: "--- /dev/null\n+++ b/notes.txt\n@@ -0,0 +1,2 @@\n+hello\n+world\n".into(),
+            })
+            .await
+            .expect("additions create the file");
+        assert_eq!(output.hunks_applied, 1);
+        assert_eq!(
+            std::fs::read_to_string(workspace.path().join("notes.txt")).expect("file is created"),
+            "hello\nworld\n"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Exec tool for running subprocesses (task workers only).
 
+use super::shell::SensitiveFiles;
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -14,6 +15,8 @@ use tokio::process::Command;
 pub struct ExecTool {
     instance_dir: PathBuf,
     workspace: PathBuf,
+    sensitive_files: SensitiveFiles,
+    secret_env_vars: Vec<String>,
 }
 
 impl ExecTool {
@@ -22,9 +25,26 @@ impl ExecTool {
         Self {
             instance_dir,
             workspace,
+            sensitive_files: SensitiveFiles::default(),
+            secret_env_vars: super::shell::SECRET_ENV_VARS
+                .iter()
+                .map(|var| var.to_string())
+                .collect(),
         }
     }
 
+    /// Block these sensitive file patterns instead of the built-in set.
+    pub fn with_sensitive_files(mut self, sensitive_files: SensitiveFiles) -> Self {
+        self.sensitive_files = sensitive_files;
+        self
+    }
+
+    /// Refuse to set these env vars instead of the built-in set.
+    pub fn with_secret_env_vars<'a>(mut self, vars: impl IntoIterator<Item = &'a str>) -> Self {
+        self.secret_env_vars = vars.into_iter().map(str::to_string).collect();
+        self
+    }
+
     /// Check if program arguments reference sensitive instance paths.
     fn check_args(&self, program: &str, args: &[String]) -> Result<(), ExecError> {
         let instance_str = self.instance_dir.to_string_lossy();
@@ -43,18 +63,15 @@ impl ExecTool {
         }
 
         // Block references to sensitive files by name
-        for file in super::shell::SENSITIVE_FILES {
-            if all_args.contains(file) {
-                if all_args.contains(instance_str.as_ref())
-                    || !all_args.contains(workspace_str.as_ref())
-                {
-                    return Err(ExecError {
-                        message: format!(
-                            "Cannot access {file} — instance configuration is protected."
-                        ),
-                        exit_code: -1,
-                    });
-                }
+        let words = std::iter::once(program).chain(args.iter().map(String::as_str));
+        for word in words.filter(|word| self.sensitive_files.matches(word)) {
+            if all_args.contains(instance_str.as_ref())
+                || !all_args.contains(workspace_str.as_ref())
+            {
+                return Err(ExecError {
+                    message: format!("Cannot access {word} — instance configuration is protected."),
+                    exit_code: -1,
+                });
             }
         }
 
@@ -126,7 +143,7 @@ impl Tool for ExecTool {
     async fn definition(&self, _prompt: String) -> ToolDefinition {
         ToolDefinition {
             name: Self::NAME.to_string(),
-            description: crate::prompts::text::get("tools/exec").to_string(),
+            description: crate::tools::workspace_description("tools/exec", &self.workspace),
             parameters: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -181,28 +198,28 @@ impl Tool for ExecTool {
         // Check for references to sensitive instance paths
         self.check_args(&args.program, &args.args)?;
 
-        // Validate working_dir stays within workspace if specified
-        if let Some(ref dir) = args.working_dir {
-            let path = std::path::Path::new(dir);
-            let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-            let workspace_canonical = self
-                .workspace
-                .canonicalize()
-                .unwrap_or_else(|_| self.workspace.clone());
-            if !canonical.starts_with(&workspace_canonical) {
-                return Err(ExecError {
-                    message: format!(
-                        "working_dir must be within the workspace ({}).",
-                        self.workspace.display()
-                    ),
-                    exit_code: -1,
-                });
-            }
+        // Validate working_dir stays within workspace, resolving relative
+        // paths against it
+        let working_dir = match args.working_dir.map(PathBuf::from) {
+            Some(dir) if dir.is_absolute() => dir,
+            Some(dir) => self.workspace.join(dir),
+            None => self.workspace.clone(),
+        };
+        let working_dir = crate::tools::best_effort_canonicalize(&working_dir);
+        let workspace_canonical = crate::tools::best_effort_canonicalize(&self.workspace);
+        if !working_dir.starts_with(&workspace_canonical) {
+            return Err(ExecError {
+                message: format!(
+                    "working_dir must be within the workspace ({}).",
+                    self.workspace.display()
+                ),
+                exit_code: -1,
+            });
         }
 
         // Block passing secret env var values directly
         for env_var in &args.env {
-            for secret in super::shell::SECRET_ENV_VARS {
+            for secret in &self.secret_env_vars {
                 if env_var.key == *secret {
                     return Err(ExecError {
                         message: "Cannot set secret environment variables.".to_string(),
@@ -215,12 +232,7 @@ impl Tool for ExecTool {
         let mut cmd = Command::new(&args.program);
         cmd.args(&args.args);
 
-        // Default to workspace as working directory
-        if let Some(dir) = args.working_dir {
-            cmd.current_dir(dir);
-        } else {
-            cmd.current_dir(&self.workspace);
-        }
+        cmd.current_dir(&working_dir);
 
         // Prepend persistent tools directory to PATH so user-installed
         // binaries survive container restarts.
@@ -248,14 +260,16 @@ impl Tool for ExecTool {
                 exit_code: -1,
             })?;
 
-        let stdout = crate::tools::truncate_output(
-            &String::from_utf8_lossy(&output.stdout),
-            crate::tools::MAX_TOOL_OUTPUT_BYTES,
-        );
-        let stderr = crate::tools::truncate_output(
-            &String::from_utf8_lossy(&output.stderr),
-            crate::tools::MAX_TOOL_OUTPUT_BYTES,
-        );
+        let decode = |bytes: &[u8]| {
+            crate::tools::binary_output_notice(bytes).unwrap_or_else(|| {
+                crate::tools::truncate_output(
+                    &String::from_utf8_lossy(bytes),
+                    crate::tools::MAX_TOOL_OUTPUT_BYTES,
+                )
+            })
+        };
+        let stdout = decode(&output.stdout);
+        let stderr = decode(&output.stderr);
         let exit_code = output.status.code().unwrap_or(-1);
         let success = output.status.success();
 
@@ -1,5 +1,6 @@
 //! File tool for reading/writing/listing files (task workers only).
 
+use crate::tools::best_effort_canonicalize;
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -55,36 +56,6 @@ impl FileTool {
     }
 }
 
-/// Canonicalize as much of the path as possible. For paths where the final
-/// components don't exist yet (e.g. writing a new file), canonicalize the
-/// deepest existing ancestor and append the rest.
-fn best_effort_canonicalize(path: &Path) -> PathBuf {
-    if let Ok(canonical) = path.canonicalize() {
-        return canonical;
-    }
-
-    // Walk up until we find something that exists
-    let mut existing = path.to_path_buf();
-    let mut suffix = Vec::new();
-    while !existing.exists() {
-        if let Some(file_name) = existing.file_name() {
-            suffix.push(file_name.to_os_string());
-        } else {
-            break;
-        }
-        if !existing.pop() {
-            break;
-        }
-    }
-
-    let base = existing.canonicalize().unwrap_or(existing);
-    let mut result = base;
-    for component in suffix.into_iter().rev() {
-        result.push(component);
-    }
-    result
-}
-
 /// Error type for file tool.
 #[derive(Debug, thiserror::Error)]
 #[error("File operation failed: {0}")]
@@ -102,6 +73,8 @@ pub struct FileArgs {
     /// Whether to create parent directories if they don't exist (for write operations).
     #[serde(default = "default_create_dirs")]
     pub create_dirs: bool,
+    /// For read operations: return only the first N lines.
+    pub peek: Option<usize>,
 }
 
 fn default_create_dirs() -> bool {
@@ -146,7 +119,7 @@ impl Tool for FileTool {
     async fn definition(&self, _prompt: String) -> ToolDefinition {
         ToolDefinition {
             name: Self::NAME.to_string(),
-            description: crate::prompts::text::get("tools/file").to_string(),
+            description: crate::tools::workspace_description("tools/file", &self.workspace),
             parameters: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -167,6 +140,11 @@ impl Tool for FileTool {
                         "type": "boolean",
                         "default": true,
                         "description": "For write operations: create parent directories if they don't exist"
+                    },
+                    "peek": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "For read operations: return only the first N lines. Use this to cheaply check whether a file is the one you want before reading all of it."
                     }
                 },
                 "required": ["operation", "path"]
@@ -178,7 +156,10 @@ impl Tool for FileTool {
         let path = self.resolve_path(&args.path)?;
 
         match args.operation.as_str() {
-            "read" => do_file_read(&path).await,
+            "read" => match args.peek {
+                Some(lines) => do_file_peek(&path, lines).await,
+                None => do_file_read(&path).await,
+            },
             "write" => {
                 let content = args.content.ok_or_else(|| {
                     FileError("Content is required for write operation".to_string())
@@ -208,6 +189,54 @@ async fn do_file_read(path: &Path) -> Result<FileOutput, FileError> {
     })
 }
 
+/// Read only the first `lines` lines of a file. Stops reading as soon as the
+/// limit is reached so peeking at a large file stays cheap.
+async fn do_file_peek(path: &Path, lines: usize) -> Result<FileOutput, FileError> {
+    use tokio::io::AsyncBufReadExt as _;
+
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| FileError(format!("Failed to read file: {e}")))?;
+    let mut reader = tokio::io::BufReader::new(file);
+
+    let mut raw = Vec::new();
+    let mut lines_read = 0;
+    while lines_read < lines {
+        let bytes = reader
+            .read_until(b'\n', &mut raw)
+            .await
+            .map_err(|e| FileError(format!("Failed to read file: {e}")))?;
+        if bytes == 0 {
+            break;
+        }
+        lines_read += 1;
+    }
+
+    let has_more = !reader
+        .fill_buf()
+        .await
+        .map_err(|e| FileError(format!("Failed to read file: {e}")))?
+        .is_empty();
+
+    let raw = String::from_utf8(raw)
+        .map_err(|_| FileError("Failed to read file: stream did not contain valid UTF-8".into()))?;
+    let mut content = crate::tools::truncate_output(&raw, crate::tools::MAX_TOOL_OUTPUT_BYTES);
+    if has_more {
+        content.push_str(&format!(
+            "\n[Showing the first {lines_read} lines. Read without `peek` for the full file.]"
+        ));
+    }
+
+    Ok(FileOutput {
+        success: true,
+        operation: "read".to_string(),
+        path: path.to_string_lossy().to_string(),
+        content: Some(content),
+        entries: None,
+        error: None,
+    })
+}
+
 async fn do_file_write(
     path: &Path,
     content: String,
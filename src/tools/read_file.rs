@@ -0,0 +1,245 @@
+//! Read file tool: numbered lines from a workspace file (task workers only).
+//!
+//! Unlike `cat` through the shell, the file is read directly, so there's no
+//! quoting to get wrong and binary files are refused instead of dumped. Paths
+//! are confined to the workspace the same way shell `working_dir`s are, and
+//! sensitive files ([`SensitiveFiles`]) are off limits.
+
+use crate::tools::shell::SensitiveFiles;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Tool for reading text files in the agent's workspace by line.
+#[derive(Debug, Clone)]
+pub struct ReadFileTool {
+    workspace: PathBuf,
+    sensitive_files: SensitiveFiles,
+}
+
+impl ReadFileTool {
+    /// Create a read file tool restricted to the given workspace directory.
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            sensitive_files: SensitiveFiles::default(),
+        }
+    }
+
+    /// Use the shell tool's sensitive file patterns, so both refuse the same
+    /// files.
+    pub fn with_sensitive_files(mut self, sensitive_files: SensitiveFiles) -> Self {
+        self.sensitive_files = sensitive_files;
+        self
+    }
+}
+
+/// Error type for read file tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Read file failed: {0}")]
+pub struct ReadFileError(String);
+
+/// Arguments for read file tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReadFileArgs {
+    /// The file path, relative to the workspace root.
+    pub path: String,
+    /// First line to return, counting from 1.
+    pub start_line: Option<usize>,
+    /// Last line to return, inclusive.
+    pub end_line: Option<usize>,
+}
+
+/// Output from read file tool.
+#[derive(Debug, Serialize)]
+pub struct ReadFileOutput {
+    /// The resolved file path.
+    pub path: String,
+    /// The requested lines, each prefixed with its line number.
+    pub content: String,
+    /// Line number of the first line returned.
+    pub start_line: usize,
+    /// Line number of the last line returned (0 for an empty file).
+    pub end_line: usize,
+    /// Number of lines in the whole file.
+    pub total_lines: usize,
+}
+
+impl Tool for ReadFileTool {
+    const NAME: &'static str = "read_file";
+
+    type Error = ReadFileError;
+    type Args = ReadFileArgs;
+    type Output = ReadFileOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::tools::workspace_description("tools/read_file", &self.workspace),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "The file to read, relative to the workspace root."
+                    },
+                    "start_line": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "First line to return, counting from 1. Defaults to the start of the file."
+                    },
+                    "end_line": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Last line to return, inclusive. Defaults to the end of the file."
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let start_line = args.start_line.unwrap_or(1);
+        if start_line == 0 {
+            return Err(ReadFileError("start_line counts from 1".into()));
+        }
+        if let Some(end_line) = args.end_line
+            && end_line < start_line
+        {
+            return Err(ReadFileError(format!(
+                "end_line ({end_line}) is before start_line ({start_line})"
+            )));
+        }
+
+        let path = crate::tools::resolve_workspace_file(
+            &self.workspace,
+            &args.path,
+            &self.sensitive_files,
+        )
+        .map_err(ReadFileError)?;
+        if path.is_dir() {
+            return Err(ReadFileError(format!(
+                "{} is a directory. Use the file tool's list operation to see its contents.",
+                args.path
+            )));
+        }
+
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|e| ReadFileError(format!("Failed to read {}: {e}", args.path)))?;
+        if bytes.contains(&0) {
+            return Err(ReadFileError(format!(
+                "{} looks like a binary file ({} bytes), so its contents aren't shown. \
+                 Use the shell (e.g. `file` or `xxd | head`) to inspect it.",
+                args.path,
+                bytes.len()
+            )));
+        }
+
+        let text = String::from_utf8_lossy(&bytes);
+        let total_lines = text.lines().count();
+        if start_line > total_lines.max(1) {
+            return Err(ReadFileError(format!(
+                "start_line {start_line} is past the end of {} ({total_lines} lines)",
+                args.path
+            )));
+        }
+        let end_line = args.end_line.unwrap_or(total_lines).min(total_lines);
+        let (content, end_line) = number_lines(&text, start_line, end_line);
+
+        Ok(ReadFileOutput {
+            path: path.to_string_lossy().to_string(),
+            content: crate::tools::truncate_output(&content, crate::tools::MAX_TOOL_OUTPUT_BYTES),
+            start_line,
+            end_line,
+            total_lines,
+        })
+    }
+}
+
+/// Lines `start_line..=end_line` of `text`, each prefixed with its number.
+/// Returns the numbered lines and the last line number included.
+fn number_lines(text: &str, start_line: usize, end_line: usize) -> (String, usize) {
+    let width = end_line.max(1).to_string().len();
+    let mut content = String::new();
+    let mut last = start_line - 1;
+    for (number, line) in (1..).zip(text.lines()).skip(start_line - 1) {
+        if number > end_line {
+            break;
+        }
+        content.push_str(&format!("{number:>width$}\t{line}\n"));
+        last = number;
+    }
+    (content, last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(
+        tool: &ReadFileTool,
+        path: &str,
+        start: Option<usize>,
+        end: Option<usize>,
+    ) -> Result<ReadFileOutput, ReadFileError> {
+        tool.call(ReadFileArgs {
+            path: path.into(),
+            start_line: start,
+            end_line: end,
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn reads_numbered_line_ranges() {
+        let workspace = tempfile::tempdir().expect("failed to create temp dir");
+        let text: String = (1..=12).map(|n| format!("line {n}\n")).collect();
+        std::fs::write(workspace.path().join("notes.txt"), text).expect("failed to write file");
+        let tool = ReadFileTool::new(workspace.path().into());
+
+        let output = read(&tool, "notes.txt", Some(9), Some(10))
+            .await
+            .expect("range is readable");
+        assert_eq!(output.content, " 9\tline 9\n10\tline 10\n");
+        assert_eq!(
+            (output.start_line, output.end_line, output.total_lines),
+            (9, 10, 12)
+        );
+
+        let output = read(&tool, "notes.txt", Some(12), Some(50))
+            .await
+            .expect("end is clamped");
+        assert_eq!(output.content, "12\tline 12\n");
+        assert!(read(&tool, "notes.txt", Some(13), None).await.is_err());
+        assert!(read(&tool, "notes.txt", Some(5), Some(4)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn refuses_outside_sensitive_and_binary_files() {
+        let root = tempfile::tempdir().expect("failed to create temp dir");
+        let workspace = root.path().join("workspace");
+        std::fs::create_dir(&workspace).expect("failed to create workspace");
+        std::fs::write(root.path().join("outside.txt"), "secret").expect("failed to write file");
+        std::fs::write(workspace.join("config.toml"), "key = 1").expect("failed to write file");
+        std::fs::write(workspace.join("image.png"), b"\x89PNG\0\0\0")
+            .expect("failed to write file");
+        let tool = ReadFileTool::new(workspace.clone());
+
+        let error = read(&tool, "../outside.txt", None, None)
+            .await
+            .expect_err("outside the workspace");
+        assert!(error.0.contains("outside the workspace"), "{}", error.0);
+        let error = read(&tool, "./sub/../config.toml", None, None)
+            .await
+            .expect_err("sensitive file");
+        assert!(error.0.contains("protected"), "{}", error.0);
+        let error = read(&tool, "image.png", None, None)
+            .await
+            .expect_err("binary file");
+        assert!(error.0.contains("binary"), "{}", error.0);
+    }
+}
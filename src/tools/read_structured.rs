@@ -0,0 +1,392 @@
+//! Read structured tool: one value out of a JSON, YAML or TOML file (task
+//! workers only).
+//!
+//! The file is parsed by its extension and, given a query such as
+//! `$.dependencies.serde.version` or `jobs.build.steps[0].run`, only the
+//! value at that path is returned, as JSON. That's cheaper in tokens than
+//! reading the whole file and more reliable than picking it apart with
+//! shell tools. Paths are checked like `read_file`'s.
+
+use crate::tools::shell::SensitiveFiles;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Tool for querying JSON, YAML and TOML files in the agent's workspace.
+#[derive(Debug, Clone)]
+pub struct ReadStructuredTool {
+    workspace: PathBuf,
+    sensitive_files: SensitiveFiles,
+}
+
+impl ReadStructuredTool {
+    /// Create a read structured tool restricted to the given workspace
+    /// directory.
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            sensitive_files: SensitiveFiles::default(),
+        }
+    }
+
+    /// Use the shell tool's sensitive file patterns, so both refuse the same
+    /// files.
+    pub fn with_sensitive_files(mut self, sensitive_files: SensitiveFiles) -> Self {
+        self.sensitive_files = sensitive_files;
+        self
+    }
+}
+
+/// Error type for read structured tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Read structured failed: {0}")]
+pub struct ReadStructuredError(String);
+
+/// Arguments for read structured tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReadStructuredArgs {
+    /// The file path, relative to the workspace root.
+    pub path: String,
+    /// Path to the value to return, e.g. `$.server.ports[0]`. The whole
+    /// document when omitted.
+    pub query: Option<String>,
+}
+
+/// Output from read structured tool.
+#[derive(Debug, Serialize)]
+pub struct ReadStructuredOutput {
+    /// The resolved file path.
+    pub path: String,
+    /// The format the file was parsed as.
+    pub format: &'static str,
+    /// The query that selected the value, if any.
+    pub query: Option<String>,
+    /// The selected value as pretty-printed JSON.
+    pub value: String,
+}
+
+impl Tool for ReadStructuredTool {
+    const NAME: &'static str = "read_structured";
+
+    type Error = ReadStructuredError;
+    type Args = ReadStructuredArgs;
+    type Output = ReadStructuredOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::tools::workspace_description(
+                "tools/read_structured",
+                &self.workspace,
+            ),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "The .json, .yaml/.yml or .toml file to read, relative to the workspace root."
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "Path to the value to return: keys separated by dots, array indexes in brackets, quoted keys for names with dots (e.g. '$.jobs.build.steps[0]', 'tool[\"black\"].line-length'). Omit for the whole document."
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = crate::tools::resolve_workspace_file(
+            &self.workspace,
+            &args.path,
+            &self.sensitive_files,
+        )
+        .map_err(ReadStructuredError)?;
+        let format = Format::of(&path).ok_or_else(|| {
+            ReadStructuredError(format!(
+                "Can't tell the format of {} from its extension. Supported: .json, .yaml, \
+                 .yml and .toml.",
+                args.path
+            ))
+        })?;
+        let segments = match &args.query {
+            Some(query) => parse_query(query).map_err(ReadStructuredError)?,
+            None => Vec::new(),
+        };
+
+        let text = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| ReadStructuredError(format!("Failed to read {}: {e}", args.path)))?;
+        let document = format.parse(&text).map_err(|error| {
+            ReadStructuredError(format!(
+                "Failed to parse {} as {}: {error}",
+                args.path,
+                format.name()
+            ))
+        })?;
+        let value = select(&document, &segments).map_err(ReadStructuredError)?;
+
+        let value = serde_json::to_string_pretty(value)
+            .map_err(|e| ReadStructuredError(format!("Failed to format value: {e}")))?;
+        Ok(ReadStructuredOutput {
+            path: path.to_string_lossy().to_string(),
+            format: format.name(),
+            query: args.query,
+            value: crate::tools::truncate_output(&value, crate::tools::MAX_TOOL_OUTPUT_BYTES),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+        }
+    }
+
+    fn parse(self, text: &str) -> Result<Value, String> {
+        match self {
+            Self::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            Self::Toml => {
+                let document: toml::Value = toml::from_str(text).map_err(|e| e.to_string())?;
+                serde_json::to_value(document).map_err(|e| e.to_string())
+            }
+            Self::Yaml => {
+                let documents =
+                    yaml_rust2::YamlLoader::load_from_str(text).map_err(|e| e.to_string())?;
+                Ok(match documents.as_slice() {
+                    [] => Value::Null,
+                    [document] => yaml_to_json(document),
+                    documents => Value::Array(documents.iter().map(yaml_to_json).collect()),
+                })
+            }
+        }
+    }
+}
+
+fn yaml_to_json(yaml: &yaml_rust2::Yaml) -> Value {
+    use yaml_rust2::Yaml;
+
+    match yaml {
+        Yaml::Null | Yaml::BadValue | Yaml::Alias(_) => Value::Null,
+        Yaml::Boolean(value) => Value::Bool(*value),
+        Yaml::Integer(value) => Value::from(*value),
+        Yaml::Real(value) => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map_or_else(|| Value::String(value.clone()), Value::Number),
+        Yaml::String(value) => Value::String(value.clone()),
+        Yaml::Array(items) => Value::Array(items.iter().map(yaml_to_json).collect()),
+        Yaml::Hash(entries) => Value::Object(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        Yaml::String(key) | Yaml::Real(key) => key.clone(),
+                        Yaml::Integer(key) => key.to_string(),
+                        Yaml::Boolean(key) => key.to_string(),
+                        other => format!("{other:?}"),
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// One step of a query.
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a query like `$.a.b[0]["c.d"]` into its steps. The leading `$`
+/// is optional.
+fn parse_query(query: &str) -> Result<Vec<Segment>, String> {
+    let invalid = |reason: &str| format!("Invalid query {query}: {reason}");
+    let rest = query.trim();
+    let mut rest = rest.strip_prefix('$').unwrap_or(rest);
+    let mut segments = Vec::new();
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(|| invalid("unclosed '['"))?;
+            let inner = after[..end].trim();
+            let quoted = inner
+                .strip_prefix('"')
+                .and_then(|inner| inner.strip_suffix('"'))
+                .or_else(|| {
+                    inner
+                        .strip_prefix('\'')
+                        .and_then(|inner| inner.strip_suffix('\''))
+                });
+            segments.push(match quoted {
+                Some(key) => Segment::Key(key.to_string()),
+                None => Segment::Index(
+                    inner
+                        .parse()
+                        .map_err(|_| invalid("expected an index or a quoted key in brackets"))?,
+                ),
+            });
+            rest = &after[end + 1..];
+        } else {
+            let after = rest.strip_prefix('.').unwrap_or(rest);
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid("empty key"));
+            }
+            segments.push(Segment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Follow `segments` into `document`. The error names the step that
+/// failed and what was there instead.
+fn select<'a>(document: &'a Value, segments: &[Segment]) -> Result<&'a Value, String> {
+    let mut value = document;
+    let mut path = String::from("$");
+    for segment in segments {
+        value = match (segment, value) {
+            (Segment::Key(key), Value::Object(map)) => map.get(key).ok_or_else(|| {
+                let mut keys: Vec<&str> = map.keys().map(String::as_str).take(20).collect();
+                if map.len() > keys.len() {
+                    keys.push("...");
+                }
+                format!(
+                    "No key '{key}' at {path}. Available keys: {}",
+                    keys.join(", ")
+                )
+            })?,
+            (Segment::Index(index), Value::Array(items)) => items.get(*index).ok_or_else(|| {
+                format!(
+                    "Index {index} is out of range at {path}, which has {} items",
+                    items.len()
+                )
+            })?,
+            (segment, other) => {
+                let kind = match other {
+                    Value::Null => "null",
+                    Value::Bool(_) => "a boolean",
+                    Value::Number(_) => "a number",
+                    Value::String(_) => "a string",
+                    Value::Array(_) => "an array",
+                    Value::Object(_) => "an object",
+                };
+                let step = match segment {
+                    Segment::Key(key) => format!("key '{key}'"),
+                    Segment::Index(index) => format!("index {index}"),
+                };
+                return Err(format!("Can't look up {step} at {path}, which is {kind}"));
+            }
+        };
+        match segment {
+            Segment::Key(key) => path.push_str(&format!(".{key}")),
+            Segment::Index(index) => path.push_str(&format!("[{index}]")),
+        }
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn query(
+        tool: &ReadStructuredTool,
+        path: &str,
+        query: &str,
+    ) -> Result<ReadStructuredOutput, ReadStructuredError> {
+        tool.call(ReadStructuredArgs {
+            path: path.into(),
+            query: Some(query.into()),
+        })
+        .await
+    }
+
+    #[test]
+    fn parses_queries() {
+        assert_eq!(
+            parse_query(r#"$.jobs.build["runs.on"][2]"#).expect("valid query"),
+            vec![
+                Segment::Key("jobs".into()),
+                Segment::Key("build".into()),
+                Segment::Key("runs.on".into()),
+                Segment::Index(2),
+            ]
+        );
+        assert_eq!(parse_query("$").expect("valid query"), vec![]);
+        assert!(parse_query("a..b").is_err());
+        assert!(parse_query("a[x]").is_err());
+    }
+
+    #[tokio::test]
+    async fn extracts_nested_values_and_refuses_sensitive_files() {
+        let workspace = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::write(
+            workspace.path().join("package.json"),
+            r#"{"scripts": {"test": "vitest"}, "workspaces": ["web", "api"]}"#,
+        )
+        .expect("failed to write file");
+        std::fs::write(
+            workspace.path().join("ci.yml"),
+            "jobs:\n  build:\n    steps:\n      - run: cargo build\n      - run: cargo test\n",
+        )
+        .expect("failed to write file");
+        std::fs::write(workspace.path().join("config.toml"), "[llm]\nkey = 1\n")
+            .expect("failed to write file");
+        let tool = ReadStructuredTool::new(workspace.path().into());
+
+        let output = query(&tool, "package.json", "$.scripts.test")
+            .await
+            .expect("value exists");
+        assert_eq!(output.value, r#""vitest""#);
+        let output = query(&tool, "package.json", "workspaces[1]")
+            .await
+            .expect("value exists");
+        assert_eq!(output.value, r#""api""#);
+
+        let output = query(&tool, "ci.yml", "$.jobs.build.steps[1].run")
+            .await
+            .expect("value exists");
+        assert_eq!(output.format, "yaml");
+        assert_eq!(output.value, r#""cargo test""#);
+
+        let error = query(&tool, "package.json", "$.scripts.lint")
+            .await
+            .expect_err("missing key");
+        assert!(error.0.contains("Available keys: test"), "{}", error.0);
+
+        let error = query(&tool, "config.toml", "$.llm.key")
+            .await
+            .expect_err("sensitive file");
+        assert!(error.0.contains("protected"), "{}", error.0);
+    }
+}
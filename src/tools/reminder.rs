@@ -0,0 +1,295 @@
+//! Reminder tool for scheduling one-shot follow-ups in the current conversation.
+
+use crate::ChannelId;
+use crate::cron::reminder::{MAX_REMINDERS_PER_CHANNEL, ReminderScheduler};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Longest delay a reminder can be set for (30 days).
+const MAX_DELAY_SECS: u64 = 30 * 86400;
+
+/// Tool for managing reminders in the channel it is registered on.
+#[derive(Debug, Clone)]
+pub struct ReminderTool {
+    scheduler: Arc<ReminderScheduler>,
+    channel_id: ChannelId,
+}
+
+impl ReminderTool {
+    pub fn new(scheduler: Arc<ReminderScheduler>, channel_id: ChannelId) -> Self {
+        Self {
+            scheduler,
+            channel_id,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Reminder operation failed: {0}")]
+pub struct ReminderError(String);
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReminderArgs {
+    /// The operation to perform: "create", "list", or "cancel".
+    pub action: String,
+    /// Required for "create": what to be reminded about.
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Required for "create": seconds from now until the reminder fires.
+    #[serde(default)]
+    pub delay_secs: Option<u64>,
+    /// Required for "cancel": the ID of the reminder to cancel.
+    #[serde(default)]
+    pub reminder_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReminderOutput {
+    pub success: bool,
+    pub message: String,
+    /// Populated on "list" action.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<Vec<ReminderEntry>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReminderEntry {
+    pub id: String,
+    pub text: String,
+    pub due_at: String,
+}
+
+impl Tool for ReminderTool {
+    const NAME: &'static str = "reminder";
+
+    type Error = ReminderError;
+    type Args = ReminderArgs;
+    type Output = ReminderOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/reminder").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["create", "list", "cancel"],
+                        "description": "The operation: create a reminder, list pending reminders in this conversation, or cancel one."
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "For 'create': what to be reminded about, written as a note to yourself."
+                    },
+                    "delay_secs": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_DELAY_SECS,
+                        "description": "For 'create': seconds from now until the reminder fires (e.g. 3600 = in an hour)."
+                    },
+                    "reminder_id": {
+                        "type": "string",
+                        "description": "For 'cancel': the ID of the reminder to cancel."
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        match args.action.as_str() {
+            "create" => self.create(args).await,
+            "list" => self.list().await,
+            "cancel" => self.cancel(args).await,
+            other => Ok(ReminderOutput {
+                success: false,
+                message: format!("Unknown action '{other}'. Use 'create', 'list', or 'cancel'."),
+                reminders: None,
+            }),
+        }
+    }
+}
+
+impl ReminderTool {
+    async fn create(&self, args: ReminderArgs) -> Result<ReminderOutput, ReminderError> {
+        let text = args
+            .text
+            .filter(|text| !text.trim().is_empty())
+            .ok_or_else(|| ReminderError("'text' is required for create".into()))?;
+        let delay_secs = args
+            .delay_secs
+            .ok_or_else(|| ReminderError("'delay_secs' is required for create".into()))?;
+        if delay_secs == 0 || delay_secs > MAX_DELAY_SECS {
+            return Err(ReminderError(format!(
+                "'delay_secs' must be between 1 and {MAX_DELAY_SECS}"
+            )));
+        }
+
+        let reminder = self
+            .scheduler
+            .create(
+                &self.channel_id,
+                text,
+                std::time::Duration::from_secs(delay_secs),
+            )
+            .await
+            .map_err(|error| ReminderError(format!("failed to create: {error}")))?;
+
+        Ok(ReminderOutput {
+            success: true,
+            message: format!(
+                "Reminder '{}' set for {} (max {MAX_REMINDERS_PER_CHANNEL} pending per conversation).",
+                reminder.id,
+                reminder.due_at.to_rfc3339()
+            ),
+            reminders: None,
+        })
+    }
+
+    async fn list(&self) -> Result<ReminderOutput, ReminderError> {
+        let entries: Vec<ReminderEntry> = self
+            .scheduler
+            .list(&self.channel_id)
+            .await
+            .map_err(|error| ReminderError(format!("failed to list: {error}")))?
+            .into_iter()
+            .map(|reminder| ReminderEntry {
+                id: reminder.id,
+                text: reminder.text,
+                due_at: reminder.due_at.to_rfc3339(),
+            })
+            .collect();
+
+        let count = entries.len();
+        Ok(ReminderOutput {
+            success: true,
+            message: format!("{count} pending reminder(s)."),
+            reminders: Some(entries),
+        })
+    }
+
+    async fn cancel(&self, args: ReminderArgs) -> Result<ReminderOutput, ReminderError> {
+        let id = args
+            .reminder_id
+            .ok_or_else(|| ReminderError("'reminder_id' is required for cancel".into()))?;
+
+        let cancelled = self
+            .scheduler
+            .cancel(&self.channel_id, &id)
+            .await
+            .map_err(|error| ReminderError(format!("failed to cancel: {error}")))?;
+
+        Ok(ReminderOutput {
+            success: cancelled,
+            message: if cancelled {
+                format!("Reminder '{id}' cancelled.")
+            } else {
+                format!("No pending reminder '{id}' in this conversation.")
+            },
+            reminders: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::reminder::ReminderStore;
+    use tokio::sync::broadcast;
+
+    async fn scheduler() -> Arc<ReminderScheduler> {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .in_memory(true)
+            .create_if_missing(true);
+        let pool = sqlx::pool::PoolOptions::<sqlx::Sqlite>::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("in-memory SQLite");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+
+        let (event_tx, _) = broadcast::channel(16);
+        Arc::new(ReminderScheduler::new(
+            Arc::from("agent"),
+            Arc::new(ReminderStore::new(pool)),
+            event_tx,
+        ))
+    }
+
+    fn create_args(delay_secs: u64) -> ReminderArgs {
+        ReminderArgs {
+            action: "create".into(),
+            text: Some("check the deploy".into()),
+            delay_secs: Some(delay_secs),
+            reminder_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn delays_over_thirty_days_are_rejected() {
+        let tool = ReminderTool::new(scheduler().await, Arc::from("channel"));
+
+        let error = tool
+            .call(create_args(MAX_DELAY_SECS + 1))
+            .await
+            .expect_err("delay above the maximum");
+        assert!(error.to_string().contains("'delay_secs' must be between"));
+
+        assert!(tool.call(create_args(MAX_DELAY_SECS)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn creates_past_the_cap_are_rejected() {
+        let tool = ReminderTool::new(scheduler().await, Arc::from("channel"));
+        for _ in 0..MAX_REMINDERS_PER_CHANNEL {
+            tool.call(create_args(3600)).await.expect("under the cap");
+        }
+
+        let error = tool
+            .call(create_args(3600))
+            .await
+            .expect_err("past the cap");
+        assert!(error.to_string().contains("pending reminders"));
+    }
+
+    #[tokio::test]
+    async fn other_channels_reminders_cannot_be_cancelled() {
+        let scheduler = scheduler().await;
+        let owner = ReminderTool::new(scheduler.clone(), Arc::from("owner"));
+        let other = ReminderTool::new(scheduler.clone(), Arc::from("other"));
+        let reminder = scheduler
+            .create("owner", "mine".into(), std::time::Duration::from_secs(3600))
+            .await
+            .unwrap();
+
+        let output = other
+            .call(ReminderArgs {
+                action: "cancel".into(),
+                text: None,
+                delay_secs: None,
+                reminder_id: Some(reminder.id.clone()),
+            })
+            .await
+            .unwrap();
+        assert!(!output.success);
+
+        let listed = owner
+            .call(ReminderArgs {
+                action: "list".into(),
+                text: None,
+                delay_secs: None,
+                reminder_id: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(listed.reminders.unwrap().len(), 1);
+    }
+}
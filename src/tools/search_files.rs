@@ -0,0 +1,351 @@
+//! Search files tool: find lines matching a literal or regex query across the
+//! workspace (task workers only).
+//!
+//! The workspace is walked with ripgrep's `ignore` crate, so `.gitignore`,
+//! `.ignore` and hidden files are treated the way `rg` treats them, without
+//! the quoting and noise of `grep -r` through the shell. Symlinks aren't
+//! followed, sensitive files ([`SensitiveFiles`]) are never opened, and an
+//! instance directory nested inside the workspace is skipped entirely.
+
+use crate::tools::shell::SensitiveFiles;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Upper bound on `max_results`, whatever the model asks for.
+const MAX_RESULTS: usize = 500;
+
+/// Longest matching line returned; longer lines are cut in the middle.
+const MAX_LINE_BYTES: usize = 400;
+
+/// Tool for searching file contents in the agent's workspace.
+#[derive(Debug, Clone)]
+pub struct SearchFilesTool {
+    instance_dir: PathBuf,
+    workspace: PathBuf,
+    sensitive_files: SensitiveFiles,
+}
+
+impl SearchFilesTool {
+    /// Create a search tool for the given workspace. Nothing under
+    /// `instance_dir` is searched unless it's also under the workspace.
+    pub fn new(instance_dir: PathBuf, workspace: PathBuf) -> Self {
+        Self {
+            instance_dir,
+            workspace,
+            sensitive_files: SensitiveFiles::default(),
+        }
+    }
+
+    /// Use the shell tool's sensitive file patterns, so both refuse the same
+    /// files.
+    pub fn with_sensitive_files(mut self, sensitive_files: SensitiveFiles) -> Self {
+        self.sensitive_files = sensitive_files;
+        self
+    }
+}
+
+/// Error type for search files tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Search failed: {0}")]
+pub struct SearchFilesError(String);
+
+/// Arguments for search files tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchArgs {
+    /// The text or regular expression to look for.
+    pub query: String,
+    /// Only search files whose workspace-relative path matches this glob.
+    #[serde(default)]
+    pub path_glob: Option<String>,
+    /// Treat `query` as a regular expression instead of literal text.
+    #[serde(default)]
+    pub regex: bool,
+    /// Maximum number of matching lines to return.
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+}
+
+fn default_max_results() -> usize {
+    50
+}
+
+/// A matching line.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// The file, relative to the workspace root.
+    pub file: String,
+    /// Line number, counting from 1.
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Output from search files tool.
+#[derive(Debug, Serialize)]
+pub struct SearchFilesOutput {
+    pub matches: Vec<SearchMatch>,
+    /// Number of files searched.
+    pub files_searched: usize,
+    /// Whether the search stopped early at `max_results` or the output size
+    /// limit, so more matches may exist.
+    pub truncated: bool,
+}
+
+impl Tool for SearchFilesTool {
+    const NAME: &'static str = "search_files";
+
+    type Error = SearchFilesError;
+    type Args = SearchArgs;
+    type Output = SearchFilesOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::tools::workspace_description("tools/search_files", &self.workspace),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "The text to look for, or a regular expression when regex is true."
+                    },
+                    "path_glob": {
+                        "type": "string",
+                        "description": "Only search files whose path relative to the workspace matches this glob, e.g. \"*.rs\" or \"src/**/*.ts\"."
+                    },
+                    "regex": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Treat query as a regular expression (Rust regex syntax)."
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_RESULTS,
+                        "default": 50,
+                        "description": "Maximum number of matching lines to return."
+                    }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if args.query.is_empty() {
+            return Err(SearchFilesError("query is empty".into()));
+        }
+        let pattern = if args.regex {
+            args.query.clone()
+        } else {
+            regex::escape(&args.query)
+        };
+        let matcher = regex::Regex::new(&pattern)
+            .map_err(|e| SearchFilesError(format!("Invalid regex: {e}")))?;
+        let path_glob = args
+            .path_glob
+            .as_deref()
+            .map(|glob| {
+                globset::Glob::new(glob)
+                    .map(|glob| glob.compile_matcher())
+                    .map_err(|e| SearchFilesError(format!("Invalid path_glob: {e}")))
+            })
+            .transpose()?;
+
+        let search = Search {
+            workspace: crate::tools::best_effort_canonicalize(&self.workspace),
+            instance_dir: crate::tools::best_effort_canonicalize(&self.instance_dir),
+            sensitive_files: self.sensitive_files.clone(),
+            matcher,
+            path_glob,
+            max_results: args.max_results.clamp(1, MAX_RESULTS),
+        };
+        tokio::task::spawn_blocking(move || search.run())
+            .await
+            .map_err(|e| SearchFilesError(format!("Search task failed: {e}")))
+    }
+}
+
+/// One search over the workspace, run on a blocking thread.
+struct Search {
+    workspace: PathBuf,
+    instance_dir: PathBuf,
+    sensitive_files: SensitiveFiles,
+    matcher: regex::Regex,
+    path_glob: Option<globset::GlobMatcher>,
+    max_results: usize,
+}
+
+impl Search {
+    fn run(self) -> SearchFilesOutput {
+        let mut output = SearchFilesOutput {
+            matches: Vec::new(),
+            files_searched: 0,
+            truncated: false,
+        };
+        let mut output_bytes = 0;
+
+        // The workspace usually lives inside the instance dir; only an
+        // instance dir nested in the workspace needs skipping.
+        let skip_instance_dir =
+            self.instance_dir != self.workspace && self.instance_dir.starts_with(&self.workspace);
+        let workspace = self.workspace.clone();
+        let instance_dir = self.instance_dir.clone();
+        let sensitive_files = self.sensitive_files.clone();
+        let walker = ignore::WalkBuilder::new(&self.workspace)
+            .follow_links(false)
+            .require_git(false)
+            .filter_entry(move |entry| {
+                let path = entry.path();
+                !(skip_instance_dir && path.starts_with(&instance_dir))
+                    && !sensitive_files.matches(&relative_path(&workspace, path))
+            })
+            .build();
+
+        for entry in walker.flatten() {
+            if !entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+            {
+                continue;
+            }
+            let file = relative_path(&self.workspace, entry.path());
+            if let Some(path_glob) = &self.path_glob
+                && !path_glob.is_match(&file)
+            {
+                continue;
+            }
+            let Ok(bytes) = std::fs::read(entry.path()) else {
+                continue;
+            };
+            // Like rg, skip binary files.
+            if bytes.contains(&0) {
+                continue;
+            }
+            output.files_searched += 1;
+
+            let text = String::from_utf8_lossy(&bytes);
+            for (line_number, line) in (1..).zip(text.lines()) {
+                if !self.matcher.is_match(line) {
+                    continue;
+                }
+                if output.matches.len() == self.max_results
+                    || output_bytes > crate::tools::MAX_TOOL_OUTPUT_BYTES
+                {
+                    output.truncated = true;
+                    return output;
+                }
+                let line = crate::tools::truncate_output(line, MAX_LINE_BYTES);
+                output_bytes += file.len() + line.len();
+                output.matches.push(SearchMatch {
+                    file: file.clone(),
+                    line_number,
+                    line,
+                });
+            }
+        }
+        output
+    }
+}
+
+/// `path` relative to `workspace`, with forward slashes.
+fn relative_path(workspace: &Path, path: &Path) -> String {
+    path.strip_prefix(workspace)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(query: &str) -> SearchArgs {
+        SearchArgs {
+            query: query.into(),
+            path_glob: None,
+            regex: false,
+            max_results: default_max_results(),
+        }
+    }
+
+    fn files(output: &SearchFilesOutput) -> Vec<(&str, usize)> {
+        output
+            .matches
+            .iter()
+            .map(|found| (found.file.as_str(), found.line_number))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn searches_literal_and_regex_queries_honoring_gitignore() {
+        let workspace = tempfile::tempdir().expect("failed to create temp dir");
+        let root = workspace.path();
+        std::fs::create_dir_all(root.join("src")).expect("failed to create dir");
+        std::fs::create_dir_all(root.join("target")).expect("failed to create dir");
+        std::fs::write(root.join(".gitignore"), "target/\n").expect("failed to write file");
+        std::fs::write(
+            root.join("src/main.rs"),
+            "fn main() {\n    run(1 + 2);\n}\n",
+        )
+        .expect("failed to write file");
+        std::fs::write(root.join("notes.md"), "call run(1 + 2) here\n")
+            .expect("failed to write file");
+        std::fs::write(root.join("target/out.rs"), "run(1 + 2)\n").expect("failed to write file");
+        let tool = SearchFilesTool::new(root.join("instance"), root.into());
+
+        // Literal queries don't treat `(` and `+` as regex syntax.
+        let mut output = tool.call(search("run(1 + 2)")).await.expect("search runs");
+        output.matches.sort_by(|a, b| a.file.cmp(&b.file));
+        assert_eq!(files(&output), [("notes.md", 1), ("src/main.rs", 2)]);
+        assert_eq!(output.matches[1].line, "    run(1 + 2);");
+
+        let output = tool
+            .call(SearchArgs {
+                regex: true,
+                path_glob: Some("*.rs".into()),
+                ..search(r"^fn \w+\(")
+            })
+            .await
+            .expect("search runs");
+        assert_eq!(files(&output), [("src/main.rs", 1)]);
+
+        let output = tool
+            .call(SearchArgs {
+                max_results: 1,
+                ..search("run")
+            })
+            .await
+            .expect("search runs");
+        assert_eq!(output.matches.len(), 1);
+        assert!(output.truncated);
+
+        assert!(
+            tool.call(SearchArgs {
+                regex: true,
+                ..search("(")
+            })
+            .await
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_sensitive_files_and_a_nested_instance_dir() {
+        let workspace = tempfile::tempdir().expect("failed to create temp dir");
+        let root = workspace.path();
+        std::fs::create_dir_all(root.join("instance/agents")).expect("failed to create dir");
+        std::fs::write(root.join("config.toml"), "api_key = \"secret\"\n")
+            .expect("failed to write file");
+        std::fs::write(root.join("instance/agents/notes.txt"), "secret\n")
+            .expect("failed to write file");
+        std::fs::write(root.join("readme.txt"), "no secret here\n").expect("failed to write file");
+        let tool = SearchFilesTool::new(root.join("instance"), root.into());
+
+        let output = tool.call(search("secret")).await.expect("search runs");
+        assert_eq!(files(&output), [("readme.txt", 1)]);
+        assert_eq!(output.files_searched, 1);
+    }
+}
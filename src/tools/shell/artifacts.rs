@@ -0,0 +1,117 @@
+//! Files a command produced, picked out by a workspace-relative glob.
+//!
+//! After a successful command with `artifacts_glob` set, the workspace is
+//! walked (without following symlinks, skipping `.git`) and every file whose
+//! workspace-relative path matches is reported with its size and mtime. The
+//! glob can't reach outside the workspace: absolute patterns and `..` are
+//! refused.
+
+use super::snapshot::walk_files;
+use globset::{GlobBuilder, GlobMatcher};
+use serde::Serialize;
+use std::path::{Component, Path};
+
+/// Most artifacts listed. The rest are only counted.
+const MAX_LISTED_ARTIFACTS: usize = 100;
+
+/// A file matching `artifacts_glob`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Artifact {
+    /// Path relative to the workspace.
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Files matching `artifacts_glob` after the command ran.
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct Artifacts {
+    pub files: Vec<Artifact>,
+    /// Matches beyond the listing limit that were left out.
+    pub omitted: usize,
+}
+
+/// Compile an artifacts glob, refusing patterns that could match outside
+/// the workspace.
+pub(super) fn matcher(pattern: &str) -> Result<GlobMatcher, String> {
+    let path = Path::new(pattern);
+    if path.has_root()
+        || path
+            .components()
+            .any(|component| matches!(component, Component::ParentDir | Component::Prefix(_)))
+    {
+        return Err(format!(
+            "artifacts_glob must be relative to the workspace and can't contain '..': {pattern}"
+        ));
+    }
+
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|error| format!("invalid artifacts_glob {pattern}: {error}"))
+}
+
+/// Files under `workspace` matching `matcher`. Does blocking I/O.
+pub(super) fn collect(workspace: &Path, matcher: &GlobMatcher) -> Artifacts {
+    let mut files: Vec<Artifact> = walk_files(workspace)
+        .into_iter()
+        .filter_map(|path| {
+            let relative = path.strip_prefix(workspace).ok()?;
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if !matcher.is_match(&relative) {
+                return None;
+            }
+            let metadata = std::fs::symlink_metadata(&path).ok()?;
+            Some(Artifact {
+                path: relative,
+                size: metadata.len(),
+                modified: metadata.modified().ok().map(Into::into),
+            })
+        })
+        .collect();
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let omitted = files.len().saturating_sub(MAX_LISTED_ARTIFACTS);
+    files.truncate(MAX_LISTED_ARTIFACTS);
+    Artifacts { files, omitted }
+}
+
+impl Artifacts {
+    /// Format the artifacts for the LLM-facing summary.
+    pub fn summary(&self) -> String {
+        if self.files.is_empty() {
+            return "\n[No files matched artifacts_glob]\n".to_string();
+        }
+
+        let mut output = String::from("\n--- ARTIFACTS ---\n");
+        for artifact in &self.files {
+            let modified = artifact
+                .modified
+                .map(|modified| format!(", modified {}", modified.format("%Y-%m-%d %H:%M:%S UTC")))
+                .unwrap_or_default();
+            output.push_str(&format!(
+                "{} ({} bytes{modified})\n",
+                artifact.path, artifact.size
+            ));
+        }
+        if self.omitted > 0 {
+            output.push_str(&format!("[... and {} more]\n", self.omitted));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_are_confined_to_the_workspace() {
+        assert!(matcher("dist/**").is_ok());
+        assert!(matcher("/etc/*").is_err());
+        assert!(matcher("../other/**").is_err());
+        assert!(matcher("dist/../../secret").is_err());
+        assert!(matcher("dist/[").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! An append-only record of the shell commands workers ran.
+//!
+//! Every command that gets past the dry-run check is recorded as one JSON
+//! line: ones that finished, timed out or failed to start, and ones a check
+//! rejected, with the reason. Secret values are redacted from the command
+//! before it's written.
+
+use serde::Serialize;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+/// Where the audit log goes under the instance directory.
+pub const AUDIT_LOG_PATH: &str = "logs/shell_audit.jsonl";
+
+/// The file audit records are appended to, or nowhere.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+}
+
+/// One command, as it was recorded.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub agent_id: String,
+    pub command: String,
+    /// The `working_dir` the command asked for, if any.
+    pub working_dir: Option<String>,
+    /// None for a command that was blocked, timed out or never started.
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub blocked: bool,
+    /// Why the command was blocked or failed to start.
+    pub reason: Option<String>,
+}
+
+impl AuditLog {
+    /// Append records to the JSONL file at `path`, creating it and its
+    /// directory as needed.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path: Some(path) }
+    }
+
+    /// Record nothing.
+    pub fn disabled() -> Self {
+        Self { path: None }
+    }
+
+    /// Append `record`. Failing to write it is logged, not returned, so an
+    /// unwritable log doesn't stop commands from running.
+    pub async fn append(&self, record: AuditRecord) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let result = tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let mut line = serde_json::to_vec(&record)?;
+            line.push(b'\n');
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // One write per record, so concurrent appends don't interleave.
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)?
+                .write_all(&line)
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => tracing::warn!(%error, "failed to write shell audit record"),
+            Err(error) => tracing::warn!(%error, "shell audit task failed"),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Heuristics for commands that would block waiting for interactive input.
+//!
+//! Shell commands run without a terminal, so anything that prompts (a
+//! confirmation, an editor, a REPL) just sits there until the timeout. Each
+//! rule names a command that does this unless one of its non-interactive
+//! flags is given. Rules are used to explain a timeout and, when the operator
+//! opts in, to append a safe flag up front. Add an entry to
+//! [`INTERACTIVE_RULES`] to cover another command.
+
+/// A command that prompts for input unless told not to.
+#[derive(Debug)]
+pub(super) struct InteractiveRule {
+    /// Executables the rule applies to.
+    pub programs: &'static [&'static str],
+    /// Subcommands the rule applies to. Empty matches every invocation.
+    pub subcommands: &'static [&'static str],
+    /// Flags any one of which makes the invocation non-interactive.
+    pub non_interactive_flags: &'static [&'static str],
+    /// Flag that can be appended to skip the prompt without changing what
+    /// the command does. None when there's no safe automatic fix.
+    pub auto_flag: Option<&'static str>,
+    /// How to run the command non-interactively, shown on timeout.
+    pub hint: &'static str,
+}
+
+pub(super) const INTERACTIVE_RULES: &[InteractiveRule] = &[
+    InteractiveRule {
+        programs: &["git"],
+        subcommands: &["commit"],
+        non_interactive_flags: &[
+            "-m",
+            "--message",
+            "-am",
+            "-F",
+            "--file",
+            "-C",
+            "--reuse-message",
+            "--no-edit",
+            "--fixup",
+        ],
+        auto_flag: None,
+        hint: "`git commit` opens an editor for the message; pass it with -m \"message\"",
+    },
+    InteractiveRule {
+        programs: &["apt", "apt-get"],
+        subcommands: &[
+            "install",
+            "remove",
+            "purge",
+            "upgrade",
+            "dist-upgrade",
+            "full-upgrade",
+            "autoremove",
+        ],
+        non_interactive_flags: &["-y", "--yes", "--assume-yes"],
+        auto_flag: Some("-y"),
+        hint: "apt asks for confirmation; pass -y",
+    },
+    InteractiveRule {
+        programs: &["yum", "dnf"],
+        subcommands: &["install", "remove", "erase", "update", "upgrade"],
+        non_interactive_flags: &["-y", "--assumeyes"],
+        auto_flag: Some("-y"),
+        hint: "yum/dnf ask for confirmation; pass -y",
+    },
+    InteractiveRule {
+        programs: &["pip", "pip3"],
+        subcommands: &["uninstall"],
+        non_interactive_flags: &["-y", "--yes"],
+        auto_flag: Some("-y"),
+        hint: "`pip uninstall` asks for confirmation; pass -y",
+    },
+    InteractiveRule {
+        programs: &["npm"],
+        subcommands: &["init"],
+        non_interactive_flags: &["-y", "--yes"],
+        auto_flag: Some("-y"),
+        hint: "`npm init` asks a series of questions; pass -y to accept the defaults",
+    },
+    InteractiveRule {
+        programs: &["mysql"],
+        subcommands: &[],
+        non_interactive_flags: &["-e", "--execute"],
+        auto_flag: None,
+        hint: "mysql without a query opens an interactive prompt; pass the query with -e \
+               or through stdin",
+    },
+    InteractiveRule {
+        programs: &["psql"],
+        subcommands: &[],
+        non_interactive_flags: &["-c", "--command", "-f", "--file", "-l", "--list"],
+        auto_flag: None,
+        hint: "psql without a query opens an interactive prompt; pass the query with -c \
+               or through stdin",
+    },
+];
+
+/// Rules matching any simple command in `segments`, as produced by
+/// `command_segments`.
+pub(super) fn find_interactive(segments: &[Vec<String>]) -> Vec<&'static InteractiveRule> {
+    segments
+        .iter()
+        .filter_map(|words| {
+            INTERACTIVE_RULES
+                .iter()
+                .find(|rule| rule_matches(rule, words))
+        })
+        .collect()
+}
+
+/// Append the automatic flag for `rules` to the command, if that's safe.
+///
+/// Only a single simple command is rewritten, since a flag appended to a
+/// pipeline or chain would land on its last command. Returns None when
+/// there's nothing to add or it can't be added safely.
+pub(super) fn append_auto_flags(
+    command: &str,
+    segments: &[Vec<String>],
+    rules: &[&'static InteractiveRule],
+) -> Option<(String, Vec<&'static str>)> {
+    if segments.len() != 1 || rules.is_empty() || command.contains('#') {
+        return None;
+    }
+    let flags: Vec<&'static str> = rules.iter().filter_map(|rule| rule.auto_flag).collect();
+    if flags.len() != rules.len() {
+        return None;
+    }
+
+    let command = format!("{} {}", command.trim_end(), flags.join(" "));
+    Some((command, flags))
+}
+
+fn rule_matches(rule: &InteractiveRule, words: &[String]) -> bool {
+    let words = strip_sudo(words);
+    let Some((program, arguments)) = words.split_first() else {
+        return false;
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    if !rule.programs.contains(&program) {
+        return false;
+    }
+
+    if !rule.subcommands.is_empty() {
+        let subcommand = arguments.iter().find(|word| !word.starts_with('-'));
+        if !subcommand.is_some_and(|subcommand| rule.subcommands.contains(&subcommand.as_str())) {
+            return false;
+        }
+    }
+
+    !arguments.iter().any(|word| {
+        rule.non_interactive_flags
+            .iter()
+            .any(|flag| flag_matches(word, flag))
+    })
+}
+
+/// Skip a leading `sudo` and its options.
+fn strip_sudo(words: &[String]) -> &[String] {
+    match words.split_first() {
+        Some((first, rest)) if first == "sudo" => {
+            let options = rest.iter().take_while(|word| word.starts_with('-')).count();
+            &rest[options..]
+        }
+        _ => words,
+    }
+}
+
+/// Whether `word` passes `flag`, including the `--flag=value` and
+/// `-fvalue` forms.
+fn flag_matches(word: &str, flag: &str) -> bool {
+    if word == flag {
+        return true;
+    }
+    if flag.starts_with("--") {
+        word.strip_prefix(flag)
+            .is_some_and(|rest| rest.starts_with('='))
+    } else {
+        flag.len() == 2 && !word.starts_with("--") && word.starts_with(flag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(command: &str) -> Vec<Vec<String>> {
+        vec![command.split_whitespace().map(String::from).collect()]
+    }
+
+    #[test]
+    fn flags_prompting_invocations_only() {
+        assert_eq!(find_interactive(&segment("git commit")).len(), 1);
+        assert!(find_interactive(&segment("git commit -m fix")).is_empty());
+        assert!(find_interactive(&segment("git commit --message=fix")).is_empty());
+        assert!(find_interactive(&segment("git log --grep commit")).is_empty());
+
+        assert_eq!(
+            find_interactive(&segment("sudo apt-get install jq")).len(),
+            1
+        );
+        assert!(find_interactive(&segment("apt-get -y install jq")).is_empty());
+        assert!(find_interactive(&segment("apt list --installed")).is_empty());
+
+        assert_eq!(
+            find_interactive(&segment("/usr/bin/mysql -u root")).len(),
+            1
+        );
+        assert!(find_interactive(&segment("mysql -u root -e 'SELECT 1'")).is_empty());
+    }
+
+    #[test]
+    fn auto_flags_only_rewrite_a_single_command() {
+        let segments = segment("apt install jq");
+        let rules = find_interactive(&segments);
+        assert_eq!(
+            append_auto_flags("apt install jq\n", &segments, &rules),
+            Some(("apt install jq -y".to_string(), vec!["-y"]))
+        );
+
+        // No safe flag for git commit.
+        let segments = segment("git commit");
+        let rules = find_interactive(&segments);
+        assert_eq!(append_auto_flags("git commit", &segments, &rules), None);
+
+        // Appending to a pipeline would hit the wrong command.
+        let mut segments = segment("apt install jq");
+        segments.push(vec!["tee".into(), "log".into()]);
+        let rules = find_interactive(&segments);
+        assert_eq!(
+            append_auto_flags("apt install jq | tee log", &segments, &rules),
+            None
+        );
+    }
+}
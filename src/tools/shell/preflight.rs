@@ -0,0 +1,79 @@
+//! Checking that a command's programs exist before it's spawned.
+//!
+//! A missing program otherwise shows up as `sh: 1: git: not found` on stderr
+//! after the fact. The first word of each simple command is looked up on the
+//! PATH the command will run with, skipping shell keywords and builtins.
+//! Anything that can't be judged statically (paths, expansions, commands
+//! that change PATH themselves) is left for the shell to find.
+
+use std::ffi::OsStr;
+
+/// Keywords that come before the command they introduce.
+const PREFIX_KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "while", "until", "do", "!", "time", "{",
+];
+
+/// Keywords whose segment has no program to check, and shell builtins.
+const BUILTINS: &[&str] = &[
+    ".", ":", "[", "[[", "]]", "}", "alias", "bg", "break", "case", "cd", "command", "continue",
+    "done", "echo", "esac", "eval", "exec", "exit", "export", "false", "fg", "fi", "for",
+    "function", "getopts", "hash", "jobs", "kill", "local", "printf", "pwd", "read", "readonly",
+    "return", "set", "shift", "source", "test", "times", "trap", "true", "type", "ulimit", "umask",
+    "unalias", "unset", "wait",
+];
+
+/// The first program named by `segments` that isn't on `search_path`.
+pub(super) fn missing_program(segments: &[Vec<String>], search_path: &OsStr) -> Option<String> {
+    segments
+        .iter()
+        .filter_map(|words| {
+            words
+                .iter()
+                .find(|word| !PREFIX_KEYWORDS.contains(&word.as_str()))
+        })
+        .filter(|program| is_checkable(program))
+        .find(|program| crate::tools::find_binary(program, Some(search_path)).is_none())
+        .cloned()
+}
+
+/// Whether `program` is a plain name to look up on PATH.
+fn is_checkable(program: &str) -> bool {
+    !program.is_empty()
+        && !BUILTINS.contains(&program)
+        && !program.contains(['/', '\\', '$', '`', '*', '?', '[', '=', '~'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(command: &[&[&str]]) -> Vec<Vec<String>> {
+        command
+            .iter()
+            .map(|words| words.iter().map(|word| word.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn finds_the_first_missing_program() {
+        let directory = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::write(directory.path().join("git"), "").expect("failed to write file");
+        let path = directory.path().as_os_str();
+
+        let command = segments(&[
+            &["cd", "repo"],
+            &["if", "git", "status"],
+            &["then", "echo", "ok"],
+            &["fi"],
+            &["./build.sh"],
+            &["$EDITOR", "notes"],
+        ]);
+        assert_eq!(missing_program(&command, path), None);
+
+        let command = segments(&[&["git", "log"], &["defnotinstalled", "--help"]]);
+        assert_eq!(
+            missing_program(&command, path).as_deref(),
+            Some("defnotinstalled")
+        );
+    }
+}
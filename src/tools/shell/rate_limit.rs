@@ -0,0 +1,98 @@
+//! Per-agent rate limiting for shell commands.
+//!
+//! A token bucket per agent: it holds up to `commands` tokens, refills
+//! continuously at `commands` per `window`, and every command takes one. A
+//! model stuck in a loop drains it and gets told when to retry, while an
+//! agent that pauses between bursts never notices the limit.
+
+use crate::AgentId;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Token buckets shared by every `ShellTool` an agent's workers create.
+#[derive(Debug)]
+pub struct RateLimiter {
+    commands: u32,
+    window: Duration,
+    buckets: Mutex<HashMap<AgentId, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Allow bursts of up to `commands`, refilling at `commands` per
+    /// `window`. Both must be non-zero.
+    pub fn new(commands: u32, window: Duration) -> Self {
+        assert!(
+            commands > 0 && !window.is_zero(),
+            "rate limit must allow commands"
+        );
+        Self {
+            commands,
+            window,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The `(commands, window)` this limiter was built with.
+    pub fn limits(&self) -> (u32, Duration) {
+        (self.commands, self.window)
+    }
+
+    /// Take a token for a command by `agent_id`, or say how long until one
+    /// is available.
+    pub fn acquire(&self, agent_id: &AgentId) -> Result<(), Duration> {
+        let now = Instant::now();
+        let capacity = f64::from(self.commands);
+        let window = self.window.as_secs_f64();
+
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let bucket = buckets.entry(agent_id.clone()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed / window * capacity).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) * window / capacity,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn bursts_are_limited_and_refill_over_the_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(30));
+        let agent: AgentId = "main".into();
+        let other: AgentId = "other".into();
+
+        assert!(limiter.acquire(&agent).is_ok());
+        assert!(limiter.acquire(&agent).is_ok());
+        assert_eq!(limiter.acquire(&agent), Err(Duration::from_secs(15)));
+        // Each agent has its own bucket.
+        assert!(limiter.acquire(&other).is_ok());
+
+        tokio::time::advance(Duration::from_secs(15)).await;
+        assert!(limiter.acquire(&agent).is_ok());
+        assert!(limiter.acquire(&agent).is_err());
+    }
+}
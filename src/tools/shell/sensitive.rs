@@ -0,0 +1,226 @@
+//! Which paths count as sensitive instance files.
+//!
+//! The built-in [`SENSITIVE_FILES`] patterns can be extended through
+//! `shell.sensitive_files`. Patterns are globs such as `*.redb`, `secrets/**`
+//! or `.env*`, matched against every trailing run of a path's components, so
+//! `secrets/**` catches `./secrets/key` and `/srv/app/secrets/key` alike.
+//! Matching ignores case on Windows and macOS, whose filesystems usually do.
+
+use super::SENSITIVE_FILES;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+const CASE_INSENSITIVE: bool = cfg!(any(windows, target_os = "macos"));
+
+/// A compiled set of sensitive file patterns.
+#[derive(Debug, Clone)]
+pub struct SensitiveFiles {
+    set: GlobSet,
+    /// Patterns without glob syntax, for matching targets that are globs
+    /// themselves (`rm config.*`).
+    literals: Vec<String>,
+}
+
+impl SensitiveFiles {
+    /// The built-in patterns plus `extra`.
+    pub fn new(extra: &[String]) -> Result<Self, globset::Error> {
+        let patterns = SENSITIVE_FILES
+            .iter()
+            .copied()
+            .chain(extra.iter().map(String::as_str));
+
+        let mut builder = GlobSetBuilder::new();
+        let mut literals = Vec::new();
+        for pattern in patterns {
+            builder.add(
+                GlobBuilder::new(pattern)
+                    .case_insensitive(CASE_INSENSITIVE)
+                    .build()?,
+            );
+            if !pattern.contains(['*', '?', '[', '{']) {
+                literals.push(fold_case(pattern));
+            }
+        }
+
+        Ok(Self {
+            set: builder.build()?,
+            literals,
+        })
+    }
+
+    /// Whether a word of a command names a sensitive file. Also looks past a
+    /// leading redirection (`>config.toml`) and at the value of
+    /// `--flag=path` style arguments.
+    pub fn matches(&self, word: &str) -> bool {
+        word_paths(word).any(|path| self.matches_path(path))
+    }
+
+    /// Like [`matches`](Self::matches), but also true for a word that is a
+    /// shell glob which could expand to a sensitive file (`conf*.toml`).
+    pub(super) fn may_match_word(&self, word: &str) -> bool {
+        word_paths(word).any(|path| self.may_match(path))
+    }
+
+    /// A literal pattern (`config.toml`, not `*.redb`) that appears anywhere
+    /// in `text` as a whole file name, even inside a larger word such as a
+    /// script passed to `python3 -c`. `config.toml.example` doesn't count.
+    pub(super) fn literal_in(&self, text: &str) -> Option<&str> {
+        let text = fold_case(text);
+        let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '.' | '_' | '-');
+        self.literals
+            .iter()
+            .find(|literal| {
+                text.match_indices(literal.as_str()).any(|(start, _)| {
+                    let before = text[..start].chars().next_back();
+                    let after = text[start + literal.len()..].chars().next();
+                    !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+                })
+            })
+            .map(String::as_str)
+    }
+
+    /// Whether a path, or a shell glob of file names such as `config.*`,
+    /// could name a sensitive file.
+    pub(super) fn may_match(&self, path: &str) -> bool {
+        if self.matches_path(path) {
+            return true;
+        }
+        let name = path.rsplit('/').next().unwrap_or(path);
+        name.contains(['*', '?'])
+            && self
+                .literals
+                .iter()
+                .any(|literal| wildcard_matches(&fold_case(name), literal))
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        let components: Vec<&str> = path
+            .split('/')
+            .filter(|component| !component.is_empty() && *component != ".")
+            .collect();
+        (0..components.len()).any(|start| self.set.is_match(components[start..].join("/")))
+    }
+}
+
+/// The paths a command word may name: the word itself, the target of a
+/// leading redirection (`>config.toml`) and the value of `--flag=path`.
+fn word_paths(word: &str) -> impl Iterator<Item = &str> {
+    let redirected = word.trim_start_matches(['<', '>', '&']);
+    let value = word.rsplit_once('=').map(|(_, value)| value);
+    [Some(word), Some(redirected), value].into_iter().flatten()
+}
+
+impl Default for SensitiveFiles {
+    fn default() -> Self {
+        Self::new(&[]).expect("built-in sensitive file patterns are valid")
+    }
+}
+
+fn fold_case(text: &str) -> String {
+    if CASE_INSENSITIVE {
+        text.to_lowercase()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Match a file name that may contain `*` and `?` wildcards against `text`.
+///
+/// Linear in the lengths of both: on a mismatch it backtracks only to the
+/// last `*`, so a model-supplied run of stars can't stall the check.
+fn wildcard_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The last `*` seen and the text position it currently stands for.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    // Let the star swallow one more character and retry.
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_builtin_and_extra_patterns() {
+        let sensitive =
+            SensitiveFiles::new(&["*.redb".into(), "secrets/**".into(), ".env*".into()])
+                .expect("valid patterns");
+
+        assert!(sensitive.matches("config.toml"));
+        assert!(sensitive.matches("./workspace/../config.toml"));
+        assert!(sensitive.matches(">>spacebot.db"));
+        assert!(sensitive.matches("--config=/srv/instance/config.toml"));
+        assert!(sensitive.matches("data/cache.redb"));
+        assert!(sensitive.matches("/srv/app/secrets/api_key"));
+        assert!(sensitive.matches(".env.local"));
+        assert!(!sensitive.matches("config.toml.example"));
+        assert!(!sensitive.matches("notes/secrets.md"));
+
+        assert!(sensitive.may_match("conf*.toml"));
+        assert!(!sensitive.may_match("*.txt"));
+        assert!(sensitive.may_match_word(">config.t*"));
+    }
+
+    #[test]
+    fn long_runs_of_wildcards_are_checked_quickly() {
+        let sensitive = SensitiveFiles::default();
+        let stars = "*".repeat(200);
+
+        let started = std::time::Instant::now();
+        assert!(!sensitive.may_match(&format!("{stars}x")));
+        assert!(sensitive.may_match(&format!("{stars}.toml")));
+        assert!(wildcard_matches(
+            &format!("c{stars}g?t{stars}"),
+            "config.toml"
+        ));
+        assert!(!wildcard_matches(
+            &format!("{stars}a{stars}b"),
+            &"a".repeat(500)
+        ));
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn finds_literal_names_inside_larger_words() {
+        let sensitive = SensitiveFiles::new(&["*.redb".into()]).expect("valid patterns");
+
+        assert_eq!(
+            sensitive.literal_in("print(open('../config.toml').read())"),
+            Some("config.toml")
+        );
+        assert_eq!(sensitive.literal_in("awk 1 \"$PWD\"/../.env"), Some(".env"));
+        assert_eq!(sensitive.literal_in("cat config.toml.example"), None);
+        assert_eq!(sensitive.literal_in("cat myconfig.toml"), None);
+        // Only literal patterns are searched for; globs need a whole word.
+        assert_eq!(sensitive.literal_in("sqlite3 cache.redb"), None);
+    }
+
+    #[cfg(any(windows, target_os = "macos"))]
+    #[test]
+    fn matching_ignores_case() {
+        let sensitive = SensitiveFiles::default();
+        assert!(sensitive.matches("Config.TOML"));
+        assert!(sensitive.may_match("CONFIG.*"));
+    }
+}
@@ -0,0 +1,251 @@
+//! Persistent shell sessions, so the working directory and exported
+//! variables carry over between calls that share a `session_id`.
+//!
+//! Each session is one long-lived `sh` reading commands from its stdin. A
+//! command is wrapped so that it reads `/dev/null` rather than the session's
+//! own input, and is followed by a marker line on stdout and stderr carrying
+//! its exit code and the shell's directory afterwards; output up to the
+//! marker belongs to the command. Sessions that sit idle past the configured
+//! timeout are shut down by a reaper task, and the number open at once is
+//! capped.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+/// A long-lived shell serving the commands of one `session_id`.
+#[derive(Debug)]
+pub(super) struct Session {
+    pub(super) child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    stderr: BufReader<ChildStderr>,
+    /// The shell's directory after the last command.
+    pub(super) cwd: PathBuf,
+    last_used: Instant,
+}
+
+/// How a command run in a session ended.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum SessionRun {
+    /// The command finished; the shell is ready for the next one.
+    Finished { exit_code: i32, cwd: PathBuf },
+    /// The shell itself exited (e.g. the command ran `exit`).
+    ShellExited,
+}
+
+impl Session {
+    /// Start the session's shell from a command set up like a one-off
+    /// command (environment, process group, limits), minus the program.
+    pub(super) fn spawn(mut cmd: Command, cwd: PathBuf) -> std::io::Result<Self> {
+        cmd.stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = cmd.spawn()?;
+        let (Some(stdin), Some(stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err(std::io::Error::other("session shell pipes are missing"));
+        };
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            stderr: BufReader::new(stderr),
+            cwd,
+            last_used: Instant::now(),
+        })
+    }
+
+    /// Run `command` in `working_dir`, appending its output to the buffers
+    /// and passing each line to `on_line` as it arrives.
+    pub(super) async fn run(
+        &mut self,
+        command: &str,
+        working_dir: &std::path::Path,
+        stdout: &mut Vec<u8>,
+        stderr: &mut Vec<u8>,
+        on_line: &(dyn Fn(&str, &[u8]) + Sync),
+    ) -> std::io::Result<SessionRun> {
+        self.last_used = Instant::now();
+        let marker = format!("__SPACEBOT_SESSION_{}__", uuid::Uuid::new_v4().simple());
+        let script = format!(
+            "cd -- {} && {{ {command}\n}} </dev/null\n\
+             printf '\\n%s %s %s\\n' {marker} \"$?\" \"$PWD\"\n\
+             printf '\\n%s\\n' {marker} >&2\n",
+            shell_quote(&working_dir.to_string_lossy()),
+        );
+        self.stdin.write_all(script.as_bytes()).await?;
+        self.stdin.flush().await?;
+
+        let (stdout_marker, stderr_marker) = tokio::join!(
+            read_until_marker(&mut self.stdout, &marker, "stdout", stdout, on_line),
+            read_until_marker(&mut self.stderr, &marker, "stderr", stderr, on_line),
+        );
+        self.last_used = Instant::now();
+
+        let (Some(stdout_marker), Some(_)) = (stdout_marker?, stderr_marker?) else {
+            return Ok(SessionRun::ShellExited);
+        };
+        let (exit_code, cwd) = stdout_marker
+            .split_once(' ')
+            .unwrap_or((&stdout_marker, ""));
+        Ok(SessionRun::Finished {
+            exit_code: exit_code.parse().unwrap_or(-1),
+            cwd: PathBuf::from(cwd),
+        })
+    }
+}
+
+impl Drop for Session {
+    /// `kill_on_drop` only reaches the shell; take down anything it left
+    /// running in its process group too.
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        {
+            if let Some(pid) = self.child.id() {
+                // SAFETY: plain syscall on the group created with process_group(0).
+                unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) };
+            }
+        }
+    }
+}
+
+/// Read lines into `buffer` until the marker line. Returns what followed
+/// the marker on its line, or None at end of output. The newline printed
+/// ahead of the marker, so it always starts a line, is dropped.
+async fn read_until_marker<R: tokio::io::AsyncRead + Unpin>(
+    reader: &mut BufReader<R>,
+    marker: &str,
+    stream: &str,
+    buffer: &mut Vec<u8>,
+    on_line: &(dyn Fn(&str, &[u8]) + Sync),
+) -> std::io::Result<Option<String>> {
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            return Ok(None);
+        }
+        let text = String::from_utf8_lossy(&line);
+        if let Some(rest) = text.trim_end_matches('\n').strip_prefix(marker) {
+            if buffer.last() == Some(&b'\n') {
+                buffer.pop();
+            }
+            return Ok(Some(rest.trim_start().to_string()));
+        }
+        buffer.extend_from_slice(&line);
+        on_line(stream, &line);
+    }
+}
+
+/// Quote a value for `sh` with single quotes.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// The open sessions of one shell tool.
+#[derive(Debug, Default)]
+pub(super) struct Sessions {
+    inner: Mutex<SessionsInner>,
+}
+
+#[derive(Debug, Default)]
+struct SessionsInner {
+    sessions: HashMap<String, Arc<Mutex<Session>>>,
+    reaper_running: bool,
+}
+
+impl Sessions {
+    /// The session for `id`, if one is open.
+    pub(super) async fn get(&self, id: &str) -> Option<Arc<Mutex<Session>>> {
+        self.inner.lock().await.sessions.get(id).cloned()
+    }
+
+    /// Open a session under `id`, unless `max_sessions` are open already.
+    /// Expired sessions are closed first, so they don't count.
+    pub(super) async fn insert(
+        self: &Arc<Self>,
+        id: &str,
+        session: Session,
+        max_sessions: usize,
+        idle_timeout: Duration,
+    ) -> Result<Arc<Mutex<Session>>, String> {
+        let mut inner = self.inner.lock().await;
+        // Another call with the same ID got there first.
+        if let Some(existing) = inner.sessions.get(id) {
+            return Ok(existing.clone());
+        }
+        inner.expire(idle_timeout);
+        if inner.sessions.len() >= max_sessions {
+            return Err(format!(
+                "There are already {max_sessions} open shell sessions. Reuse one of them, or \
+                 wait for an idle one to expire."
+            ));
+        }
+
+        let session = Arc::new(Mutex::new(session));
+        inner.sessions.insert(id.to_string(), session.clone());
+        if !inner.reaper_running {
+            inner.reaper_running = true;
+            tokio::spawn(reap(Arc::downgrade(self), idle_timeout));
+        }
+        Ok(session)
+    }
+
+    /// Close the session under `id`, shutting down its shell.
+    pub(super) async fn remove(&self, id: &str) {
+        self.inner.lock().await.sessions.remove(id);
+    }
+}
+
+impl SessionsInner {
+    /// Drop sessions idle for longer than `idle_timeout`. A session that is
+    /// locked is running a command and stays.
+    fn expire(&mut self, idle_timeout: Duration) {
+        self.sessions.retain(|id, session| {
+            let Ok(session) = session.try_lock() else {
+                return true;
+            };
+            let keep = session.last_used.elapsed() < idle_timeout;
+            if !keep {
+                tracing::debug!(session_id = %id, "shell session expired");
+            }
+            keep
+        });
+    }
+}
+
+/// Periodically expire idle sessions until none are left or the tool is
+/// gone.
+async fn reap(sessions: Weak<Sessions>, idle_timeout: Duration) {
+    let interval = (idle_timeout / 4).clamp(Duration::from_secs(1), Duration::from_secs(60));
+    loop {
+        tokio::time::sleep(interval).await;
+        let Some(sessions) = sessions.upgrade() else {
+            return;
+        };
+        let mut inner = sessions.inner.lock().await;
+        inner.expire(idle_timeout);
+        if inner.sessions.is_empty() {
+            inner.reaper_running = false;
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_for_sh() {
+        assert_eq!(shell_quote("/tmp/it's here"), r"'/tmp/it'\''s here'");
+    }
+}
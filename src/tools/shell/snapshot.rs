@@ -0,0 +1,215 @@
+//! Before/after snapshots of the workspace, for reporting which files a
+//! command created, modified or deleted.
+//!
+//! A snapshot records the size and mtime of every file. In a git repo the file
+//! list comes from `git ls-files`, so ignored build output doesn't drown the
+//! listing; anywhere else the tree is walked directly, skipping `.git`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Most files a snapshot records. Bigger trees are cut off, which can hide
+/// changes past the cut; the summary says so.
+const MAX_SNAPSHOT_FILES: usize = 50_000;
+
+/// Most paths listed for each kind of change.
+const MAX_LISTED_CHANGES: usize = 50;
+
+/// Size and mtime of every file under a root.
+#[derive(Debug)]
+pub(super) struct Snapshot {
+    root: PathBuf,
+    files: HashMap<PathBuf, (u64, Option<SystemTime>)>,
+    truncated: bool,
+}
+
+/// Files a command changed, relative to the workspace.
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct FileChanges {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    /// Changes beyond the listing limit that were left out.
+    pub omitted: usize,
+    /// Whether the workspace had more files than a snapshot records.
+    pub incomplete: bool,
+}
+
+impl Snapshot {
+    /// Snapshot the files under `root`. Does blocking I/O.
+    pub(super) fn take(root: &Path) -> Self {
+        let paths = git_files(root).unwrap_or_else(|| walk_files(root));
+        let truncated = paths.len() > MAX_SNAPSHOT_FILES;
+
+        let files = paths
+            .into_iter()
+            .take(MAX_SNAPSHOT_FILES)
+            .filter_map(|path| {
+                let metadata = std::fs::symlink_metadata(&path).ok()?;
+                Some((path, (metadata.len(), metadata.modified().ok())))
+            })
+            .collect();
+
+        Self {
+            root: root.to_path_buf(),
+            files,
+            truncated,
+        }
+    }
+
+    /// What changed between `before` and this snapshot.
+    pub(super) fn changes_since(&self, before: &Snapshot) -> FileChanges {
+        let mut created = Vec::new();
+        let mut modified = Vec::new();
+        for (path, state) in &self.files {
+            match before.files.get(path) {
+                None => created.push(self.display(path)),
+                Some(previous) if previous != state => modified.push(self.display(path)),
+                Some(_) => {}
+            }
+        }
+        let mut deleted: Vec<String> = before
+            .files
+            .keys()
+            .filter(|path| !self.files.contains_key(*path))
+            .map(|path| self.display(path))
+            .collect();
+
+        let mut omitted = 0;
+        for list in [&mut created, &mut modified, &mut deleted] {
+            list.sort();
+            omitted += list.len().saturating_sub(MAX_LISTED_CHANGES);
+            list.truncate(MAX_LISTED_CHANGES);
+        }
+
+        FileChanges {
+            created,
+            modified,
+            deleted,
+            omitted,
+            incomplete: self.truncated || before.truncated,
+        }
+    }
+
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+impl FileChanges {
+    /// Format the changes for the LLM-facing summary.
+    pub fn summary(&self) -> String {
+        if self.created.is_empty() && self.modified.is_empty() && self.deleted.is_empty() {
+            return "\n[No workspace files changed]\n".to_string();
+        }
+
+        let mut output = String::from("\n--- FILES CHANGED ---\n");
+        for (kind, paths) in [
+            ("created", &self.created),
+            ("modified", &self.modified),
+            ("deleted", &self.deleted),
+        ] {
+            for path in paths {
+                output.push_str(&format!("{kind}: {path}\n"));
+            }
+        }
+        if self.omitted > 0 {
+            output.push_str(&format!("[... and {} more]\n", self.omitted));
+        }
+        if self.incomplete {
+            output.push_str(&format!(
+                "[Workspace has over {MAX_SNAPSHOT_FILES} files; changes past that were not tracked]\n"
+            ));
+        }
+        output
+    }
+}
+
+/// Tracked and untracked-but-not-ignored files, if `root` is in a git repo.
+fn git_files(root: &Path) -> Option<Vec<PathBuf>> {
+    let output = std::process::Command::new("git")
+        .args([
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ])
+        .current_dir(root)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(
+        output
+            .stdout
+            .split(|byte| *byte == 0)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| root.join(String::from_utf8_lossy(entry).as_ref()))
+            .collect(),
+    )
+}
+
+/// Every file under `root` without following symlinks, skipping `.git`.
+/// Stops one past the snapshot limit so truncation can be detected.
+pub(super) fn walk_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if entry.file_name() != ".git" {
+                    pending.push(entry.path());
+                }
+            } else {
+                files.push(entry.path());
+                if files.len() > MAX_SNAPSHOT_FILES {
+                    return files;
+                }
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_modified_and_deleted_files() {
+        let workspace = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::create_dir(workspace.path().join("src")).expect("failed to create dir");
+        std::fs::write(workspace.path().join("src/lib.rs"), "old").expect("failed to write");
+        std::fs::write(workspace.path().join("notes.txt"), "notes").expect("failed to write");
+        let before = Snapshot::take(workspace.path());
+
+        std::fs::write(workspace.path().join("src/lib.rs"), "new contents").expect("write");
+        std::fs::remove_file(workspace.path().join("notes.txt")).expect("remove");
+        let changes = Snapshot::take(workspace.path()).changes_since(&before);
+
+        assert_eq!(
+            changes,
+            FileChanges {
+                modified: vec![format!("src{}lib.rs", std::path::MAIN_SEPARATOR)],
+                deleted: vec!["notes.txt".into()],
+                ..Default::default()
+            }
+        );
+    }
+}
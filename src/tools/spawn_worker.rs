@@ -84,6 +84,9 @@ impl Tool for SpawnWorkerTool {
         if web_search_enabled {
             tools_list.push("web_search");
         }
+        if rc.web_fetch_config.load().enabled {
+            tools_list.push("web_fetch");
+        }
 
         let opencode_note = if opencode_enabled {
             " Set worker_type to \"opencode\" with a directory path for complex coding tasks — this spawns a full OpenCode coding agent with codebase exploration, context management, and its own tool suite."
@@ -0,0 +1,553 @@
+//! Fetch a URL over HTTP(S) (task workers only).
+//!
+//! A narrower surface than `curl` through the shell: only http and https,
+//! hosts are checked against the configured allow and block lists, and only
+//! public addresses are connected to. Workers can't reach loopback services,
+//! the private network, or cloud metadata endpoints such as
+//! `169.254.169.254`. Redirects are followed by hand so each hop gets the
+//! same checks.
+//!
+//! Addresses are filtered by the client's own resolver, so the address that
+//! passes the check is the one dialed, and a DNS server that answers
+//! differently the second time can't rebind a host to an internal address.
+
+use crate::config::WebFetchConfig;
+
+use reqwest::header::{AUTHORIZATION, COOKIE, HeaderMap, HeaderName, HeaderValue, LOCATION};
+use reqwest::{Method, StatusCode, Url};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+/// Most redirects one fetch follows.
+const MAX_REDIRECTS: usize = 5;
+
+/// Host names refused on top of the configured `blocked_hosts`. Anything
+/// else that points inside the machine or network is caught by its address.
+const BLOCKED_HOSTS: &[&str] = &["localhost", "*.localhost", "metadata.google.internal"];
+
+/// One client for every worker, so connections are pooled. It never follows
+/// redirects itself: [`WebFetchTool`] does, checking each hop. Host names are
+/// resolved through [`PublicResolver`].
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .gzip(true)
+        .build()
+        .expect("hardcoded reqwest client config")
+});
+
+/// Resolves host names to their public addresses only, so the client never
+/// dials an internal one however the name resolves.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addresses = public_addresses(name.as_str()).await?;
+            Ok(Box::new(addresses.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// A host that resolved only to addresses the tool won't connect to.
+#[derive(Debug, thiserror::Error)]
+#[error("{host} points to {ip}, which is not a public address")]
+struct NonPublicHost {
+    host: String,
+    ip: IpAddr,
+}
+
+/// The public addresses `host` resolves to, with port 0 for the client to
+/// fill in. Fails if there are none.
+async fn public_addresses(
+    host: &str,
+) -> Result<Vec<SocketAddr>, Box<dyn std::error::Error + Send + Sync>> {
+    let resolved: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+    let public: Vec<SocketAddr> = resolved
+        .iter()
+        .copied()
+        .filter(|address| is_public(address.ip()))
+        .collect();
+    match resolved.first() {
+        Some(address) if public.is_empty() => Err(NonPublicHost {
+            host: host.to_string(),
+            ip: address.ip(),
+        }
+        .into()),
+        _ => Ok(public),
+    }
+}
+
+/// Tool for making HTTP requests.
+#[derive(Debug, Clone)]
+pub struct WebFetchTool {
+    config: WebFetchConfig,
+}
+
+impl WebFetchTool {
+    pub fn new(config: WebFetchConfig) -> Self {
+        Self { config }
+    }
+
+    /// Refuse `url` unless it's http(s) to an allowed host. A literal IP
+    /// address must be public; host names are left to [`PublicResolver`].
+    fn check_url(&self, url: &Url) -> Result<(), WebFetchError> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(WebFetchError::InvalidRequest(format!(
+                "unsupported URL scheme '{}', only http and https are allowed",
+                url.scheme()
+            )));
+        }
+        let Some(host) = url.host_str() else {
+            return Err(WebFetchError::InvalidRequest(format!(
+                "URL '{url}' has no host"
+            )));
+        };
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_lowercase();
+
+        let blocked = BLOCKED_HOSTS
+            .iter()
+            .copied()
+            .chain(self.config.blocked_hosts.iter().map(String::as_str))
+            .any(|pattern| host_matches(pattern, &host));
+        if blocked {
+            return Err(WebFetchError::Blocked(format!("{host} is a blocked host")));
+        }
+        let allowed = &self.config.allowed_hosts;
+        if !allowed.is_empty() && !allowed.iter().any(|pattern| host_matches(pattern, &host)) {
+            return Err(WebFetchError::Blocked(format!(
+                "{host} is not in the allowed hosts: {}",
+                allowed.join(", ")
+            )));
+        }
+
+        match host.parse::<IpAddr>() {
+            Ok(ip) => check_address(url, ip),
+            Err(_) => Ok(()),
+        }
+    }
+
+    async fn fetch(&self, args: WebFetchArgs) -> Result<WebFetchOutput, WebFetchError> {
+        let mut url = Url::parse(&args.url)
+            .map_err(|error| WebFetchError::InvalidRequest(format!("invalid URL: {error}")))?;
+        let mut method =
+            Method::from_bytes(args.method.to_uppercase().as_bytes()).map_err(|_| {
+                WebFetchError::InvalidRequest(format!("invalid method '{}'", args.method))
+            })?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &args.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                WebFetchError::InvalidRequest(format!("invalid header name '{name}'"))
+            })?;
+            let value = HeaderValue::from_str(value).map_err(|_| {
+                WebFetchError::InvalidRequest(format!("invalid value for header '{name}'"))
+            })?;
+            headers.insert(name, value);
+        }
+        let mut body = args.body;
+        let mut redirects = 0;
+
+        loop {
+            self.check_url(&url)?;
+
+            let mut request = CLIENT
+                .request(method.clone(), url.clone())
+                .headers(headers.clone());
+            if let Some(body) = &body {
+                request = request.body(body.clone());
+            }
+            let response = request.send().await.map_err(request_error)?;
+
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok());
+            let Some(location) = location.filter(|_| status.is_redirection()) else {
+                return self.read_response(url, response).await;
+            };
+
+            if redirects == MAX_REDIRECTS {
+                return Err(WebFetchError::RequestFailed(format!(
+                    "stopped after {MAX_REDIRECTS} redirects"
+                )));
+            }
+            let next = url.join(location).map_err(|error| {
+                WebFetchError::RequestFailed(format!("invalid redirect to '{location}': {error}"))
+            })?;
+            // Credentials were meant for the host they were given for.
+            if next.host_str() != url.host_str() {
+                headers.remove(AUTHORIZATION);
+                headers.remove(COOKIE);
+            }
+            // Like browsers: 303 always turns into a GET, 301 and 302 do
+            // for anything but GET and HEAD, 307 and 308 repeat the request.
+            let becomes_get = status == StatusCode::SEE_OTHER
+                || (matches!(status, StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND)
+                    && method != Method::HEAD);
+            if becomes_get {
+                method = Method::GET;
+                body = None;
+            }
+            url = next;
+            redirects += 1;
+        }
+    }
+
+    async fn read_response(
+        &self,
+        url: Url,
+        mut response: reqwest::Response,
+    ) -> Result<WebFetchOutput, WebFetchError> {
+        let status = response.status().as_u16();
+        let mut headers = BTreeMap::<String, String>::new();
+        for (name, value) in response.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            headers
+                .entry(name.to_string())
+                .and_modify(|existing| {
+                    existing.push_str(", ");
+                    existing.push_str(&value);
+                })
+                .or_insert_with(|| value.into_owned());
+        }
+
+        let limit = self.config.max_response_bytes;
+        let mut bytes = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| WebFetchError::RequestFailed(error.to_string()))?
+        {
+            let room = limit - bytes.len();
+            if chunk.len() > room {
+                bytes.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let body = match crate::tools::binary_output_notice(&bytes) {
+            Some(notice) => notice,
+            None => {
+                let (text, _) = crate::tools::sanitize_output(&String::from_utf8_lossy(&bytes));
+                if text.len() > crate::tools::MAX_TOOL_OUTPUT_BYTES {
+                    truncated = true;
+                }
+                crate::tools::truncate_output(&text, crate::tools::MAX_TOOL_OUTPUT_BYTES)
+            }
+        };
+
+        Ok(WebFetchOutput {
+            url: url.to_string(),
+            status,
+            headers,
+            body,
+            truncated,
+        })
+    }
+}
+
+/// Whether `pattern` is a usable `allowed_hosts`/`blocked_hosts` entry: a
+/// host name or IP address, optionally with a leading `*.` wildcard.
+pub fn is_host_pattern(pattern: &str) -> bool {
+    let host = pattern.strip_prefix("*.").unwrap_or(pattern);
+    !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | ':'))
+}
+
+/// Whether `host` (lowercase) matches `pattern`. `*.example.com` matches
+/// subdomains of `example.com` but not `example.com` itself.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.')),
+        None => host == pattern,
+    }
+}
+
+/// A failed request as a tool error: [`WebFetchError::Blocked`] if the
+/// resolver refused the host, with the reason reqwest wraps in its own.
+fn request_error(error: reqwest::Error) -> WebFetchError {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&error);
+    while let Some(current) = source {
+        if let Some(refused) = current.downcast_ref::<NonPublicHost>() {
+            return WebFetchError::Blocked(refused.to_string());
+        }
+        source = current.source();
+    }
+    WebFetchError::RequestFailed(error.to_string())
+}
+
+fn check_address(url: &Url, ip: IpAddr) -> Result<(), WebFetchError> {
+    if is_public(ip) {
+        Ok(())
+    } else {
+        Err(WebFetchError::Blocked(format!(
+            "{url} points to {ip}, which is not a public address"
+        )))
+    }
+}
+
+/// Whether `ip` is on the public internet, rather than loopback, private,
+/// link-local (cloud metadata lives there), shared, reserved or multicast.
+/// IPv6 addresses that carry an IPv4 one (mapped, compatible, NAT64, 6to4)
+/// are judged by the address they carry.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || a >= 240
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (18..20).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if ip.is_loopback() || ip.is_unspecified() {
+                return false;
+            }
+            // `to_ipv4` covers both `::ffff:a.b.c.d` and `::a.b.c.d`.
+            if let Some(ip) = ip.to_ipv4() {
+                return is_public(IpAddr::V4(ip));
+            }
+            let segments = ip.segments();
+            let embedded = match segments {
+                [0x64, 0xff9b, 0, 0, 0, 0, high, low] => Some((high, low)),
+                [0x2002, high, low, ..] => Some((high, low)),
+                _ => None,
+            };
+            if let Some((high, low)) = embedded {
+                let [a, b] = high.to_be_bytes();
+                let [c, d] = low.to_be_bytes();
+                return is_public(IpAddr::V4(Ipv4Addr::new(a, b, c, d)));
+            }
+            let first = segments[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+        }
+    }
+}
+
+/// Error type for web fetch tool.
+#[derive(Debug, thiserror::Error)]
+pub enum WebFetchError {
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("ACCESS DENIED: {0}")]
+    Blocked(String),
+
+    #[error("Request failed: {0}")]
+    RequestFailed(String),
+}
+
+/// Arguments for web fetch tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WebFetchArgs {
+    /// The http:// or https:// URL to fetch.
+    pub url: String,
+    /// HTTP method (default GET).
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Request headers.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Request body, sent as-is.
+    pub body: Option<String>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// Output from web fetch tool.
+#[derive(Debug, Serialize)]
+pub struct WebFetchOutput {
+    /// The URL that answered, after any redirects.
+    pub url: String,
+    /// HTTP status code.
+    pub status: u16,
+    /// Response headers. Repeated headers are joined with commas.
+    pub headers: BTreeMap<String, String>,
+    /// Response body as text, or a placeholder for binary content.
+    pub body: String,
+    /// Whether the body was cut short.
+    pub truncated: bool,
+}
+
+impl Tool for WebFetchTool {
+    const NAME: &'static str = "web_fetch";
+
+    type Error = WebFetchError;
+    type Args = WebFetchArgs;
+    type Output = WebFetchOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/web_fetch").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "The http:// or https:// URL to fetch"
+                    },
+                    "method": {
+                        "type": "string",
+                        "default": "GET",
+                        "description": "HTTP method, e.g. GET, POST, PUT, DELETE, HEAD"
+                    },
+                    "headers": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Request headers, e.g. {\"Accept\": \"application/json\"}"
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Request body, sent as-is. Set a Content-Type header to match."
+                    }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        tokio::time::timeout(timeout, self.fetch(args))
+            .await
+            .map_err(|_| {
+                WebFetchError::RequestFailed(format!(
+                    "timed out after {} seconds",
+                    self.config.timeout_secs
+                ))
+            })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn fetch(config: WebFetchConfig, url: &str) -> Result<WebFetchOutput, WebFetchError> {
+        let args = serde_json::from_value(serde_json::json!({ "url": url })).unwrap();
+        WebFetchTool::new(config).call(args).await
+    }
+
+    #[tokio::test]
+    async fn internal_addresses_and_unlisted_hosts_are_refused() {
+        for url in [
+            "http://169.254.169.254/latest/meta-data/",
+            "http://127.0.0.1:8080/",
+            "http://10.0.0.5/",
+            "http://[::1]/",
+            "http://[::ffff:192.168.1.1]/",
+            "http://localhost:3000/",
+            "http://api.localhost/",
+            "http://metadata.google.internal/computeMetadata/v1/",
+        ] {
+            let error = fetch(WebFetchConfig::default(), url).await.unwrap_err();
+            assert!(matches!(error, WebFetchError::Blocked(_)), "{url}: {error}");
+        }
+
+        let error = fetch(WebFetchConfig::default(), "file:///etc/passwd")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, WebFetchError::InvalidRequest(_)), "{error}");
+
+        let config = WebFetchConfig {
+            allowed_hosts: vec!["*.example.com".into()],
+            blocked_hosts: vec!["private.example.com".into()],
+            ..WebFetchConfig::default()
+        };
+        for url in ["https://example.org/", "https://private.example.com/"] {
+            let error = fetch(config.clone(), url).await.unwrap_err();
+            assert!(matches!(error, WebFetchError::Blocked(_)), "{url}: {error}");
+        }
+    }
+
+    #[test]
+    fn host_patterns_match_exact_hosts_and_subdomains() {
+        assert!(host_matches("example.com", "example.com"));
+        assert!(host_matches("Example.COM", "example.com"));
+        assert!(!host_matches("example.com", "api.example.com"));
+        assert!(host_matches("*.example.com", "api.example.com"));
+        assert!(!host_matches("*.example.com", "example.com"));
+        assert!(!host_matches("*.example.com", "badexample.com"));
+
+        assert!(is_host_pattern("*.example.com"));
+        assert!(is_host_pattern("10.1.2.3"));
+        assert!(!is_host_pattern(""));
+        assert!(!is_host_pattern("https://example.com"));
+        assert!(!is_host_pattern("ex*ample.com"));
+    }
+
+    #[test]
+    fn only_public_addresses_are_public() {
+        for ip in ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "169.254.169.254",
+            "192.168.0.1",
+            "172.16.0.1",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::127.0.0.1",
+            "::a9fe:a9fe",
+            "64:ff9b::a9fe:a9fe",
+            "64:ff9b::10.0.0.1",
+            "2002:7f00:1::",
+            "2002:c0a8:101::1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+        // The same wrappers around a public address stay public.
+        for ip in ["64:ff9b::5db8:d822", "2002:5db8:d822::1"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn the_resolver_refuses_hosts_without_public_addresses() {
+        let error = public_addresses("localhost").await.unwrap_err();
+        let refused = error
+            .downcast_ref::<NonPublicHost>()
+            .expect("refused for its address");
+        assert!(!is_public(refused.ip), "{}", refused.ip);
+    }
+}
@@ -0,0 +1,286 @@
+//! Write file tool: atomically replace or append to a workspace file (task
+//! workers only).
+//!
+//! Content goes to a temporary file next to the target, which is flushed to
+//! disk and then renamed over it, so a write that's interrupted part way
+//! leaves the original file as it was. Appending works the same way, on a
+//! copy of the existing content. Paths are checked like `read_file`'s: they
+//! must stay inside the workspace and may not name a sensitive file.
+
+use crate::tools::shell::SensitiveFiles;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt as _;
+
+/// Tool for writing files in the agent's workspace.
+#[derive(Debug, Clone)]
+pub struct WriteFileTool {
+    workspace: PathBuf,
+    sensitive_files: SensitiveFiles,
+}
+
+impl WriteFileTool {
+    /// Create a write file tool restricted to the given workspace directory.
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            sensitive_files: SensitiveFiles::default(),
+        }
+    }
+
+    /// Use the shell tool's sensitive file patterns, so both refuse the same
+    /// files.
+    pub fn with_sensitive_files(mut self, sensitive_files: SensitiveFiles) -> Self {
+        self.sensitive_files = sensitive_files;
+        self
+    }
+}
+
+/// Error type for write file tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Write file failed: {0}")]
+pub struct WriteFileError(String);
+
+/// Arguments for write file tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WriteFileArgs {
+    /// The file path, relative to the workspace root.
+    pub path: String,
+    /// Content to write.
+    pub content: String,
+    /// Create missing parent directories.
+    #[serde(default)]
+    pub create_dirs: bool,
+    /// Add the content to the end of the file instead of replacing it.
+    #[serde(default)]
+    pub append: bool,
+}
+
+/// Output from write file tool.
+#[derive(Debug, Serialize)]
+pub struct WriteFileOutput {
+    /// The resolved file path.
+    pub path: String,
+    /// Bytes of content written by this call.
+    pub bytes_written: usize,
+    /// Whether the content was appended to an existing file.
+    pub appended: bool,
+}
+
+impl Tool for WriteFileTool {
+    const NAME: &'static str = "write_file";
+
+    type Error = WriteFileError;
+    type Args = WriteFileArgs;
+    type Output = WriteFileOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::tools::workspace_description("tools/write_file", &self.workspace),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "The file to write, relative to the workspace root."
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "The content to write."
+                    },
+                    "create_dirs": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Create missing parent directories."
+                    },
+                    "append": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Add the content to the end of the file instead of replacing it."
+                    }
+                },
+                "required": ["path", "content"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = crate::tools::resolve_workspace_file(
+            &self.workspace,
+            &args.path,
+            &self.sensitive_files,
+        )
+        .map_err(WriteFileError)?;
+        if path.is_dir() {
+            return Err(WriteFileError(format!("{} is a directory", args.path)));
+        }
+
+        let Some(parent) = path.parent() else {
+            return Err(WriteFileError(format!(
+                "{} has no parent directory",
+                args.path
+            )));
+        };
+        if !parent.is_dir() {
+            if !args.create_dirs {
+                return Err(WriteFileError(format!(
+                    "The directory for {} doesn't exist. Pass create_dirs: true to create it.",
+                    args.path
+                )));
+            }
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| WriteFileError(format!("Failed to create directory: {e}")))?;
+        }
+
+        let mut content = Vec::new();
+        if args.append {
+            match tokio::fs::read(&path).await {
+                Ok(existing) => content = existing,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => {
+                    return Err(WriteFileError(format!(
+                        "Failed to read {} to append to it: {error}",
+                        args.path
+                    )));
+                }
+            }
+        }
+        content.extend_from_slice(args.content.as_bytes());
+
+        replace_atomically(&path, &content)
+            .await
+            .map_err(|e| WriteFileError(format!("Failed to write {}: {e}", args.path)))?;
+
+        Ok(WriteFileOutput {
+            path: path.to_string_lossy().to_string(),
+            bytes_written: args.content.len(),
+            appended: args.append,
+        })
+    }
+}
+
+/// Replace the contents of `path` with `content` so that readers, and the
+/// file after a crash, only ever see the old or the new contents.
+pub(super) async fn replace_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let temp = stage(path, content).await?;
+    if let Err(error) = tokio::fs::rename(&temp, path).await {
+        let _ = tokio::fs::remove_file(&temp).await;
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Write `content` to a new temporary file next to `path` and flush it to
+/// disk, ready to be renamed over `path`. The file takes over `path`'s
+/// permissions if it exists. Removed again if anything fails.
+async fn stage(path: &Path, content: &[u8]) -> std::io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{name}.{}.tmp", uuid::Uuid::new_v4().simple()));
+
+    let result = async {
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp)
+            .await?;
+        file.write_all(content).await?;
+        file.sync_all().await?;
+        if let Ok(metadata) = tokio::fs::metadata(path).await {
+            tokio::fs::set_permissions(&temp, metadata.permissions()).await?;
+        }
+        Ok::<_, std::io::Error>(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => Ok(temp),
+        Err(error) => {
+            let _ = tokio::fs::remove_file(&temp).await;
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &str, content: &str) -> WriteFileArgs {
+        WriteFileArgs {
+            path: path.into(),
+            content: content.into(),
+            create_dirs: false,
+            append: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_appends_and_refuses_protected_paths() {
+        let workspace = tempfile::tempdir().expect("failed to create temp dir");
+        let tool = WriteFileTool::new(workspace.path().into());
+
+        let output = tool
+            .call(write("notes.txt", "first\n"))
+            .await
+            .expect("file is written");
+        assert_eq!(output.bytes_written, 6);
+        let output = tool
+            .call(WriteFileArgs {
+                append: true,
+                ..write("notes.txt", "second\n")
+            })
+            .await
+            .expect("file is appended to");
+        assert_eq!(output.bytes_written, 7);
+        assert_eq!(
+            std::fs::read_to_string(workspace.path().join("notes.txt")).expect("written"),
+            "first\nsecond\n"
+        );
+
+        assert!(tool.call(write("src/lib.rs", "")).await.is_err());
+        tool.call(WriteFileArgs {
+            create_dirs: true,
+            ..write("src/lib.rs", "")
+        })
+        .await
+        .expect("parent directories are created on request");
+
+        assert!(tool.call(write("config.toml", "x")).await.is_err());
+        assert!(tool.call(write("spacebot.db", "x")).await.is_err());
+        assert!(tool.call(write("../outside.txt", "x")).await.is_err());
+        // Only notes.txt and src/ were created; no temp files are left over.
+        let mut entries: Vec<String> = std::fs::read_dir(workspace.path())
+            .expect("workspace is readable")
+            .map(|entry| entry.expect("entry").file_name().to_string_lossy().into())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, ["notes.txt", "src"]);
+    }
+
+    #[tokio::test]
+    async fn crash_before_rename_leaves_the_original_intact() {
+        let workspace = tempfile::tempdir().expect("failed to create temp dir");
+        let target = workspace.path().join("main.rs");
+        std::fs::write(&target, "fn main() {}\n").expect("failed to write file");
+
+        // Stop after staging, as if the process died before the rename.
+        let temp = stage(&target, b"fn main() { half")
+            .await
+            .expect("content is staged");
+
+        assert_eq!(
+            std::fs::read_to_string(&target).expect("original is readable"),
+            "fn main() {}\n"
+        );
+        assert_ne!(temp, target);
+        assert_eq!(temp.parent(), target.parent());
+    }
+}